@@ -5,7 +5,7 @@ from uuid import uuid4
 from pathlib import Path
 from src.familiar import entities
 from src.familiar.dag import Dag
-from src.familiar.laws import Law, decay_law, resonance_law, binding_law
+from src.familiar.laws import Law, decay_law, resonance_law, binding_law, consolidation_law, bonding_law
 from src.familiar.schema import (
     BaseEntity, BaseComponent, Visibility, AccessScope, Cardinality, Metadata
 )
@@ -70,8 +70,8 @@ def _clean_schema_refs(schema):
                             "enum": enum_values[type_name],
                             "description": f"<enum '{type_name}'>"
                         }
-                # For other refs, just use string type
-                return {"type": "string", "format": "uuid" if "id" in ref.lower() else None}
+                # Other refs point at exported models - keep them so the hot path can resolve them
+                return {"$ref": f"#/models/{type_name}"}
         
         # Recursively clean nested objects
         return {k: _clean_schema_refs(v) for k, v in schema.items()}
@@ -136,6 +136,31 @@ def schema_dump(output_path: str = "assets/sample_schema.json"):
         print(f"   • {name}")
 
 
+@app.command()
+def laws_dump(output_path: str = "../assets/laws.json"):
+    """Export the physics laws (JSON, or YAML for .yml/.yaml) for the hot path to load."""
+    laws = [decay_law, resonance_law, binding_law, consolidation_law, bonding_law]
+
+    Path(output_path).parent.mkdir(parents=True, exist_ok=True)
+
+    # Stamped like the schema export so the hot path can refuse incompatible law files
+    output = {
+        "schema_version": "1.0.0",
+        "generated_by": "familiar-cold-path",
+        "laws": [law.model_dump() for law in laws]
+    }
+
+    with open(output_path, "w") as f:
+        if Path(output_path).suffix in (".yml", ".yaml"):
+            yaml.safe_dump(output, f, sort_keys=False)
+        else:
+            json.dump(output, f, indent=2)
+
+    print(f"✅ Physics laws exported to {output_path}")
+    for law in laws:
+        print(f"   • {law.name} ({law.trigger}) -> {', '.join(law.applies_to)}")
+
+
 @app.command()
 def schema_summary():
     """Show a summary of all available schemas."""
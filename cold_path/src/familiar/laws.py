@@ -18,7 +18,7 @@ class Law(BaseModel):
 decay_law = Law(
     name="decay",
     trigger="on_tick", 
-    applies_to=["filament", "motif"],
+    applies_to=["filament", "motif", "moment"],
     formula="strength = strength * pow(0.5, time_elapsed / half_life)",
     variables=["strength", "half_life", "last_update"],
     constants={},  # No universal constants needed
@@ -50,6 +50,40 @@ binding_law = Law(
     variables=["position", "affinity"],
     constants={
         "proximity_threshold": 5.0,   # Universal spatial binding distance
-        "affinity_threshold": 0.7     # Universal affinity requirement
+        "affinity_threshold": 0.7,    # Universal affinity requirement
+        "max_bindings_per_moment": 3  # Caps pairwise blow-up
     }
-) 
\ No newline at end of file
+) 
+
+# --- Consolidation Law: Decaying moments collapse into filaments and motifs ---
+# Fresh → Consolidating when the predicate holds; clusters on a thread become a filament,
+# clusters sharing a tag across threads become a motif
+consolidation_law = Law(
+    name="consolidation",
+    trigger="on_tick",
+    applies_to=["moment"],
+    formula="consolidate_if(strength < consolidation_threshold)",
+    variables=["strength"],
+    constants={
+        "consolidation_threshold": 0.3,  # Moments fading below this start consolidating
+        "min_cluster_size": 3,           # Moments needed to form a filament/motif
+        "interval_seconds": 60           # Consolidation cadence (daily → yearly in production)
+    }
+)
+
+# --- Bonding Law: Emergent bonds between repeatedly bound threads ---
+# Affinity grows with the number of shared bindings, strength follows their decay;
+# faded bonds are garbage-collected and each thread keeps only its strongest bonds
+bonding_law = Law(
+    name="bonding",
+    trigger="on_tick",
+    applies_to=["thread"],
+    formula="form_bond_if(shared_bindings >= min_shared_bindings)",
+    variables=["strength", "affinity"],
+    constants={
+        "min_shared_bindings": 2,     # Bindings needed before threads bond
+        "binding_saturation": 5.0,    # Shared bindings at which affinity reaches ~63%
+        "gc_strength": 0.15,          # Bonds fading below this are collected
+        "max_bonds_per_thread": 5     # Cap per thread (strongest kept)
+    }
+)
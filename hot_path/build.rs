@@ -1,275 +1,571 @@
 use std::path::Path;
+use serde_json::{Map, Value};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let schema_path = "../assets/sample_schema.json";
-    
+
     // Check if schema file exists
     if !Path::new(schema_path).exists() {
         panic!(
             "Schema file not found at {}. \
-            Please run: cd ../cold_path && python cli.py schema-dump", 
+            Please run: cd ../cold_path && python cli.py schema-dump",
             schema_path
         );
     }
-    
+
     println!("🔧 Generating types from schema: {}", schema_path);
-    
-    // Generate simple, direct Rust types
-    let generated_code = generate_rust_types()?;
-    
+
+    let schema: Value = serde_json::from_str(&std::fs::read_to_string(schema_path)?)?;
+
+    // Walk the exported models and emit matching Rust types
+    let generated_code = generate_rust_types(&schema)?;
+
     // Write to src/generated.rs
     let output_path = "src/generated.rs";
     std::fs::write(output_path, generated_code)?;
-    
+
     println!("✅ Generated types written to: {}", output_path);
-    
+
     // Tell cargo to rerun if schema changes
     println!("cargo:rerun-if-changed={}", schema_path);
-    
+    println!("cargo:rerun-if-changed=build.rs");
+
     Ok(())
 }
 
-fn generate_rust_types() -> Result<String, Box<dyn std::error::Error>> {
-    let code = r#"// Generated types for Familiar Engine Hot Path
-// DO NOT EDIT - regenerated on every build
-
-use serde::{Serialize, Deserialize};
-use uuid::Uuid;
-use chrono::{DateTime, Utc};
-
-// Core enums from cold path
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum AccessScope {
-    View,
-    Edit,
-    Admin,
+/// Enum collected from the schema, either a top-level model or an inline `enum` property
+struct EnumDef {
+    name: String,
+    values: Vec<String>, // Wire values as serialized by the cold path
+    needs_default: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Visibility {
-    Private,
-    Household,
-    Org,
-    Public,
+/// Untagged union for `anyOf` fields mixing several scalar types (e.g. Metadata.value)
+struct UnionDef {
+    name: String,
+    variants: Vec<(&'static str, String)>, // (variant name, Rust type)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum Cardinality {
-    Actor,
-    Observer,
-    Recipient,
-    CoParticipant,
-    Target,
+/// Collects enums up front so struct fields can refer to them by name
+struct Generator<'a> {
+    models: &'a Map<String, Value>,
+    enums: Vec<EnumDef>,
+    unions: Vec<UnionDef>,
+    entity_models: Vec<String>,
+    default_fns: Vec<String>,
 }
 
-// Base entity with versioning and soft deletion
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct BaseEntity {
-    #[serde(default)]
-    pub id: Option<Uuid>,
-    pub org_id: Uuid,
-    pub owner_id: Uuid,
-    #[serde(default)]
-    pub created_at: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub updated_at: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub deleted_at: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub tags: Vec<String>,
-    #[serde(default)]
-    pub component_ids: Vec<Uuid>,
-    #[serde(default)]
-    pub sub_type: Option<String>,
-    #[serde(default)]
-    pub visibility: Visibility,
-    #[serde(default)]
-    pub security_level: i64,
-    #[serde(default)]
-    pub access_scope: Vec<AccessScope>,
-    #[serde(default = "default_version")]
-    pub version: i64,
-    #[serde(default)]
-    pub parent_version: Option<i64>,
-}
+fn generate_rust_types(schema: &Value) -> Result<String, Box<dyn std::error::Error>> {
+    let models = schema
+        .get("models")
+        .and_then(Value::as_object)
+        .ok_or("schema has no `models` map")?;
 
-// Domain entities from cold path
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Moment {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
-    
-    // Moment-specific fields
-    pub thread_id: Uuid,
-    pub author_id: Uuid,
-    #[serde(default)]
-    pub binding_hint: Option<i64>,
-    #[serde(default)]
-    pub binding_id: Option<Uuid>,
-    #[serde(default)]
-    pub cardinality: Option<Cardinality>,
-}
+    let mut generator = Generator {
+        models,
+        enums: Vec::new(),
+        unions: Vec::new(),
+        entity_models: Vec::new(),
+        default_fns: Vec::new(),
+    };
+    generator.collect_enums();
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct BindingPoint {
-    pub thread_id: Uuid,
-    pub moment_id: Uuid,
-    pub cardinality: Cardinality,
-}
+    // Structs are emitted first so enum defaults are known before enums are written
+    let mut structs = String::new();
+    for (name, model) in models {
+        if model.get("enum").is_some() {
+            continue;
+        }
+        if let Some(error) = model.get("error") {
+            println!("cargo:warning=Skipping model {} (export failed: {})", name, error);
+            continue;
+        }
+        if generator.is_alias(name, model) {
+            // Law instances (DecayLaw, ResonanceLaw, ...) export the `Law` schema under their own key
+            continue;
+        }
+        structs.push_str(&generator.emit_struct(name, model)?);
+    }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Binding {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
-    
-    // Binding-specific fields
-    #[serde(default)]
-    pub points: Vec<BindingPoint>,
-    #[serde(default)]
-    pub thread_ids: Vec<Uuid>, // Query optimization index
-}
+    let schema_version = schema.get("schema_version").and_then(Value::as_str).unwrap_or("0.0.0");
+    let generated_by = schema.get("generated_by").and_then(Value::as_str).unwrap_or("unknown");
+    let schema_hash = fnv1a_hex(serde_json::to_string(models)?.as_bytes());
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Thread {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
-}
+    let mut code = String::new();
+    code.push_str("// Generated types for Familiar Engine Hot Path\n");
+    code.push_str("// DO NOT EDIT - regenerated on every build from assets/sample_schema.json\n\n");
+    code.push_str("use serde::{Serialize, Deserialize};\n");
+    code.push_str("use async_graphql::{Enum, InputObject, SimpleObject};\n");
+    code.push_str("use uuid::Uuid;\n");
+    code.push_str("use chrono::{DateTime, Utc};\n\n");
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Bond {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
-    
-    // Bond-specific fields
-    pub affinity_score: f64,
-    pub bond_strength: f64,
-    #[serde(default)]
-    pub component_context: Vec<Uuid>,
-    #[serde(default)]
-    pub thread_ids: Vec<Uuid>,
-}
+    code.push_str("// Schema identity - checked when loading persisted data and law files\n");
+    code.push_str(&format!("pub const SCHEMA_VERSION: &str = {:?};\n", schema_version));
+    code.push_str(&format!("pub const SCHEMA_GENERATED_BY: &str = {:?};\n", generated_by));
+    code.push_str(&format!("pub const SCHEMA_HASH: &str = {:?};\n\n", schema_hash));
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Filament {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
-}
+    code.push_str("// Enums from cold path\n");
+    for def in &generator.enums {
+        code.push_str(&emit_enum(def));
+    }
+
+    for def in &generator.unions {
+        code.push_str(&emit_union(def));
+    }
+
+    code.push_str("// Models from cold path\n");
+    code.push_str(&structs);
+
+    if !generator.entity_models.is_empty() {
+        code.push_str(&emit_schema_entity_impls(&generator.entity_models));
+    }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Motif {
-    // BaseEntity fields (flattened)
-    #[serde(flatten)]
-    pub base: BaseEntity,
+    if !generator.default_fns.is_empty() {
+        code.push_str("// Serde defaults declared in the schema\n");
+        for default_fn in &generator.default_fns {
+            code.push_str(default_fn);
+        }
+    }
+
+    Ok(code.trim_end().to_string() + "\n")
 }
 
-// Law system from cold path - with mathematical specifications
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Law {
-    pub name: String,  // e.g. 'decay', 'resonance', 'binding'
-    pub trigger: LawTrigger,
-    pub applies_to: Vec<String>,  // e.g. ['filament', 'moment']
-    
-    // Mathematical specification - the "physics" of this law
-    pub formula: String,  // Mathematical expression from cold path
-    pub variables: Vec<String>,  // Component fields this law reads/writes
-    pub constants: serde_json::Map<String, serde_json::Value>,  // Universal constants
-    #[serde(default)]
-    pub constraints: Option<serde_json::Map<String, serde_json::Value>>,  // Min/max bounds
+impl<'a> Generator<'a> {
+    fn collect_enums(&mut self) {
+        let models = self.models;
+        let enums = &mut self.enums;
+
+        // Top-level enum models export member names (e.g. CO_PARTICIPANT)
+        for (name, model) in models {
+            if let Some(members) = model.get("enum").and_then(Value::as_array) {
+                let values = members
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_lowercase)
+                    .collect();
+                enums.push(EnumDef { name: name.clone(), values, needs_default: false });
+            }
+        }
+
+        // Inline refs carry the real wire values, tagged with "<enum 'Name'>"
+        for model in models.values().filter(|model| model.get("enum").is_none()) {
+            visit_schemas(model, &mut |node| {
+                let (Some(name), Some(values)) = (described_enum_name(node), enum_values(node)) else {
+                    return;
+                };
+                match enums.iter_mut().find(|def| def.name == name) {
+                    Some(def) => def.values = values,
+                    None => enums.push(EnumDef { name, values, needs_default: false }),
+                }
+            });
+        }
+
+        // Anonymous inline enums (Literal[...] fields) get named after their model and field
+        for (model_name, model) in models {
+            if self.is_alias(model_name, model) {
+                continue;
+            }
+            let Some(properties) = model.get("properties").and_then(Value::as_object) else {
+                continue;
+            };
+            for (field, prop) in properties {
+                visit_schemas(prop, &mut |node| {
+                    if described_enum_name(node).is_some() {
+                        return;
+                    }
+                    let Some(values) = enum_values(node) else {
+                        return;
+                    };
+                    if !self.enums.iter().any(|def| def.values == values) {
+                        let title = node.get("title").and_then(Value::as_str).unwrap_or(field);
+                        let name = format!("{}{}", model_name, to_pascal_case(title));
+                        self.enums.push(EnumDef { name, values, needs_default: false });
+                    }
+                });
+            }
+        }
+    }
+
+    fn enum_for_values(&self, values: &[String]) -> Option<&EnumDef> {
+        self.enums.iter().find(|def| def.values == values)
+    }
+
+    fn is_alias(&self, name: &str, model: &Value) -> bool {
+        match model.get("title").and_then(Value::as_str) {
+            Some(title) => title != name && self.models.contains_key(title),
+            None => false,
+        }
+    }
+
+    /// Field names every BaseEntity subclass inherits
+    fn base_entity_fields(&self) -> Vec<&'a str> {
+        self.models
+            .get("BaseEntity")
+            .and_then(|model| model.get("properties"))
+            .and_then(Value::as_object)
+            .map(|properties| properties.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    fn emit_struct(&mut self, name: &str, model: &Value) -> Result<String, Box<dyn std::error::Error>> {
+        let properties = model
+            .get("properties")
+            .and_then(Value::as_object)
+            .ok_or_else(|| format!("model {} has no properties", name))?;
+        let required: Vec<&str> = model
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let base_fields = self.base_entity_fields();
+        let extends_base = name != "BaseEntity"
+            && !base_fields.is_empty()
+            && base_fields.iter().all(|field| properties.contains_key(*field));
+
+        let mut code = String::new();
+        if let Some(description) = model.get("description").and_then(Value::as_str) {
+            code.push_str(&format!("/// {}\n", description));
+        }
+        code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]\n");
+        if extends_base {
+            // The API serves entities as typed objects under the model's own name (graphql_types.rs)
+            code.push_str(&format!("#[graphql(name = \"{0}Record\", input_name = \"{0}RecordInput\")]\n", name));
+        } else {
+            code.push_str(&format!("#[graphql(input_name = \"{}Input\")]\n", name));
+        }
+        code.push_str(&format!("pub struct {} {{\n", name));
+
+        if extends_base {
+            self.entity_models.push(name.to_string());
+            code.push_str("    // BaseEntity fields (flattened)\n");
+            code.push_str("    #[serde(flatten)]\n");
+            code.push_str("    #[graphql(flatten)]\n");
+            code.push_str("    pub base: BaseEntity,\n");
+        }
+
+        for (field, prop) in properties {
+            if extends_base && base_fields.contains(&field.as_str()) {
+                // Subclasses only narrow inherited fields (e.g. sub_type: Literal['moment'])
+                continue;
+            }
+            let is_required = required.contains(&field.as_str());
+            let (ty, attr) = self.field_type(name, field, prop, is_required)?;
+            if let Some(attr) = attr {
+                code.push_str(&format!("    {}\n", attr));
+            }
+            if ty.contains("serde_json::Map") {
+                // Free-form objects have no GraphQL type; they stay out of the API
+                code.push_str("    #[graphql(skip)]\n");
+            }
+            code.push_str(&format!("    pub {}: {},\n", field_ident(field), ty));
+        }
+
+        code.push_str("}\n\n");
+        Ok(code)
+    }
+
+    /// Resolve a field's Rust type and serde attribute from required-ness and declared default
+    fn field_type(
+        &mut self,
+        model: &str,
+        field: &str,
+        prop: &Value,
+        is_required: bool,
+    ) -> Result<(String, Option<String>), Box<dyn std::error::Error>> {
+        let ty = self.rust_type(prop, &format!("{}{}", model, to_pascal_case(field)))?;
+        if is_required {
+            return Ok((ty, None));
+        }
+
+        let serde_default = "#[serde(default)]".to_string();
+        match prop.get("default") {
+            Some(Value::Null) => Ok((wrap_option(ty), Some(serde_default))),
+            Some(default) => {
+                if is_zero_value(default) {
+                    Ok((ty, Some(serde_default)))
+                } else {
+                    let fn_name = format!("default_{}_{}", to_snake_case(model), field);
+                    let literal = self.default_literal(&ty, default)?;
+                    self.default_fns.push(format!(
+                        "fn {}() -> {} {{\n    {}\n}}\n\n",
+                        fn_name, ty, literal
+                    ));
+                    Ok((ty, Some(format!("#[serde(default = \"{}\")]", fn_name))))
+                }
+            }
+            None if ty.starts_with("Vec<") || ty.starts_with("Option<") => Ok((ty, Some(serde_default))),
+            None => {
+                // Pydantic drops enum defaults when inlining refs; fall back to the first variant
+                if let Some(def) = self.enums.iter_mut().find(|def| def.name == ty) {
+                    def.needs_default = true;
+                    Ok((ty, Some(serde_default)))
+                } else {
+                    // default_factory fields (id, created_at) are absent from the export
+                    Ok((wrap_option(ty), Some(serde_default)))
+                }
+            }
+        }
+    }
+
+    /// `hint` names any union type that has to be generated for this property
+    fn rust_type(&mut self, prop: &Value, hint: &str) -> Result<String, Box<dyn std::error::Error>> {
+        if let Some(reference) = prop.get("$ref").and_then(Value::as_str) {
+            let target = reference.rsplit('/').next().unwrap_or(reference);
+            if !self.models.contains_key(target) {
+                return Err(format!("unresolved $ref {}", reference).into());
+            }
+            return Ok(target.to_string());
+        }
+
+        if let Some(variants) = prop.get("anyOf").and_then(Value::as_array) {
+            let non_null: Vec<&Value> = variants
+                .iter()
+                .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+                .collect();
+            let nullable = non_null.len() < variants.len();
+            let inner = match non_null.as_slice() {
+                [single] => self.rust_type(single, hint)?,
+                many => self.union_type(hint, many)?,
+            };
+            return Ok(if nullable { wrap_option(inner) } else { inner });
+        }
+
+        if let Some(values) = enum_values(prop) {
+            let def = match described_enum_name(prop) {
+                Some(name) => self.enums.iter().find(|def| def.name == name),
+                None => self.enum_for_values(&values),
+            };
+            return def
+                .map(|def| def.name.clone())
+                .ok_or_else(|| format!("no enum collected for values {:?}", values).into());
+        }
+
+        let ty = match prop.get("type").and_then(Value::as_str) {
+            Some("string") => match prop.get("format").and_then(Value::as_str) {
+                Some("uuid") => "Uuid".to_string(),
+                Some("date-time") => "DateTime<Utc>".to_string(),
+                _ => "String".to_string(),
+            },
+            Some("integer") => "i64".to_string(),
+            Some("number") => "f64".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => {
+                let items = prop.get("items").ok_or("array property without items")?;
+                format!("Vec<{}>", self.rust_type(items, hint)?)
+            }
+            Some("object") => "serde_json::Map<String, serde_json::Value>".to_string(),
+            _ => "serde_json::Value".to_string(),
+        };
+        Ok(ty)
+    }
+
+    fn union_type(&mut self, name: &str, members: &[&Value]) -> Result<String, Box<dyn std::error::Error>> {
+        let mut variants = Vec::new();
+        for member in members {
+            let ty = self.rust_type(member, name)?;
+            let variant = match ty.as_str() {
+                "bool" => "Boolean",
+                "i64" => "Integer",
+                "f64" => "Number",
+                "DateTime<Utc>" => "DateTime",
+                "Uuid" => "Uuid",
+                "String" => "String",
+                // Nested objects and arrays are carried as raw JSON
+                _ => return Ok("serde_json::Value".to_string()),
+            };
+            variants.push((variant, ty));
+        }
+
+        // Untagged variants are tried in order, so the most specific types go first
+        const PRECEDENCE: [&str; 6] = ["Boolean", "Integer", "Number", "DateTime", "Uuid", "String"];
+        variants.sort_by_key(|(variant, _)| PRECEDENCE.iter().position(|p| p == variant));
+        variants.dedup_by_key(|(variant, _)| *variant);
+
+        if !self.unions.iter().any(|def| def.name == name) {
+            self.unions.push(UnionDef { name: name.to_string(), variants });
+        }
+        Ok(name.to_string())
+    }
+
+    fn default_literal(&self, ty: &str, default: &Value) -> Result<String, Box<dyn std::error::Error>> {
+        if let Some(def) = self.enums.iter().find(|def| def.name == ty) {
+            let value = default.as_str().map(str::to_lowercase).unwrap_or_default();
+            if def.values.contains(&value) {
+                return Ok(format!("{}::{}", def.name, to_pascal_case(&value)));
+            }
+            return Err(format!("default {} is not a variant of {}", default, ty).into());
+        }
+        let literal = match default {
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) if ty == "f64" => format!("{:?}", n.as_f64().unwrap_or_default()),
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => format!("{:?}.to_string()", s),
+            other => return Err(format!("unsupported default {} for {}", other, ty).into()),
+        };
+        Ok(literal)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum LawTrigger {
-    OnObservation,
-    OnTick,
-    OnAffinityMatch,
+fn emit_enum(def: &EnumDef) -> String {
+    let variants: Vec<String> = def.values.iter().map(|value| to_pascal_case(value)).collect();
+
+    let rename_all = if variants.iter().zip(&def.values).all(|(v, value)| to_snake_case(v) == *value) {
+        Some("snake_case")
+    } else if variants.iter().zip(&def.values).all(|(v, value)| to_snake_case(v).replace('_', "-") == *value) {
+        Some("kebab-case")
+    } else {
+        None
+    };
+
+    let mut code = String::new();
+    code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum");
+    code.push_str(if def.needs_default { ", Default)]\n" } else { ")]\n" });
+    if let Some(rule) = rename_all {
+        code.push_str(&format!("#[serde(rename_all = \"{}\")]\n", rule));
+    }
+    code.push_str(&format!("pub enum {} {{\n", def.name));
+    for (index, (variant, value)) in variants.iter().zip(&def.values).enumerate() {
+        if def.needs_default && index == 0 {
+            code.push_str("    #[default]\n");
+        }
+        if rename_all.is_none() {
+            code.push_str(&format!("    #[serde(rename = \"{}\")]\n", value));
+        }
+        code.push_str(&format!("    {},\n", variant));
+    }
+    code.push_str("}\n\n");
+    code
 }
 
-// Helper functions
-fn default_version() -> i64 {
-    1
+fn emit_union(def: &UnionDef) -> String {
+    let mut code = String::new();
+    code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    code.push_str("#[serde(untagged)]\n");
+    code.push_str(&format!("pub enum {} {{\n", def.name));
+    for (variant, ty) in &def.variants {
+        code.push_str(&format!("    {}({}),\n", variant, ty));
+    }
+    code.push_str("}\n\n");
+    // Mixed scalar types have no GraphQL union; carried as a JSON scalar
+    code.push_str(&format!("async_graphql::scalar!({});\n\n", def.name));
+    code
 }
 
-impl Default for Visibility {
-    fn default() -> Self {
-        Self::Private
+fn emit_schema_entity_impls(entity_models: &[String]) -> String {
+    let mut code = String::new();
+    let names: Vec<String> = entity_models.iter().map(|name| format!("{:?}", name)).collect();
+    code.push_str("/// Models that flatten BaseEntity\n");
+    code.push_str(&format!("pub const SCHEMA_ENTITY_MODELS: &[&str] = &[{}];\n\n", names.join(", ")));
+    code.push_str("/// Implemented by every model that flattens BaseEntity\n");
+    code.push_str("pub trait SchemaEntity {\n");
+    code.push_str("    fn base(&self) -> &BaseEntity;\n");
+    code.push_str("    fn base_mut(&mut self) -> &mut BaseEntity;\n");
+    code.push_str("}\n\n");
+    for name in entity_models {
+        code.push_str(&format!("impl SchemaEntity for {} {{\n", name));
+        code.push_str("    fn base(&self) -> &BaseEntity {\n        &self.base\n    }\n\n");
+        code.push_str("    fn base_mut(&mut self) -> &mut BaseEntity {\n        &mut self.base\n    }\n");
+        code.push_str("}\n\n");
     }
+    code
 }
 
-// Implementations for generated types
-impl Moment {
-    /// Check if this moment was created recently
-    pub fn is_recent(&self) -> bool {
-        if let Some(created_at) = &self.base.created_at {
-            let now = chrono::Utc::now();
-            let duration = now.signed_duration_since(*created_at);
-            duration.num_minutes() < 30 // Recent if created within 30 minutes
-        } else {
-            false
+/// Depth-first walk over a property schema and its `anyOf`/`items` children
+fn visit_schemas(node: &Value, visit: &mut dyn FnMut(&Value)) {
+    visit(node);
+    if let Some(variants) = node.get("anyOf").and_then(Value::as_array) {
+        for variant in variants {
+            visit_schemas(variant, visit);
         }
     }
-    
-    /// Get the thread UUID (guaranteed present in Moment)
-    pub fn thread_uuid(&self) -> Uuid {
-        self.thread_id
+    if let Some(items) = node.get("items") {
+        visit_schemas(items, visit);
     }
-    
-    /// Get the author UUID (guaranteed present in Moment)
-    pub fn author_uuid(&self) -> Uuid {
-        self.author_id
+    if let Some(properties) = node.get("properties").and_then(Value::as_object) {
+        for prop in properties.values() {
+            visit_schemas(prop, visit);
+        }
+    }
+}
+
+fn enum_values(node: &Value) -> Option<Vec<String>> {
+    node.get("enum")
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+}
+
+/// Name from the cold path's "<enum 'Name'>" marker on inlined enum refs
+fn described_enum_name(node: &Value) -> Option<String> {
+    let description = node.get("description")?.as_str()?;
+    let name = description.strip_prefix("<enum '")?.strip_suffix("'>")?;
+    Some(name.to_string())
+}
+
+/// FNV-1a over the compact models JSON, so whitespace-only re-exports keep the same hash
+fn fnv1a_hex(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
     }
+    format!("{:016x}", hash)
 }
 
-impl Binding {
-    /// Get all unique thread IDs from binding points
-    pub fn get_thread_ids(&self) -> Vec<Uuid> {
-        let mut thread_ids: Vec<Uuid> = self.points
-            .iter()
-            .map(|point| point.thread_id)
-            .collect();
-        thread_ids.sort();
-        thread_ids.dedup();
-        thread_ids
+fn is_zero_value(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => !b,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        Value::Null => true,
     }
-    
-    /// Get all unique moment IDs from binding points  
-    pub fn get_moment_ids(&self) -> Vec<Uuid> {
-        let mut moment_ids: Vec<Uuid> = self.points
-            .iter()
-            .map(|point| point.moment_id)
-            .collect();
-        moment_ids.sort();
-        moment_ids.dedup();
-        moment_ids
+}
+
+fn wrap_option(ty: String) -> String {
+    if ty.starts_with("Option<") {
+        ty
+    } else {
+        format!("Option<{}>", ty)
     }
-    
-    /// Check if this binding involves a specific thread
-    pub fn involves_thread(&self, thread_id: Uuid) -> bool {
-        // Check both the semantic points and the optimization index
-        self.points.iter().any(|point| point.thread_id == thread_id) ||
-        self.thread_ids.contains(&thread_id)
+}
+
+fn field_ident(field: &str) -> String {
+    const KEYWORDS: &[&str] = &["type", "ref", "match", "move", "impl", "fn", "mod", "use", "where", "loop"];
+    if KEYWORDS.contains(&field) {
+        format!("r#{}", field)
+    } else {
+        field.to_string()
     }
 }
 
-impl BindingPoint {
-    /// Create a new binding point
-    pub fn new(thread_id: Uuid, moment_id: Uuid, cardinality: Cardinality) -> Self {
-        Self {
-            thread_id,
-            moment_id,
-            cardinality,
+fn to_pascal_case(value: &str) -> String {
+    value
+        .split(['_', '-', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let lower = part.to_lowercase();
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn to_snake_case(value: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in value.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
         }
     }
+    out
 }
-"#;
-    
-    Ok(code.to_string())
-} 
\ No newline at end of file
@@ -0,0 +1,271 @@
+// Binding system - the `binding` law (`create_binding_if(distance < ... and affinity > ...)`).
+// Scores every pair of one owner's live moments on different threads and spawns a Binding
+// entity, with one BindingPoint per moment, for each pair the law's predicate accepts. Bindings
+// are private to that owner, so moments of different owners never bind.
+
+use chrono::{DateTime, Utc};
+use hecs::{Entity, World};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::components::{
+    AccessScope, Affinity, BaseEntity, Binding, BindingPoint, Cardinality, DecayComponent, DisplayText,
+    EntityType, Moment, TemporalPosition, Thread, ThreadId, Visibility,
+};
+use crate::changes::ChangeSet;
+use crate::index::EntityIndex;
+use crate::laws::CompiledLaw;
+
+/// Used when the law doesn't declare a `max_bindings_per_moment` constant
+pub const DEFAULT_MAX_BINDINGS_PER_MOMENT: usize = 3;
+
+/// Weights of the affinity signals; they sum to 1.0
+const TAG_WEIGHT: f64 = 0.4;
+const AUTHOR_WEIGHT: f64 = 0.3;
+const TEMPORAL_WEIGHT: f64 = 0.3;
+
+/// Tags every moment carries, so they say nothing about how two moments relate
+const TYPE_TAGS: [&str; 1] = ["moment"];
+
+/// What the binding system needs to know about one moment
+#[derive(Debug, Clone)]
+pub struct MomentSnapshot {
+    pub entity: Entity,
+    pub id: Uuid,
+    pub thread_id: Uuid,
+    pub thread_key: String,
+    pub author_id: Uuid,
+    pub org_id: Uuid,
+    pub owner_id: Uuid,
+    pub tags: HashSet<String>,
+    pub timestamp: DateTime<Utc>,
+    pub cardinality: Option<Cardinality>,
+}
+
+/// Temporal distance between two moments, in minutes (the law's `distance`)
+pub fn temporal_distance(a: &MomentSnapshot, b: &MomentSnapshot) -> f64 {
+    (a.timestamp - b.timestamp).num_milliseconds().abs() as f64 / 60_000.0
+}
+
+/// Pairwise affinity in [0, 1] from shared tags, shared author and temporal proximity.
+/// When neither moment has a tag of its own the tag signal is left out and the other two
+/// carry the whole weight, so untagged moments can still reach the law's threshold.
+pub fn moment_affinity(a: &MomentSnapshot, b: &MomentSnapshot, proximity: f64) -> f64 {
+    let same_author = if a.author_id == b.author_id { 1.0 } else { 0.0 };
+    let closeness = if proximity > 0.0 {
+        (-temporal_distance(a, b) / proximity).exp()
+    } else {
+        0.0
+    };
+    let score = AUTHOR_WEIGHT * same_author + TEMPORAL_WEIGHT * closeness;
+
+    let union = a.tags.union(&b.tags).count();
+    if union == 0 {
+        return score / (AUTHOR_WEIGHT + TEMPORAL_WEIGHT);
+    }
+    let shared_tags = a.tags.intersection(&b.tags).count() as f64 / union as f64;
+    score + TAG_WEIGHT * shared_tags
+}
+
+/// Roles of the two points: a declared moment cardinality wins, otherwise the earlier
+/// moment is the actor and the later one the recipient (co-participants when simultaneous)
+pub fn binding_cardinalities(a: &MomentSnapshot, b: &MomentSnapshot) -> (Cardinality, Cardinality) {
+    let (default_a, default_b) = match a.timestamp.cmp(&b.timestamp) {
+        std::cmp::Ordering::Less => (Cardinality::Actor, Cardinality::Recipient),
+        std::cmp::Ordering::Greater => (Cardinality::Recipient, Cardinality::Actor),
+        std::cmp::Ordering::Equal => (Cardinality::CoParticipant, Cardinality::CoParticipant),
+    };
+    (a.cardinality.unwrap_or(default_a), b.cardinality.unwrap_or(default_b))
+}
+
+/// Binding System - spawns Binding entities for moment pairs accepted by the binding law.
+/// Returns the number of bindings created.
+pub fn binding_system(
+    world: &mut World,
+    index: &mut EntityIndex,
+    law: &CompiledLaw,
+    current_time: f64,
+    changes: &mut ChangeSet,
+) -> usize {
+    let proximity = law.constant("proximity_threshold").unwrap_or(f64::INFINITY);
+    let max_per_moment = law
+        .constant("max_bindings_per_moment")
+        .map(|max| max.max(0.0) as usize)
+        .unwrap_or(DEFAULT_MAX_BINDINGS_PER_MOMENT);
+
+    let mut moments = collect_moments(world, law);
+    moments.sort_by_key(|moment| moment.timestamp);
+
+    // Existing bindings count towards the cap and are never duplicated
+    let mut bound_pairs: HashSet<(Uuid, Uuid)> = HashSet::new();
+    let mut binding_counts: HashMap<Uuid, usize> = HashMap::new();
+    for (_entity, binding) in world.query::<&Binding>().iter() {
+        let moment_ids = binding.get_moment_ids();
+        for id in &moment_ids {
+            *binding_counts.entry(*id).or_insert(0) += 1;
+        }
+        if let [a, b] = moment_ids.as_slice() {
+            bound_pairs.insert(ordered_pair(*a, *b));
+        }
+    }
+
+    let mut accepted = Vec::new();
+    for (i, a) in moments.iter().enumerate() {
+        for b in &moments[i + 1..] {
+            let distance = temporal_distance(a, b);
+            // Sorted by time: every later moment is even further away
+            if distance >= proximity {
+                break;
+            }
+            // Orgs and owners are hard boundaries: a binding is private to its owner and names
+            // both moments, so it only ever joins one owner's moments
+            if a.org_id != b.org_id
+                || a.owner_id != b.owner_id
+                || a.thread_key == b.thread_key
+                || bound_pairs.contains(&ordered_pair(a.id, b.id))
+            {
+                continue;
+            }
+            let at_cap = |id: &Uuid| binding_counts.get(id).copied().unwrap_or(0) >= max_per_moment;
+            if at_cap(&a.id) || at_cap(&b.id) {
+                continue;
+            }
+
+            let affinity = moment_affinity(a, b, proximity);
+            let mut scope = law.scope();
+            scope.set("distance", distance).set("affinity", affinity);
+            if !law.formula.evaluate(&scope).is_ok_and(|result| result != 0.0) {
+                continue;
+            }
+
+            bound_pairs.insert(ordered_pair(a.id, b.id));
+            *binding_counts.entry(a.id).or_insert(0) += 1;
+            *binding_counts.entry(b.id).or_insert(0) += 1;
+            accepted.push((a.clone(), b.clone(), affinity));
+        }
+    }
+
+    let created = accepted.len();
+    for (a, b, affinity) in accepted {
+        spawn_binding(world, index, &a, &b, affinity, current_time, changes);
+    }
+    created
+}
+
+fn ordered_pair(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+fn collect_moments(world: &World, law: &CompiledLaw) -> Vec<MomentSnapshot> {
+    // Moments address threads by name; resolve names to thread UUIDs for binding points
+    let thread_uuids: HashMap<String, Uuid> = world
+        .query::<(&Thread, &ThreadId)>()
+        .iter()
+        .filter_map(|(_, (thread, thread_id))| thread.base.id.map(|id| (thread_id.0.clone(), id)))
+        .collect();
+
+    let mut moments = Vec::new();
+    for (entity, (moment, entity_type, thread_id, position)) in world
+        .query::<(&Moment, &EntityType, Option<&ThreadId>, Option<&TemporalPosition>)>()
+        .iter()
+    {
+        if !law.applies_to(&entity_type.0) || moment.base.deleted_at.is_some() {
+            continue;
+        }
+        let Some(id) = moment.base.id else {
+            continue;
+        };
+        let Some(timestamp) = position.map(|p| p.timestamp).or(moment.base.created_at) else {
+            continue;
+        };
+
+        let thread_key = thread_id.map(|t| t.0.clone()).unwrap_or_else(|| moment.thread_id.to_string());
+        let thread_uuid = if moment.thread_id.is_nil() {
+            thread_uuids.get(&thread_key).copied().unwrap_or_default()
+        } else {
+            moment.thread_id
+        };
+
+        moments.push(MomentSnapshot {
+            entity,
+            id,
+            thread_id: thread_uuid,
+            thread_key,
+            author_id: moment.author_id,
+            org_id: moment.base.org_id,
+            owner_id: moment.base.owner_id,
+            tags: moment
+                .base
+                .tags
+                .iter()
+                .filter(|tag| !TYPE_TAGS.contains(&tag.as_str()))
+                .cloned()
+                .collect(),
+            timestamp,
+            cardinality: moment.cardinality,
+        });
+    }
+    moments
+}
+
+fn spawn_binding(
+    world: &mut World,
+    index: &mut EntityIndex,
+    a: &MomentSnapshot,
+    b: &MomentSnapshot,
+    affinity: f64,
+    current_time: f64,
+    changes: &mut ChangeSet,
+) {
+    let (cardinality_a, cardinality_b) = binding_cardinalities(a, b);
+    let points = vec![
+        BindingPoint::new(a.thread_id, a.id, cardinality_a),
+        BindingPoint::new(b.thread_id, b.id, cardinality_b),
+    ];
+    let binding_id = Uuid::new_v4();
+    let mut binding = Binding {
+        base: BaseEntity {
+            id: Some(binding_id),
+            org_id: a.org_id,
+            owner_id: a.owner_id,
+            created_at: Some(Utc::now()),
+            updated_at: None,
+            deleted_at: None,
+            tags: vec!["binding".to_string(), "binding-law".to_string()],
+            component_ids: vec![],
+            sub_type: Some("binding".to_string()),
+            visibility: Visibility::Private,
+            security_level: 0,
+            access_scope: vec![AccessScope::View],
+            version: 1,
+            parent_version: None,
+        },
+        points,
+        thread_ids: vec![],
+    };
+    binding.thread_ids = binding.get_thread_ids();
+
+    let entity = index.spawn(world, (
+        binding,
+        DisplayText(format!("Binding {} ⟷ {}", a.thread_key, b.thread_key)),
+        EntityType("binding".to_string()),
+        Affinity(affinity),
+        DecayComponent {
+            strength: affinity as f32,
+            half_life: 60.0,
+            last_update: current_time,
+        },
+    ));
+    changes.spawned(world, index, entity);
+
+    // Moments point at their first binding
+    for snapshot in [a, b] {
+        if let Ok(mut moment) = world.get::<&mut Moment>(snapshot.entity) {
+            if moment.binding_id.is_none() {
+                moment.binding_id = Some(binding_id);
+                moment.base.record_update();
+                changes.mutated(index, snapshot.entity, "Moment", "binding_id", None::<Uuid>, Some(binding_id));
+            }
+        }
+    }
+}
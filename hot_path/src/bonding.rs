@@ -0,0 +1,303 @@
+// Bonding system - the `bonding` law (`form_bond_if(shared_bindings >= ...)`).
+// Threads whose moments keep getting bound together grow a Bond; its affinity follows the
+// number of shared bindings and its strength their decay. Bonds are updated in place,
+// garbage-collected once they fade and capped per thread (strongest kept). Only bonds the law
+// formed itself (tagged `bonding-law`) are collected or count against the cap - bonds created
+// through the API are the user's, and soft-deleted bonds stay in the world as tombstones.
+
+use chrono::Utc;
+use hecs::{Entity, World};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::components::{
+    AccessScope, BaseEntity, Binding, Bond, DecayComponent, DisplayText, EntityType, Thread, ThreadId, Visibility,
+};
+use crate::changes::ChangeSet;
+use crate::index::EntityIndex;
+use crate::laws::CompiledLaw;
+
+/// Used when the law doesn't declare the matching constants
+pub const DEFAULT_MAX_BONDS_PER_THREAD: usize = 5;
+pub const DEFAULT_BINDING_SATURATION: f64 = 5.0;
+pub const DEFAULT_GC_STRENGTH: f64 = 0.15;
+
+/// Tag marking the bonds this law formed (and may therefore collect)
+pub const BONDING_LAW_TAG: &str = "bonding-law";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BondingReport {
+    pub created: usize,
+    pub updated: usize,
+    pub collected: usize,
+}
+
+/// Shared bindings between one pair of threads
+#[derive(Debug, Clone, Copy, Default)]
+struct PairStats {
+    bindings: usize,
+    strength_sum: f64,
+}
+
+impl PairStats {
+    /// Affinity saturates towards 1.0 as bindings accumulate
+    fn affinity(&self, saturation: f64) -> f64 {
+        1.0 - (-(self.bindings as f64) / saturation.max(f64::EPSILON)).exp()
+    }
+
+    /// Bond strength: affinity weighted by how alive the supporting bindings still are
+    fn bond_strength(&self, saturation: f64) -> f64 {
+        let mean_strength = if self.bindings == 0 { 0.0 } else { self.strength_sum / self.bindings as f64 };
+        self.affinity(saturation) * mean_strength
+    }
+}
+
+/// What a bond needs to know about each of its threads
+#[derive(Debug, Clone)]
+struct ThreadInfo {
+    name: String,
+    org_id: Uuid,
+    owner_id: Uuid,
+}
+
+fn ordered_pair(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// A live bond this law formed, as opposed to a user's or a soft-deleted one
+fn is_collectable(bond: &Bond) -> bool {
+    bond.base.deleted_at.is_none() && bond.base.tags.iter().any(|tag| tag == BONDING_LAW_TAG)
+}
+
+/// Bonding System - creates, refreshes, garbage-collects and caps thread ⟷ thread bonds
+pub fn bonding_system(
+    world: &mut World,
+    index: &mut EntityIndex,
+    law: &CompiledLaw,
+    current_time: f64,
+    changes: &mut ChangeSet,
+) -> BondingReport {
+    let saturation = law.constant("binding_saturation").unwrap_or(DEFAULT_BINDING_SATURATION);
+    let gc_strength = law.constant("gc_strength").unwrap_or(DEFAULT_GC_STRENGTH);
+    let max_per_thread = law
+        .constant("max_bonds_per_thread")
+        .map(|max| max.max(0.0) as usize)
+        .unwrap_or(DEFAULT_MAX_BONDS_PER_THREAD);
+    let mut report = BondingReport::default();
+
+    // Count live bindings per thread pair
+    let mut pairs: BTreeMap<(Uuid, Uuid), PairStats> = BTreeMap::new();
+    for (_entity, (binding, decay)) in world.query::<(&Binding, Option<&DecayComponent>)>().iter() {
+        if binding.base.deleted_at.is_some() {
+            continue;
+        }
+        let thread_ids = binding.get_thread_ids();
+        for (i, a) in thread_ids.iter().enumerate() {
+            for b in &thread_ids[i + 1..] {
+                let stats = pairs.entry(ordered_pair(*a, *b)).or_default();
+                stats.bindings += 1;
+                stats.strength_sum += decay.map_or(1.0, |d| d.strength as f64);
+            }
+        }
+    }
+
+    // The law's own bonds are refreshed rather than duplicated; a user's or soft-deleted bond on
+    // a pair is left alone and stops the law forming one there. Only the law's bonds count
+    // against the cap.
+    let mut existing: HashMap<(Uuid, Uuid), Option<Entity>> = HashMap::new();
+    let mut bonds_per_thread: HashMap<Uuid, usize> = HashMap::new();
+    for (entity, bond) in world.query::<&Bond>().iter() {
+        if let [a, b] = bond.thread_ids.as_slice() {
+            let pair = ordered_pair(*a, *b);
+            // A user's bond wins over a law bond on the same pair
+            let refreshable = is_collectable(bond) && existing.get(&pair).is_none_or(Option::is_some);
+            existing.insert(pair, refreshable.then_some(entity));
+            if is_collectable(bond) {
+                *bonds_per_thread.entry(*a).or_insert(0) += 1;
+                *bonds_per_thread.entry(*b).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let threads: HashMap<Uuid, ThreadInfo> = world
+        .query::<(&Thread, Option<&ThreadId>)>()
+        .iter()
+        .filter_map(|(_, (thread, name))| {
+            let id = thread.base.id?;
+            let name = name.map_or_else(|| id.to_string()[0..8].to_string(), |name| name.0.clone());
+            Some((id, ThreadInfo { name, org_id: thread.base.org_id, owner_id: thread.base.owner_id }))
+        })
+        .collect();
+
+    for (pair, stats) in &pairs {
+        let affinity = stats.affinity(saturation);
+        let strength = stats.bond_strength(saturation);
+
+        if let Some(&existing) = existing.get(pair) {
+            if let Some(entity) = existing {
+                if refresh_bond(world, index, entity, affinity, strength, current_time, changes) {
+                    report.updated += 1;
+                }
+            }
+            continue;
+        }
+
+        // Bonds belong to their threads' org, so both threads must be known and share one
+        let (Some(thread_a), Some(thread_b)) = (threads.get(&pair.0), threads.get(&pair.1)) else {
+            continue;
+        };
+        if thread_a.org_id != thread_b.org_id {
+            continue;
+        }
+
+        let mut scope = law.scope();
+        scope.set("shared_bindings", stats.bindings as f64).set("affinity", affinity).set("strength", strength);
+        if !law.formula.evaluate(&scope).is_ok_and(|result| result != 0.0) {
+            continue;
+        }
+        // New bonds don't push a thread past its cap
+        let at_cap = |id: &Uuid| bonds_per_thread.get(id).copied().unwrap_or(0) >= max_per_thread;
+        if at_cap(&pair.0) || at_cap(&pair.1) {
+            continue;
+        }
+
+        let entity = spawn_bond(world, index, *pair, (thread_a, thread_b), affinity, strength, current_time);
+        changes.spawned(world, index, entity);
+        *bonds_per_thread.entry(pair.0).or_insert(0) += 1;
+        *bonds_per_thread.entry(pair.1).or_insert(0) += 1;
+        report.created += 1;
+    }
+
+    // A law bond whose bindings are all gone has nothing left holding it together
+    for (pair, entity) in &existing {
+        if let (Some(entity), false) = (entity, pairs.contains_key(pair)) {
+            if refresh_bond(world, index, *entity, 0.0, 0.0, current_time, changes) {
+                report.updated += 1;
+            }
+        }
+    }
+
+    report.collected = collect_garbage(world, index, gc_strength, max_per_thread, changes);
+    report
+}
+
+/// Returns whether the bond changed (and got a new version)
+fn refresh_bond(
+    world: &mut World,
+    index: &EntityIndex,
+    entity: Entity,
+    affinity: f64,
+    strength: f64,
+    current_time: f64,
+    changes: &mut ChangeSet,
+) -> bool {
+    if let Ok((bond, decay)) = world.query_one_mut::<(&mut Bond, Option<&mut DecayComponent>)>(entity) {
+        let changed = (bond.affinity_score - affinity).abs() > f64::EPSILON
+            || (bond.bond_strength - strength).abs() > f64::EPSILON;
+        if !changed {
+            return false;
+        }
+        changes.mutated(index, entity, "Bond", "affinity_score", bond.affinity_score, affinity);
+        changes.mutated(index, entity, "Bond", "bond_strength", bond.bond_strength, strength);
+        bond.affinity_score = affinity;
+        bond.bond_strength = strength;
+        bond.base.record_update();
+        if let Some(decay) = decay {
+            decay.strength = strength as f32;
+            decay.last_update = current_time;
+        }
+        return true;
+    }
+    false
+}
+
+fn spawn_bond(
+    world: &mut World,
+    index: &mut EntityIndex,
+    pair: (Uuid, Uuid),
+    (thread_a, thread_b): (&ThreadInfo, &ThreadInfo),
+    affinity: f64,
+    strength: f64,
+    current_time: f64,
+) -> Entity {
+    let (name_a, name_b) = (thread_a.name.clone(), thread_b.name.clone());
+
+    let bond = Bond {
+        base: BaseEntity {
+            id: Some(Uuid::new_v4()),
+            org_id: thread_a.org_id,
+            owner_id: thread_a.owner_id,
+            created_at: Some(Utc::now()),
+            updated_at: None,
+            deleted_at: None,
+            tags: vec!["bond".to_string(), BONDING_LAW_TAG.to_string(), name_a.clone(), name_b.clone()],
+            component_ids: vec![],
+            sub_type: Some("bond".to_string()),
+            visibility: Visibility::Private,
+            security_level: 0,
+            access_scope: vec![AccessScope::View],
+            version: 1,
+            parent_version: None,
+        },
+        thread_ids: vec![pair.0, pair.1],
+        affinity_score: affinity,
+        bond_strength: strength,
+        component_context: vec![],
+    };
+
+    index.spawn(world, (
+        bond,
+        DisplayText(format!("{} ⟷ {}", name_a, name_b)),
+        EntityType("bond".to_string()),
+        DecayComponent {
+            strength: strength as f32,
+            half_life: 120.0,
+            last_update: current_time,
+        },
+    ))
+}
+
+/// Despawn the law's faded bonds, then its weakest bonds of any thread over the cap
+fn collect_garbage(
+    world: &mut World,
+    index: &mut EntityIndex,
+    gc_strength: f64,
+    max_per_thread: usize,
+    changes: &mut ChangeSet,
+) -> usize {
+    let mut bonds: Vec<(Entity, Vec<Uuid>, f64)> = Vec::new();
+    let mut doomed: Vec<Entity> = Vec::new();
+    for (entity, (bond, decay)) in world.query::<(&Bond, Option<&DecayComponent>)>().iter() {
+        if !is_collectable(bond) {
+            continue;
+        }
+        let strength = decay.map_or(bond.bond_strength, |d| d.strength as f64);
+        if strength < gc_strength {
+            doomed.push(entity);
+        } else {
+            bonds.push((entity, bond.thread_ids.clone(), strength));
+        }
+    }
+
+    // Strongest first, so each thread keeps its best bonds
+    bonds.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    let mut kept_per_thread: HashMap<Uuid, usize> = HashMap::new();
+    for (entity, thread_ids, _strength) in bonds {
+        let over_cap = thread_ids
+            .iter()
+            .any(|id| kept_per_thread.get(id).copied().unwrap_or(0) >= max_per_thread);
+        if over_cap {
+            doomed.push(entity);
+            continue;
+        }
+        for id in thread_ids {
+            *kept_per_thread.entry(id).or_insert(0) += 1;
+        }
+    }
+
+    for &entity in &doomed {
+        changes.despawned(world, index, entity);
+        let _ = index.despawn(world, entity);
+    }
+    doomed.len()
+}
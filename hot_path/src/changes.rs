@@ -0,0 +1,158 @@
+// Per-tick change sets.
+// Systems and the command processor record what they spawn, despawn and mutate while a tick
+// runs; the memory system then hands the finished ChangeSet to the snapshot, persistence and
+// subscription layers, so none of them has to rescan the whole World to find out what changed.
+
+use hecs::{Entity, World};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::components::{ConsolidationStatus, EntityType};
+use crate::index::EntityIndex;
+use crate::persistence::EventType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Spawned {
+        entity: Entity,
+        id: Option<Uuid>,
+        entity_type: String,
+    },
+    Despawned {
+        entity: Entity,
+        id: Option<Uuid>,
+        entity_type: String,
+    },
+    /// One field of one component, e.g. `DecayComponent.strength`
+    Mutated {
+        entity: Entity,
+        id: Option<Uuid>,
+        component: &'static str,
+        field: &'static str,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Change {
+    pub fn entity(&self) -> Entity {
+        match self {
+            Change::Spawned { entity, .. } | Change::Despawned { entity, .. } | Change::Mutated { entity, .. } => *entity,
+        }
+    }
+
+    pub fn id(&self) -> Option<Uuid> {
+        match self {
+            Change::Spawned { id, .. } | Change::Despawned { id, .. } | Change::Mutated { id, .. } => *id,
+        }
+    }
+
+    /// The primary-memory event this change amounts to
+    pub fn event_type(&self) -> Option<EventType> {
+        match self {
+            Change::Spawned { .. } => Some(EventType::EntityCreated),
+            Change::Despawned { .. } => Some(EventType::EntityRemoved),
+            Change::Mutated { component: "BaseEntity", field: "tags", .. } => Some(EventType::EntityTagAdded),
+            Change::Mutated { component: "BaseEntity", field: "deleted_at", .. } => Some(EventType::EntitySoftDeleted),
+            Change::Mutated { component: "MemoryLayer", field: "consolidation_status", new, .. }
+                if *new == to_value(ConsolidationStatus::Consolidated) =>
+            {
+                Some(EventType::EntityConsolidated)
+            }
+            Change::Mutated { .. } => Some(EventType::ComponentUpdated),
+        }
+    }
+}
+
+/// Everything that changed in the world during one tick, in the order it happened
+#[derive(Debug, Clone, Default)]
+pub struct ChangeSet {
+    pub tick: u64,
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub fn new(tick: u64) -> Self {
+        Self { tick, changes: Vec::new() }
+    }
+
+    /// Record an entity that was just spawned (after `EntityIndex::spawn`)
+    pub fn spawned(&mut self, world: &World, index: &EntityIndex, entity: Entity) {
+        self.changes.push(Change::Spawned {
+            entity,
+            id: index.uuid(entity),
+            entity_type: entity_type(world, entity),
+        });
+    }
+
+    /// Record an entity about to be despawned (before `EntityIndex::despawn`, while it can still be read)
+    pub fn despawned(&mut self, world: &World, index: &EntityIndex, entity: Entity) {
+        self.changes.push(Change::Despawned {
+            entity,
+            id: index.uuid(entity),
+            entity_type: entity_type(world, entity),
+        });
+    }
+
+    /// Record a field change; no-op when the value didn't actually change
+    pub fn mutated(
+        &mut self,
+        index: &EntityIndex,
+        entity: Entity,
+        component: &'static str,
+        field: &'static str,
+        old: impl Serialize,
+        new: impl Serialize,
+    ) {
+        let (old, new) = (to_value(old), to_value(new));
+        if old == new {
+            return;
+        }
+        self.changes.push(Change::Mutated { entity, id: index.uuid(entity), component, field, old, new });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter()
+    }
+
+    /// Entities spawned or mutated this tick that are still alive at its end
+    pub fn touched(&self) -> HashSet<Entity> {
+        let removed = self.removed();
+        self.changes
+            .iter()
+            .filter(|change| !matches!(change, Change::Despawned { .. }))
+            .map(Change::entity)
+            .filter(|entity| !removed.contains(entity))
+            .collect()
+    }
+
+    /// Entities despawned this tick
+    pub fn removed(&self) -> HashSet<Entity> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change, Change::Despawned { .. }))
+            .map(Change::entity)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+fn entity_type(world: &World, entity: Entity) -> String {
+    world
+        .get::<&EntityType>(entity)
+        .map(|entity_type| entity_type.0.clone())
+        .unwrap_or_default()
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
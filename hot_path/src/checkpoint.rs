@@ -0,0 +1,180 @@
+// World checkpoints: periodic full snapshots of every schema entity and its runtime components
+// (decay, temporal position, memory layer, attached components, ...), written next to the
+// primary memory log. Startup restores the newest checkpoint within the replay limit and replays
+// only the log entries after it. Each checkpoint also carries the version history of every
+// entity - one stamp per log entry it covers - so compaction can delete covered segments
+// without losing track of which versions existed, when, and under which checksum.
+
+use chrono::{DateTime, Utc};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::components::EntityType;
+use crate::event_log::{write_atomically, LogError};
+use crate::migration::{Migrations, SchemaStamp};
+use crate::persistence::{EventType, PrimaryMemoryEntry};
+use crate::replay::{EntityRecord, ReplayLimit, Replayed};
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("checkpoint encoding error: {0}")]
+    Encoding(#[from] serde_json::Error),
+
+    #[error("Event log error: {0}")]
+    Log(#[from] LogError),
+}
+
+/// One version of an entity as it was logged; the payload itself may since have been compacted away
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionStamp {
+    pub version: u64,
+    pub sequence: u64,
+    pub event_type: EventType,
+    pub timestamp: DateTime<Utc>,
+    pub checksum: String,
+}
+
+impl From<&PrimaryMemoryEntry> for VersionStamp {
+    fn from(entry: &PrimaryMemoryEntry) -> Self {
+        Self {
+            version: entry.version,
+            sequence: entry.sequence,
+            event_type: entry.event_type,
+            timestamp: entry.timestamp,
+            checksum: entry.checksum.clone(),
+        }
+    }
+}
+
+/// The whole world as of one log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Sequence number of the last log entry the checkpoint reflects
+    pub sequence: u64,
+    /// That entry's timestamp
+    pub timestamp: Option<DateTime<Utc>>,
+    pub taken_at: DateTime<Utc>,
+    /// Schema the entity records were serialized under
+    pub schema: SchemaStamp,
+    pub entities: Vec<EntityRecord>,
+    /// Every logged version of every entity, up to `sequence`, oldest first
+    pub history: BTreeMap<Uuid, Vec<VersionStamp>>,
+}
+
+impl Checkpoint {
+    /// Capture `world`, which reflects the log up to the last of `entries`: the entries logged
+    /// since `previous` (earlier ones are passed over), whose versions join its history
+    pub fn capture<'a>(
+        world: &World,
+        previous: Option<&Checkpoint>,
+        entries: impl IntoIterator<Item = &'a PrimaryMemoryEntry>,
+    ) -> Self {
+        let mut history = previous.map(|checkpoint| checkpoint.history.clone()).unwrap_or_default();
+        let (mut sequence, mut timestamp) = previous.map_or((0, None), |checkpoint| (checkpoint.sequence, checkpoint.timestamp));
+        for entry in entries {
+            if entry.sequence <= sequence {
+                continue;
+            }
+            history.entry(entry.entity_id).or_default().push(VersionStamp::from(entry));
+            sequence = entry.sequence;
+            timestamp = Some(entry.timestamp);
+        }
+
+        let entities = world
+            .query::<&EntityType>()
+            .iter()
+            .filter_map(|(entity, _)| EntityRecord::capture(world, entity))
+            .collect();
+        Self { sequence, timestamp, taken_at: Utc::now(), schema: SchemaStamp::current(), entities, history }
+    }
+
+    /// Every logged version of one entity, oldest first
+    pub fn history(&self, entity_id: &Uuid) -> &[VersionStamp] {
+        self.history.get(entity_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Spawn the checkpointed entities into a fresh world, ready to replay the log after `sequence`
+    pub fn restore(&self, migrations: &Migrations) -> Replayed {
+        let mut replayed = Replayed::new();
+        for record in &self.entities {
+            match record.restore(&mut replayed.world, &mut replayed.index, &self.schema, migrations) {
+                Ok(_) => replayed.report.applied += 1,
+                Err(e) => replayed.report.skipped.push((self.sequence, e.to_string())),
+            }
+        }
+        replayed.report.last_sequence = self.sequence;
+        replayed.report.last_timestamp = self.timestamp;
+        replayed
+    }
+}
+
+fn checkpoint_name(sequence: u64) -> String {
+    format!("checkpoint-{:012}.json", sequence)
+}
+
+fn checkpoint_sequence(path: &Path) -> Option<u64> {
+    path.file_name()?.to_str()?.strip_prefix("checkpoint-")?.strip_suffix(".json")?.parse().ok()
+}
+
+/// Sequence numbers of the checkpoints in `dir`, oldest first
+pub fn list(dir: &Path) -> Result<Vec<u64>, CheckpointError> {
+    let mut sequences = Vec::new();
+    match fs::read_dir(dir) {
+        Ok(entries) => {
+            for dir_entry in entries {
+                if let Some(sequence) = checkpoint_sequence(&dir_entry?.path()) {
+                    sequences.push(sequence);
+                }
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    sequences.sort_unstable();
+    Ok(sequences)
+}
+
+pub fn load(dir: &Path, sequence: u64) -> Result<Checkpoint, CheckpointError> {
+    Ok(serde_json::from_slice(&fs::read(dir.join(checkpoint_name(sequence)))?)?)
+}
+
+/// Write a checkpoint next to the log; a crash mid-write leaves the previous checkpoints untouched
+pub fn write(dir: &Path, checkpoint: &Checkpoint) -> Result<PathBuf, CheckpointError> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(checkpoint_name(checkpoint.sequence));
+    write_atomically(&path, &serde_json::to_vec(checkpoint)?)?;
+    Ok(path)
+}
+
+/// The newest checkpoint within `limit`, if any
+pub fn latest(dir: &Path, limit: ReplayLimit) -> Result<Option<Checkpoint>, CheckpointError> {
+    for sequence in list(dir)?.into_iter().rev() {
+        if !limit.admits(sequence, None) {
+            continue;
+        }
+        let checkpoint = load(dir, sequence)?;
+        if limit.admits(checkpoint.sequence, checkpoint.timestamp) {
+            return Ok(Some(checkpoint));
+        }
+    }
+    Ok(None)
+}
+
+/// Delete all but the newest `keep` checkpoints (at least one is always kept); returns the oldest kept
+pub fn prune(dir: &Path, keep: usize) -> Result<Option<u64>, CheckpointError> {
+    let sequences = list(dir)?;
+    let cut = sequences.len().saturating_sub(keep.max(1));
+    for sequence in &sequences[..cut] {
+        fs::remove_file(dir.join(checkpoint_name(*sequence)))?;
+    }
+    Ok(sequences.get(cut).copied())
+}
@@ -1,20 +1,93 @@
 use crossbeam_channel::Receiver;
-use hecs::World;
+use hecs::{Entity, World};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::security::Principal;
 
 pub struct EcsWorld(pub World);
 
 pub struct CommandReceiver(pub Receiver<GqlCommand>);
 
+/// Where the memory system sends the result of a command once it has been applied
+pub type Reply = oneshot::Sender<Result<CommandOutcome, CommandError>>;
+
 #[derive(Debug)]
 pub enum GqlCommand {
-    CreateMoment { text: String, thread_id: String },
-    CreateThread { name: String, thread_type: String },
-    CreateFilament { content: String, thread_name: String },
-    CreateMotif { pattern: String, strength: f32 },
-    CreateBond { thread1: String, thread2: String, affinity: f32 },
-    CreateBinding { moment_id: String, thread_id: String },
-    UpdateStrength { entity_id: String, new_strength: f32 },
-    UpdateDisplayText { entity_id: String, new_text: String },
-    AddEntityTag { entity_id: String, tag: String },
-    SoftDeleteEntity { entity_id: String },
-} 
\ No newline at end of file
+    CreateMoment { text: String, thread_id: String, emotion: Option<String>, confidence: Option<f32>, principal: Principal, reply: Reply },
+    CreateThread { name: String, thread_type: String, principal: Principal, reply: Reply },
+    CreateFilament { content: String, thread_name: String, principal: Principal, reply: Reply },
+    CreateMotif { pattern: String, strength: f32, principal: Principal, reply: Reply },
+    CreateBond { thread1: String, thread2: String, affinity: f32, principal: Principal, reply: Reply },
+    CreateBinding { moment_id: String, thread_id: String, principal: Principal, reply: Reply },
+    UpdateStrength { entity_id: String, new_strength: f32, principal: Principal, reply: Reply },
+    UpdateDisplayText { entity_id: String, new_text: String, principal: Principal, reply: Reply },
+    AddEntityTag { entity_id: String, tag: String, principal: Principal, reply: Reply },
+    SoftDeleteEntity { entity_id: String, principal: Principal, reply: Reply },
+    /// Entities returned by a GraphQL read - runs `on_observation` laws on them
+    ObserveEntities { entities: Vec<Entity> },
+}
+
+impl GqlCommand {
+    /// Where the command's result goes; None for commands nobody waits on
+    pub fn into_reply(self) -> Option<Reply> {
+        match self {
+            GqlCommand::CreateMoment { reply, .. }
+            | GqlCommand::CreateThread { reply, .. }
+            | GqlCommand::CreateFilament { reply, .. }
+            | GqlCommand::CreateMotif { reply, .. }
+            | GqlCommand::CreateBond { reply, .. }
+            | GqlCommand::CreateBinding { reply, .. }
+            | GqlCommand::UpdateStrength { reply, .. }
+            | GqlCommand::UpdateDisplayText { reply, .. }
+            | GqlCommand::AddEntityTag { reply, .. }
+            | GqlCommand::SoftDeleteEntity { reply, .. } => Some(reply),
+            GqlCommand::ObserveEntities { .. } => None,
+        }
+    }
+}
+
+/// The entity a command created or modified
+#[derive(Debug, Clone)]
+pub struct CommandOutcome {
+    pub id: Uuid,
+    pub entity_type: String,
+    pub strength: Option<f32>,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CommandError {
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Memory system unavailable")]
+    Unavailable,
+
+    /// No reply in time; the command may or may not have been applied
+    #[error("Timed out waiting for the memory system; the command may still be applied")]
+    OutcomeUnknown,
+
+    /// Primary memory only partly restored, so no change is accepted; the command wasn't applied
+    #[error("Read-only: {0}")]
+    ReadOnly(String),
+}
+
+impl CommandError {
+    /// Machine-readable code exposed as the GraphQL error's `extensions.code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::Validation(_) => "VALIDATION",
+            CommandError::NotFound(_) => "NOT_FOUND",
+            CommandError::Forbidden(_) => "FORBIDDEN",
+            CommandError::Unavailable => "UNAVAILABLE",
+            CommandError::OutcomeUnknown => "OUTCOME_UNKNOWN",
+            CommandError::ReadOnly(_) => "READ_ONLY",
+        }
+    }
+}
@@ -7,6 +7,229 @@ pub use crate::generated::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use hecs::{Entity, World};
+
+// --- Helpers on generated schema types (kept here since generated.rs is rewritten each build) ---
+impl Moment {
+    /// Check if this moment was created recently
+    pub fn is_recent(&self) -> bool {
+        if let Some(created_at) = &self.base.created_at {
+            let now = chrono::Utc::now();
+            let duration = now.signed_duration_since(*created_at);
+            duration.num_minutes() < 30 // Recent if created within 30 minutes
+        } else {
+            false
+        }
+    }
+
+    /// Get the thread UUID (guaranteed present in Moment)
+    pub fn thread_uuid(&self) -> Uuid {
+        self.thread_id
+    }
+
+    /// Get the author UUID (guaranteed present in Moment)
+    pub fn author_uuid(&self) -> Uuid {
+        self.author_id
+    }
+}
+
+impl Binding {
+    /// Get all unique thread IDs from binding points
+    pub fn get_thread_ids(&self) -> Vec<Uuid> {
+        let mut thread_ids: Vec<Uuid> = self.points
+            .iter()
+            .map(|point| point.thread_id)
+            .collect();
+        thread_ids.sort();
+        thread_ids.dedup();
+        thread_ids
+    }
+
+    /// Get all unique moment IDs from binding points
+    pub fn get_moment_ids(&self) -> Vec<Uuid> {
+        let mut moment_ids: Vec<Uuid> = self.points
+            .iter()
+            .map(|point| point.moment_id)
+            .collect();
+        moment_ids.sort();
+        moment_ids.dedup();
+        moment_ids
+    }
+
+    /// Check if this binding involves a specific thread
+    pub fn involves_thread(&self, thread_id: Uuid) -> bool {
+        // Check both the semantic points and the optimization index
+        self.points.iter().any(|point| point.thread_id == thread_id) ||
+        self.thread_ids.contains(&thread_id)
+    }
+}
+
+impl BindingPoint {
+    /// Create a new binding point
+    pub fn new(thread_id: Uuid, moment_id: Uuid, cardinality: Cardinality) -> Self {
+        Self {
+            thread_id,
+            moment_id,
+            cardinality,
+        }
+    }
+}
+
+// --- Schema-declared components (BaseComponent instances attached to entities) ---
+
+/// Every BaseComponent attached to an entity. hecs stores one instance per Rust type,
+/// so schema components (time, emotion, ...) share this container and are keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttachedComponents(pub Vec<BaseComponent>);
+
+impl AttachedComponents {
+    /// Look up an attached component by its schema name
+    pub fn get(&self, name: &str) -> Option<&BaseComponent> {
+        self.0.iter().find(|component| component.name == name)
+    }
+
+    /// Insert a component, replacing any existing one with the same name.
+    /// Returns the id of the replaced component.
+    pub fn upsert(&mut self, component: BaseComponent) -> Option<Uuid> {
+        match self.0.iter_mut().find(|existing| existing.name == component.name) {
+            Some(existing) => std::mem::replace(existing, component).id,
+            None => {
+                self.0.push(component);
+                None
+            }
+        }
+    }
+}
+
+impl BaseComponent {
+    /// Create an empty component with a fresh id
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: Some(Uuid::new_v4()),
+            name: name.into(),
+            metadata: vec![],
+        }
+    }
+
+    /// Add a typed metadata entry (the Metadata.type tag is derived from the value)
+    pub fn with_metadata(mut self, key: impl Into<String>, value: MetadataValue) -> Self {
+        self.metadata.push(Metadata {
+            key: key.into(),
+            r#type: value.metadata_type(),
+            value,
+        });
+        self
+    }
+
+    /// Get a metadata value by key
+    pub fn metadata_value(&self, key: &str) -> Option<&MetadataValue> {
+        self.metadata.iter().find(|entry| entry.key == key).map(|entry| &entry.value)
+    }
+
+    /// Time metadata component (cold path: TimeComponent)
+    pub fn time(timestamp: DateTime<Utc>) -> Self {
+        Self::new("time").with_metadata("timestamp", MetadataValue::DateTime(timestamp))
+    }
+
+    /// Emotion component with classifier confidence (cold path: EmotionComponent)
+    pub fn emotion(emotion: impl Into<String>, confidence: f64) -> Self {
+        Self::new("emotion")
+            .with_metadata("emotion", MetadataValue::String(emotion.into()))
+            .with_metadata("confidence", MetadataValue::Number(confidence.clamp(0.0, 1.0)))
+    }
+}
+
+impl MetadataValue {
+    /// The cold path's type tag for this value
+    pub fn metadata_type(&self) -> MetadataType {
+        match self {
+            MetadataValue::Boolean(_) => MetadataType::Bool,
+            MetadataValue::Integer(_) => MetadataType::Int,
+            MetadataValue::Number(_) => MetadataType::Float,
+            MetadataValue::DateTime(_) => MetadataType::Datetime,
+            MetadataValue::String(_) => MetadataType::Str,
+        }
+    }
+}
+
+impl BaseEntity {
+    /// Record an in-place change: new version pointing at the previous one
+    pub fn record_update(&mut self) {
+        self.parent_version = Some(self.version);
+        self.version += 1;
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+/// Read the BaseEntity of whichever schema entity type this entity carries
+pub fn with_base_entity<R>(world: &World, entity: Entity, f: impl FnOnce(&BaseEntity) -> R) -> Option<R> {
+    fn try_type<T: SchemaEntity + hecs::Component, R>(
+        world: &World,
+        entity: Entity,
+        f: &mut Option<impl FnOnce(&BaseEntity) -> R>,
+    ) -> Option<R> {
+        let component = world.get::<&T>(entity).ok()?;
+        f.take().map(|f| f(component.base()))
+    }
+
+    let mut f = Some(f);
+    try_type::<Moment, R>(world, entity, &mut f)
+        .or_else(|| try_type::<Thread, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Binding, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Bond, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Filament, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Motif, R>(world, entity, &mut f))
+}
+
+/// Run `f` against the BaseEntity of whichever schema entity type this entity carries
+pub fn with_base_entity_mut<R>(world: &mut World, entity: Entity, f: impl FnOnce(&mut BaseEntity) -> R) -> Option<R> {
+    fn try_type<T: SchemaEntity + hecs::Component, R>(
+        world: &mut World,
+        entity: Entity,
+        f: &mut Option<impl FnOnce(&mut BaseEntity) -> R>,
+    ) -> Option<R> {
+        let mut component = world.get::<&mut T>(entity).ok()?;
+        f.take().map(|f| f(component.base_mut()))
+    }
+
+    let mut f = Some(f);
+    try_type::<Moment, R>(world, entity, &mut f)
+        .or_else(|| try_type::<Thread, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Binding, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Bond, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Filament, R>(world, entity, &mut f))
+        .or_else(|| try_type::<Motif, R>(world, entity, &mut f))
+}
+
+/// Attach a schema-declared component to an entity and record its id in BaseEntity.component_ids
+pub fn attach_component(world: &mut World, entity: Entity, component: BaseComponent) -> Result<Uuid, hecs::NoSuchEntity> {
+    if !world.contains(entity) {
+        return Err(hecs::NoSuchEntity);
+    }
+    let component_id = component.id.unwrap_or_else(Uuid::new_v4);
+    let component = BaseComponent { id: Some(component_id), ..component };
+
+    let replaced = world
+        .get::<&mut AttachedComponents>(entity)
+        .ok()
+        .map(|mut attached| attached.upsert(component.clone()));
+    let replaced = match replaced {
+        Some(replaced) => replaced,
+        None => {
+            world.insert_one(entity, AttachedComponents(vec![component]))?;
+            None
+        }
+    };
+
+    with_base_entity_mut(world, entity, |base| {
+        base.component_ids.retain(|id| Some(*id) != replaced);
+        if !base.component_ids.contains(&component_id) {
+            base.component_ids.push(component_id);
+        }
+    });
+
+    Ok(component_id)
+}
 
 // --- Visualization-specific wrapper components (not in schema) ---
 #[derive(Debug)]
@@ -26,13 +249,17 @@ pub struct ThreadName(pub String);
 pub struct ThreadId(pub String);
 
 // --- Decay Component for implementing time-based laws ---
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecayComponent {
     pub strength: f32,
     pub half_life: f32, // Time in seconds for strength to halve
     pub last_update: f64, // Timestamp of last update
 }
 
+// --- Affinity of a pairwise relation (bindings); Bond carries its own affinity_score ---
+#[derive(Debug, Clone, Copy)]
+pub struct Affinity(pub f64);
+
 /// Temporal positioning component - treats time as a spatial dimension
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalPosition {
@@ -42,6 +269,19 @@ pub struct TemporalPosition {
     pub time_zone_offset: Option<i32>,
 }
 
+impl TemporalPosition {
+    /// Position at an exact timestamp, relative to now
+    pub fn at(timestamp: DateTime<Utc>) -> Self {
+        let offset = (Utc::now() - timestamp).num_milliseconds() as f64 / 1000.0;
+        Self {
+            timestamp,
+            precision: TemporalPrecision::Second,
+            temporal_coordinates: (offset.max(0.0), 0.0, (-offset).max(0.0)),
+            time_zone_offset: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TemporalPrecision {
     Millisecond,
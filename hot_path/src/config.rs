@@ -1,8 +1,52 @@
 use serde::Deserialize;
 
+use crate::persistence::PersistenceConfig;
+use crate::security::{ApiKeys, Principal};
+
 #[derive(Deserialize, Debug)]
 pub struct Settings {
+    /// Key acting for `principal`; leave empty (with no `api_keys`) to disable authentication
     pub api_key: String,
+    /// Further keys, each acting for its own principal
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+    /// Serve the GraphiQL IDE on `GET /`
+    #[serde(default = "default_graphiql")]
+    pub graphiql: bool,
+    #[serde(default = "default_laws_path")]
+    pub laws_path: String,
+    #[serde(default = "default_tick_interval_ms")]
+    pub tick_interval_ms: u64,
+    #[serde(default = "default_affinity_threshold")]
+    pub affinity_threshold: f64,
+    /// Who GraphQL requests act for
+    #[serde(default)]
+    pub principal: Principal,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+}
+
+/// One `[[api_keys]]` entry
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub key: String,
+    pub principal: Principal,
+}
+
+fn default_graphiql() -> bool {
+    true
+}
+
+fn default_laws_path() -> String {
+    "../assets/laws.json".to_string()
+}
+
+fn default_tick_interval_ms() -> u64 {
+    1000
+}
+
+fn default_affinity_threshold() -> f64 {
+    0.7
 }
 
 impl Settings {
@@ -21,4 +65,21 @@ impl Settings {
 
         s.try_deserialize()
     }
+
+    /// Every configured API key with the principal it acts for
+    pub fn api_keys(&self) -> ApiKeys {
+        let keys = std::iter::once((self.api_key.clone(), self.principal.clone()))
+            .chain(self.api_keys.iter().map(|entry| (entry.key.clone(), entry.principal.clone())));
+        ApiKeys::new(keys, self.principal.clone())
+    }
+
+    /// Event log directory; a relative path is resolved against the working directory
+    pub fn persistence_dir(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.persistence.dir)
+    }
+
+    /// Law file path; a relative path is resolved against the working directory
+    pub fn laws_file(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from(&self.laws_path)
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,299 @@
+// Consolidation system - the `consolidation` law (`consolidate_if(strength < ...)`).
+// Decaying moments are marked Consolidating; once enough of one owner's moments gather on one
+// thread they collapse into a Filament, and that owner's leftovers sharing a tag or an emotion
+// across threads collapse into a Motif. Clusters never mix owners: consolidated entities are
+// private to their owner and their summary quotes the source moments.
+
+use chrono::Utc;
+use hecs::{Entity, World};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use uuid::Uuid;
+
+use crate::components::{
+    with_base_entity_mut, AccessScope, AttachedComponents, BaseEntity, ConsolidationStatus,
+    DecayComponent, DisplayText, EntityType, Filament, MemoryLayer, MemoryLayerType, MetadataValue, Moment, Motif,
+    ThreadId, ThreadName, Visibility,
+};
+use crate::changes::ChangeSet;
+use crate::formula::FormulaFields;
+use crate::index::EntityIndex;
+use crate::laws::CompiledLaw;
+
+/// Used when the law doesn't declare a `min_cluster_size` constant
+pub const DEFAULT_MIN_CLUSTER_SIZE: usize = 3;
+
+/// Tags every moment carries, so they can't define a cross-thread pattern
+const TYPE_TAGS: [&str; 1] = ["moment"];
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsolidationReport {
+    pub marked: usize,
+    pub filaments: usize,
+    pub motifs: usize,
+    pub consolidated_moments: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    entity: Entity,
+    id: Uuid,
+    thread: String,
+    org_id: Uuid,
+    owner_id: Uuid,
+    security_level: i64,
+    /// Tags (other than `TYPE_TAGS`) and the attached emotion - what a motif can form around
+    patterns: Vec<String>,
+    text: String,
+    strength: f32,
+    half_life: f32,
+}
+
+/// Consolidation System - advances decaying moments Fresh → Consolidating → Consolidated
+pub fn consolidation_system(
+    world: &mut World,
+    index: &mut EntityIndex,
+    law: &CompiledLaw,
+    current_time: f64,
+    changes: &mut ChangeSet,
+) -> ConsolidationReport {
+    let min_cluster = law
+        .constant("min_cluster_size")
+        .map(|size| size.max(2.0) as usize)
+        .unwrap_or(DEFAULT_MIN_CLUSTER_SIZE);
+
+    // Phase 1: fresh moments the law's predicate accepts start consolidating
+    let mut report = ConsolidationReport {
+        marked: mark_candidates(world, index, law, changes),
+        ..Default::default()
+    };
+
+    // Phase 2: cluster everything consolidating (including moments marked on earlier runs)
+    let pool = consolidating_moments(world);
+
+    // Clusters never span orgs or owners, so every consolidated entity belongs to exactly one
+    let mut by_thread: BTreeMap<(Uuid, Uuid, String), Vec<Candidate>> = BTreeMap::new();
+    for candidate in pool {
+        by_thread.entry((candidate.org_id, candidate.owner_id, candidate.thread.clone())).or_default().push(candidate);
+    }
+
+    let mut leftovers = Vec::new();
+    for ((_org_id, _owner_id, thread), cluster) in by_thread {
+        if cluster.len() >= min_cluster {
+            spawn_filament(world, index, &thread, &cluster, current_time, changes);
+            report.filaments += 1;
+            report.consolidated_moments += complete(world, index, &cluster, changes);
+        } else {
+            leftovers.extend(cluster);
+        }
+    }
+
+    // Cross-thread clusters: one owner's leftovers sharing a pattern on at least two threads
+    let mut used: HashSet<Uuid> = HashSet::new();
+    let patterns: BTreeSet<(Uuid, Uuid, String)> = leftovers
+        .iter()
+        .flat_map(|c| c.patterns.iter().map(|pattern| (c.org_id, c.owner_id, pattern.clone())))
+        .collect();
+    for (org_id, owner_id, pattern) in patterns {
+        let cluster: Vec<Candidate> = leftovers
+            .iter()
+            .filter(|c| {
+                !used.contains(&c.id) && c.org_id == org_id && c.owner_id == owner_id && c.patterns.contains(&pattern)
+            })
+            .cloned()
+            .collect();
+        let threads: BTreeSet<&str> = cluster.iter().map(|c| c.thread.as_str()).collect();
+        if cluster.len() >= min_cluster && threads.len() >= 2 {
+            spawn_motif(world, index, &pattern, &cluster, current_time, changes);
+            used.extend(cluster.iter().map(|c| c.id));
+            report.motifs += 1;
+            report.consolidated_moments += complete(world, index, &cluster, changes);
+        }
+    }
+
+    report
+}
+
+fn status(layer: Option<&MemoryLayer>) -> ConsolidationStatus {
+    layer.map_or(ConsolidationStatus::Fresh, |layer| layer.consolidation_status.clone())
+}
+
+fn mark_candidates(world: &mut World, index: &EntityIndex, law: &CompiledLaw, changes: &mut ChangeSet) -> usize {
+    let mut accepted = Vec::new();
+    for (entity, (moment, entity_type, decay, layer)) in world
+        .query::<(&Moment, &EntityType, &DecayComponent, Option<&MemoryLayer>)>()
+        .iter()
+    {
+        if !law.applies_to(&entity_type.0)
+            || moment.base.deleted_at.is_some()
+            || !matches!(status(layer), ConsolidationStatus::Fresh)
+        {
+            continue;
+        }
+
+        let mut scope = law.scope();
+        scope.bind_fields(decay, &law.law.variables);
+        if law.formula.evaluate(&scope).is_ok_and(|result| result != 0.0) {
+            accepted.push(entity);
+        }
+    }
+
+    for &entity in &accepted {
+        set_layer(world, index, entity, MemoryLayerType::TransitionalMemory, ConsolidationStatus::Consolidating, changes);
+    }
+    accepted.len()
+}
+
+fn consolidating_moments(world: &World) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    for (entity, (moment, layer, decay, thread_id, text, attached)) in world
+        .query::<(&Moment, &MemoryLayer, &DecayComponent, Option<&ThreadId>, Option<&DisplayText>, Option<&AttachedComponents>)>()
+        .iter()
+    {
+        // A moment deleted since it was marked must not be quoted in a summary
+        if moment.base.deleted_at.is_some() || !matches!(layer.consolidation_status, ConsolidationStatus::Consolidating) {
+            continue;
+        }
+        let Some(id) = moment.base.id else {
+            continue;
+        };
+        candidates.push(Candidate {
+            entity,
+            id,
+            thread: thread_id.map(|t| t.0.clone()).unwrap_or_else(|| moment.thread_id.to_string()),
+            org_id: moment.base.org_id,
+            owner_id: moment.base.owner_id,
+            security_level: moment.base.security_level,
+            patterns: moment
+                .base
+                .tags
+                .iter()
+                .filter(|tag| !TYPE_TAGS.contains(&tag.as_str()))
+                .cloned()
+                .chain(attached.and_then(emotion))
+                .collect(),
+            text: text.map(|t| t.0.clone()).unwrap_or_default(),
+            strength: decay.get_field("strength").unwrap_or_default() as f32,
+            half_life: decay.half_life,
+        });
+    }
+    candidates
+}
+
+/// The emotion a moment was created with (`createMoment(emotion: ...)`), if any
+fn emotion(attached: &AttachedComponents) -> Option<String> {
+    attached.get("emotion")?.metadata.iter().find_map(|entry| match (&*entry.key, &entry.value) {
+        ("emotion", MetadataValue::String(emotion)) => Some(emotion.clone()),
+        _ => None,
+    })
+}
+
+fn set_layer(
+    world: &mut World,
+    index: &EntityIndex,
+    entity: Entity,
+    layer_type: MemoryLayerType,
+    status: ConsolidationStatus,
+    changes: &mut ChangeSet,
+) {
+    let now = Utc::now();
+    let previous = world.get::<&MemoryLayer>(entity).ok().map(|layer| layer.consolidation_status.clone());
+    let changes_before = changes.len();
+    changes.mutated(index, entity, "MemoryLayer", "consolidation_status", &previous, Some(&status));
+    // A logged status change is a new version of the entity
+    if changes.len() > changes_before {
+        with_base_entity_mut(world, entity, |base| base.record_update());
+    }
+
+    if let Ok(mut layer) = world.get::<&mut MemoryLayer>(entity) {
+        layer.layer_type = layer_type;
+        layer.consolidation_status = status;
+        layer.last_accessed = now;
+        return;
+    }
+    let _ = world.insert_one(entity, MemoryLayer {
+        layer_type,
+        access_frequency: 0.0,
+        last_accessed: now,
+        consolidation_status: status,
+        redis_key: None,
+    });
+}
+
+fn complete(world: &mut World, index: &EntityIndex, cluster: &[Candidate], changes: &mut ChangeSet) -> usize {
+    for candidate in cluster {
+        set_layer(world, index, candidate.entity, MemoryLayerType::PrimaryMemory, ConsolidationStatus::Consolidated, changes);
+    }
+    cluster.len()
+}
+
+/// Base entity for a consolidated entity; `component_ids` records the source moments
+fn consolidated_base(cluster: &[Candidate], kind: &str, extra_tag: &str) -> BaseEntity {
+    BaseEntity {
+        id: Some(Uuid::new_v4()),
+        org_id: cluster[0].org_id,
+        owner_id: cluster[0].owner_id,
+        created_at: Some(Utc::now()),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![kind.to_string(), "consolidated".to_string(), extra_tag.to_string()],
+        component_ids: cluster.iter().map(|c| c.id).collect(),
+        sub_type: Some(kind.to_string()),
+        visibility: Visibility::Private,
+        // The summary quotes its sources, so it is as sensitive as the most sensitive of them
+        security_level: cluster.iter().map(|c| c.security_level).max().unwrap_or_default(),
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    }
+}
+
+/// Consolidated memories start from the combined strength and outlive their sources
+fn consolidated_decay(cluster: &[Candidate], current_time: f64) -> DecayComponent {
+    let strength: f32 = cluster.iter().map(|c| c.strength).sum();
+    let half_life = cluster.iter().map(|c| c.half_life).sum::<f32>();
+    DecayComponent {
+        strength: strength.min(1.0),
+        half_life,
+        last_update: current_time,
+    }
+}
+
+fn summary(cluster: &[Candidate]) -> String {
+    let first = cluster.iter().find(|c| !c.text.is_empty()).map_or("", |c| c.text.as_str());
+    format!("{} moments: {}…", cluster.len(), first)
+}
+
+fn spawn_filament(
+    world: &mut World,
+    index: &mut EntityIndex,
+    thread: &str,
+    cluster: &[Candidate],
+    current_time: f64,
+    changes: &mut ChangeSet,
+) {
+    let entity = index.spawn(world, (
+        Filament { base: consolidated_base(cluster, "filament", thread) },
+        DisplayText(summary(cluster)),
+        ThreadName(thread.to_string()),
+        EntityType("filament".to_string()),
+        consolidated_decay(cluster, current_time),
+    ));
+    changes.spawned(world, index, entity);
+}
+
+fn spawn_motif(
+    world: &mut World,
+    index: &mut EntityIndex,
+    pattern: &str,
+    cluster: &[Candidate],
+    current_time: f64,
+    changes: &mut ChangeSet,
+) {
+    let entity = index.spawn(world, (
+        Motif { base: consolidated_base(cluster, "motif", pattern) },
+        DisplayText(format!("#{} across threads ({})", pattern, summary(cluster))),
+        EntityType("motif".to_string()),
+        consolidated_decay(cluster, current_time),
+    ));
+    changes.spawned(world, index, entity);
+}
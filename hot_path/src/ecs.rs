@@ -6,7 +6,13 @@ use hecs::World;
 
 /// Generic system runner - coordinates all ECS systems
 /// Systems are the "laws of physics" that operate on entities
-pub fn run_systems(world: &mut World, law_specs: &crate::systems::LawSpecifications) {
-    // Run all physics systems using schema-defined specifications
-    crate::systems::run_physics_systems(world, law_specs);
-} 
\ No newline at end of file
+pub fn run_systems(
+    world: &mut World,
+    index: &mut crate::index::EntityIndex,
+    law_specs: &crate::systems::LawSpecifications,
+    scheduler: &mut crate::systems::LawScheduler,
+    changes: &mut crate::changes::ChangeSet,
+) -> crate::systems::ScheduleReport {
+    // Run physics systems as their schema-defined triggers fire, recording what they change
+    scheduler.run(world, index, law_specs, changes)
+}
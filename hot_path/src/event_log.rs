@@ -0,0 +1,486 @@
+// Primary memory event log - the local, file-backed replacement for ChronicleDB.
+// PrimaryMemoryEntry records are appended to numbered segment files in one directory and never
+// rewritten. Each record is framed as [payload length: u32 LE][crc32 of payload: u32 LE][JSON];
+// a segment rolls over once it would grow past the configured size. A crash mid-append leaves
+// an incomplete frame at the end of the last segment, which opening the log truncates away.
+// On top of the per-frame CRC (which only catches accidental damage), every entry carries a
+// SHA-256 of its content and of the previous entry's hash; `verify` walks that chain to find
+// the first record that was damaged, edited, dropped or reordered.
+// Compaction deletes whole sealed segments from the front of the log once a checkpoint covers
+// them, leaving an anchor file with the last dropped entry so the chain can still be checked.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::persistence::PrimaryMemoryEntry;
+
+/// Bytes of framing in front of every record: payload length and checksum
+const FRAME_HEADER: usize = 8;
+
+/// Records larger than this are refused, and read as corruption rather than allocated
+pub const MAX_RECORD_BYTES: usize = 64 * 1024 * 1024;
+
+/// Written by compaction: where the remaining log picks up
+const ANCHOR_FILE: &str = "compacted.json";
+
+/// When appended records are forced to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPolicy {
+    /// fsync after every append: nothing acknowledged is ever lost
+    #[default]
+    Always,
+    /// fsync at most once per `sync_interval_ms`; a crash loses at most that window
+    Interval,
+    /// Leave it to the OS page cache
+    Never,
+}
+
+#[derive(Debug, Error)]
+pub enum LogError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("record encoding error: {0}")]
+    Encoding(#[from] serde_json::Error),
+
+    #[error("corrupt record in {segment} at byte {offset}: {reason}")]
+    Corrupt { segment: String, offset: u64, reason: String },
+
+    #[error("record of {0} bytes exceeds the {MAX_RECORD_BYTES} byte limit")]
+    TooLarge(usize),
+
+    #[error("entry {entity_id} v{version} in {segment} at byte {offset} does not match its checksum")]
+    Tampered { segment: String, offset: u64, entity_id: Uuid, version: u64 },
+
+    #[error("chain broken in {segment} at byte {offset}: expected previous checksum {expected:?}, found {found:?}")]
+    BrokenChain { segment: String, offset: u64, expected: String, found: String },
+}
+
+/// Where a record starts: segment number and byte offset within it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RecordPosition {
+    pub segment: u64,
+    pub offset: u64,
+}
+
+/// One record read back from the log
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub position: RecordPosition,
+    pub entry: PrimaryMemoryEntry,
+}
+
+/// The last entry compaction dropped; the first remaining entry chains onto its checksum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Anchor {
+    pub sequence: u64,
+    pub checksum: String,
+}
+
+/// The anchor of a compacted log in `dir`, or None if nothing was ever compacted away
+pub fn anchor(dir: &Path) -> Result<Option<Anchor>, LogError> {
+    match fs::read(dir.join(ANCHOR_FILE)) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replace `path` with `bytes` in one step: write a temporary file, sync it, rename it over
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temporary = path.with_extension("tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn segment_name(segment: u64) -> String {
+    format!("segment-{:06}.log", segment)
+}
+
+fn segment_number(path: &Path) -> Option<u64> {
+    path.file_name()?.to_str()?.strip_prefix("segment-")?.strip_suffix(".log")?.parse().ok()
+}
+
+/// Segment numbers present in `dir`, oldest first
+fn segments(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut numbers = Vec::new();
+    for dir_entry in fs::read_dir(dir)? {
+        if let Some(number) = segment_number(&dir_entry?.path()) {
+            numbers.push(number);
+        }
+    }
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/// Outcome of reading one frame
+enum Frame {
+    Record(Vec<u8>),
+    /// Clean end of the segment
+    End,
+    /// The segment ends part-way through a frame: a torn append
+    Incomplete,
+}
+
+/// Read until `buf` is full or the reader is exhausted; returns the bytes read
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn read_frame(reader: &mut impl Read, segment: u64, offset: u64) -> Result<Frame, LogError> {
+    let corrupt = |reason: String| LogError::Corrupt { segment: segment_name(segment), offset, reason };
+
+    let mut header = [0u8; FRAME_HEADER];
+    match read_up_to(reader, &mut header)? {
+        0 => return Ok(Frame::End),
+        FRAME_HEADER => {}
+        _ => return Ok(Frame::Incomplete),
+    }
+    let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    if len > MAX_RECORD_BYTES {
+        return Err(corrupt(format!("frame claims {} bytes", len)));
+    }
+
+    let mut payload = vec![0u8; len];
+    if read_up_to(reader, &mut payload)? < len {
+        return Ok(Frame::Incomplete);
+    }
+    if crc32fast::hash(&payload) != crc {
+        return Err(corrupt("checksum mismatch".to_string()));
+    }
+    Ok(Frame::Record(payload))
+}
+
+/// Append-only log of PrimaryMemoryEntry records in a directory of segment files
+#[derive(Debug)]
+pub struct EventLog {
+    dir: PathBuf,
+    segment_bytes: u64,
+    sync: SyncPolicy,
+    sync_interval: Duration,
+    last_sync: Instant,
+    /// Writes not yet forced to disk
+    dirty: bool,
+    segment: u64,
+    file: File,
+    len: u64,
+    /// Checksum of the newest entry, which the next one commits to
+    last_checksum: String,
+    /// Sequence number of the newest entry; 0 while the log is empty
+    last_sequence: u64,
+}
+
+impl EventLog {
+    /// Open (or create) the log in `dir`, truncating a torn record at the end of the last segment
+    pub fn open(dir: impl Into<PathBuf>, segment_bytes: u64, sync: SyncPolicy, sync_interval: Duration) -> Result<Self, LogError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let segment = segments(&dir)?.last().copied().unwrap_or(1);
+        let path = dir.join(segment_name(segment));
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&path)?;
+        let valid = Self::valid_length(&mut file, segment)?;
+        if valid < file.metadata()?.len() {
+            eprintln!("⚠️  {}: dropping torn record after byte {}", path.display(), valid);
+            file.set_len(valid)?;
+            file.sync_data()?;
+        }
+
+        let (last_checksum, last_sequence) = Self::last_entry(&dir)?;
+
+        Ok(Self {
+            dir,
+            segment_bytes: segment_bytes.max(1),
+            sync,
+            sync_interval,
+            last_sync: Instant::now(),
+            dirty: false,
+            segment,
+            file,
+            len: valid,
+            last_checksum,
+            last_sequence,
+        })
+    }
+
+    /// Checksum and sequence of the newest entry; it sits in an earlier segment while the last one is still empty
+    fn last_entry(dir: &Path) -> Result<(String, u64), LogError> {
+        for segment in segments(dir)?.into_iter().rev() {
+            let records = Records { segments: vec![segment], ..Records::open(dir)? };
+            if let Some(record) = records.last().transpose()? {
+                return Ok((record.entry.checksum, record.entry.sequence));
+            }
+        }
+        Ok((String::new(), 0))
+    }
+
+    /// The log's version: sequence number of the newest entry
+    pub fn last_sequence(&self) -> u64 {
+        self.last_sequence
+    }
+
+    /// Length of the segment's whole, checksummed records
+    fn valid_length(file: &mut File, segment: u64) -> Result<u64, LogError> {
+        let mut reader = io::BufReader::new(&*file);
+        let mut offset = 0u64;
+        loop {
+            match read_frame(&mut reader, segment, offset)? {
+                Frame::Record(payload) => offset += (FRAME_HEADER + payload.len()) as u64,
+                Frame::End | Frame::Incomplete => return Ok(offset),
+            }
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Chain and seal one entry, then append it; it is on disk when this returns if the sync policy is `Always`
+    pub fn append(&mut self, mut entry: PrimaryMemoryEntry) -> Result<LogRecord, LogError> {
+        entry.prev_checksum = self.last_checksum.clone();
+        entry.sequence = self.last_sequence + 1;
+        entry.checksum = entry.compute_checksum();
+        let payload = serde_json::to_vec(&entry)?;
+        if payload.len() > MAX_RECORD_BYTES {
+            return Err(LogError::TooLarge(payload.len()));
+        }
+        let frame_len = (FRAME_HEADER + payload.len()) as u64;
+        if self.len > 0 && self.len + frame_len > self.segment_bytes {
+            self.roll()?;
+        }
+
+        let mut frame = Vec::with_capacity(FRAME_HEADER + payload.len());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        // One write per record, so a crash can only ever tear the last one
+        self.file.write_all(&frame)?;
+
+        let position = RecordPosition { segment: self.segment, offset: self.len };
+        self.len += frame_len;
+        self.dirty = true;
+        self.last_checksum = entry.checksum.clone();
+        self.last_sequence = entry.sequence;
+        match self.sync {
+            SyncPolicy::Always => self.sync()?,
+            SyncPolicy::Interval if self.last_sync.elapsed() >= self.sync_interval => self.sync()?,
+            SyncPolicy::Interval | SyncPolicy::Never => {}
+        }
+        Ok(LogRecord { position, entry })
+    }
+
+    /// Force everything appended so far to disk
+    pub fn sync(&mut self) -> Result<(), LogError> {
+        if self.dirty {
+            self.file.sync_data()?;
+            self.dirty = false;
+        }
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// Seal the current segment and start the next one
+    fn roll(&mut self) -> Result<(), LogError> {
+        self.sync()?;
+        self.segment += 1;
+        self.file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(self.dir.join(segment_name(self.segment)))?;
+        self.len = 0;
+        // Make the new segment's directory entry durable too
+        if self.sync != SyncPolicy::Never {
+            File::open(&self.dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Every record in the log, oldest first
+    pub fn read_all(&self) -> Result<Vec<LogRecord>, LogError> {
+        read_dir(&self.dir)
+    }
+
+    /// Delete the sealed segments holding only entries up to `through`; returns the dropped records.
+    /// The anchor is written before any segment goes, so an interrupted compaction only leaves
+    /// entries behind that readers already know to be covered.
+    pub fn compact(&mut self, through: u64) -> Result<Vec<LogRecord>, LogError> {
+        let mut doomed = Vec::new();
+        let mut dropped = Vec::new();
+        for segment in segments(&self.dir)? {
+            if segment == self.segment {
+                break;
+            }
+            let records: Vec<LogRecord> = Records { segments: vec![segment], ..Records::open(&self.dir)? }.collect::<Result<_, _>>()?;
+            if records.last().is_some_and(|record| record.entry.sequence > through) {
+                break;
+            }
+            doomed.push(segment);
+            dropped.extend(records);
+        }
+        let Some(last) = dropped.last() else {
+            return Ok(dropped);
+        };
+
+        let anchor = Anchor { sequence: last.entry.sequence, checksum: last.entry.checksum.clone() };
+        write_atomically(&self.dir.join(ANCHOR_FILE), &serde_json::to_vec_pretty(&anchor)?)?;
+        for segment in doomed {
+            fs::remove_file(self.dir.join(segment_name(segment)))?;
+        }
+        Ok(dropped)
+    }
+}
+
+/// Reads the records of a log directory in order, stopping at the first error.
+/// A torn record can only end the last segment; anywhere else it is corruption.
+pub struct Records {
+    dir: PathBuf,
+    segments: Vec<u64>,
+    next_segment: usize,
+    current: Option<(u64, io::BufReader<File>, u64)>,
+    failed: bool,
+}
+
+impl Records {
+    pub fn open(dir: &Path) -> Result<Self, LogError> {
+        Ok(Self { dir: dir.to_path_buf(), segments: segments(dir)?, next_segment: 0, current: None, failed: false })
+    }
+
+    fn next_record(&mut self) -> Result<Option<LogRecord>, LogError> {
+        loop {
+            let Some((segment, reader, offset)) = &mut self.current else {
+                let Some(&segment) = self.segments.get(self.next_segment) else {
+                    return Ok(None);
+                };
+                self.next_segment += 1;
+                let file = File::open(self.dir.join(segment_name(segment)))?;
+                self.current = Some((segment, io::BufReader::new(file), 0));
+                continue;
+            };
+            let (segment, start) = (*segment, *offset);
+            let corrupt = |reason: String| LogError::Corrupt { segment: segment_name(segment), offset: start, reason };
+
+            match read_frame(reader, segment, start)? {
+                Frame::Record(payload) => {
+                    let entry = serde_json::from_slice(&payload).map_err(|e| corrupt(e.to_string()))?;
+                    *offset += (FRAME_HEADER + payload.len()) as u64;
+                    return Ok(Some(LogRecord { position: RecordPosition { segment, offset: start }, entry }));
+                }
+                Frame::End => self.current = None,
+                Frame::Incomplete if self.next_segment == self.segments.len() => self.current = None,
+                Frame::Incomplete => return Err(corrupt("truncated record in a sealed segment".to_string())),
+            }
+        }
+    }
+}
+
+impl Iterator for Records {
+    type Item = Result<LogRecord, LogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let record = self.next_record().transpose();
+        self.failed = matches!(record, Some(Err(_)));
+        record
+    }
+}
+
+/// Every record of the log in `dir`, oldest first
+pub fn read_dir(dir: &Path) -> Result<Vec<LogRecord>, LogError> {
+    Records::open(dir)?.collect()
+}
+
+/// Outcome of `verify`: how many records checked out, and the first one that didn't
+#[derive(Debug)]
+pub struct Verification {
+    pub verified: usize,
+    /// Sequence number of the last record that checked out (the anchor's, if none did)
+    pub last_sequence: u64,
+    pub corruption: Option<LogError>,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.corruption.is_none()
+    }
+}
+
+/// Scan the log in `dir`, checking every frame, every entry checksum and the chain between them.
+/// A compacted log's chain starts at its anchor.
+pub fn verify(dir: &Path) -> Verification {
+    let mut verification = Verification { verified: 0, last_sequence: 0, corruption: None };
+    let opened = anchor(dir).and_then(|anchor| Ok((anchor, Records::open(dir)?)));
+    let (anchor, records) = match opened {
+        Ok(opened) => opened,
+        Err(e) => {
+            verification.corruption = Some(e);
+            return verification;
+        }
+    };
+
+    let mut previous = String::new();
+    if let Some(anchor) = anchor {
+        verification.last_sequence = anchor.sequence;
+        previous = anchor.checksum;
+    }
+    let covered = verification.last_sequence;
+    // Entries up to the anchor outlived an interrupted compaction; a checkpoint already covers them
+    let records = records.filter(|record| !matches!(record, Ok(record) if record.entry.sequence <= covered && covered > 0));
+    for record in records {
+        let result = record.and_then(|record| {
+            let LogRecord { position, entry } = &record;
+            let segment = segment_name(position.segment);
+            if entry.prev_checksum != previous {
+                return Err(LogError::BrokenChain {
+                    segment,
+                    offset: position.offset,
+                    expected: previous.clone(),
+                    found: entry.prev_checksum.clone(),
+                });
+            }
+            if entry.checksum != entry.compute_checksum() {
+                return Err(LogError::Tampered {
+                    segment,
+                    offset: position.offset,
+                    entity_id: entry.entity_id,
+                    version: entry.version,
+                });
+            }
+            Ok(record)
+        });
+        match result {
+            Ok(record) => {
+                previous = record.entry.checksum;
+                verification.last_sequence = record.entry.sequence;
+                verification.verified += 1;
+            }
+            Err(e) => {
+                verification.corruption = Some(e);
+                break;
+            }
+        }
+    }
+    verification
+}
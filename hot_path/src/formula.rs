@@ -0,0 +1,688 @@
+// Expression engine for Law formulas authored in the cold path.
+// A formula is either an assignment (`strength = strength * pow(0.5, time_elapsed / half_life)`)
+// or a bare expression; the law's variables bind to component fields and its constants to values.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::components::DecayComponent;
+
+/// A parsed, reusable formula
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    source: String,
+    target: Option<String>,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+    Conditional {
+        condition: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Pow,
+    Min,
+    Max,
+    Exp,
+    Ln,
+    Sqrt,
+    Abs,
+    Clamp,
+}
+
+impl Function {
+    fn lookup(name: &str) -> Option<Self> {
+        match name {
+            "pow" => Some(Self::Pow),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "exp" => Some(Self::Exp),
+            "ln" | "log" => Some(Self::Ln),
+            "sqrt" => Some(Self::Sqrt),
+            "abs" => Some(Self::Abs),
+            "clamp" => Some(Self::Clamp),
+            _ => None,
+        }
+    }
+
+    /// (min, max) number of arguments; min/max are variadic
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Pow => (2, 2),
+            Self::Min | Self::Max => (1, usize::MAX),
+            Self::Exp | Self::Ln | Self::Sqrt | Self::Abs => (1, 1),
+            Self::Clamp => (3, 3),
+        }
+    }
+}
+
+/// Variable and constant values a formula is evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    values: HashMap<String, f64>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: f64) -> &mut Self {
+        self.values.insert(name.into(), value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+
+    /// Bind a law's `constants` map; non-numeric constants (booleans aside) are skipped
+    pub fn bind_constants(&mut self, constants: &serde_json::Map<String, serde_json::Value>) -> &mut Self {
+        for (name, value) in constants {
+            let number = match value {
+                serde_json::Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+                other => other.as_f64(),
+            };
+            if let Some(number) = number {
+                self.values.insert(name.clone(), number);
+            }
+        }
+        self
+    }
+
+    /// Bind the named fields of a component
+    pub fn bind_fields(&mut self, component: &impl FormulaFields, variables: &[String]) -> &mut Self {
+        for name in variables {
+            if let Some(value) = component.get_field(name) {
+                self.values.insert(name.clone(), value);
+            }
+        }
+        self
+    }
+}
+
+/// Components whose fields laws can read and write by name
+pub trait FormulaFields {
+    fn get_field(&self, name: &str) -> Option<f64>;
+
+    /// Write a field back; returns false if the component has no such field
+    fn set_field(&mut self, name: &str, value: f64) -> bool;
+}
+
+impl FormulaFields for DecayComponent {
+    fn get_field(&self, name: &str) -> Option<f64> {
+        match name {
+            "strength" => Some(self.strength as f64),
+            "half_life" => Some(self.half_life as f64),
+            "last_update" => Some(self.last_update),
+            _ => None,
+        }
+    }
+
+    fn set_field(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "strength" => self.strength = value as f32,
+            "half_life" => self.half_life = value as f32,
+            "last_update" => self.last_update = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Formula {
+    /// Parse a formula string from the cold path
+    pub fn compile(source: &str) -> Result<Self, FormulaError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0, end: source.chars().count() };
+
+        // `name = expr` assigns to a component field; `==` is a comparison
+        let target = match (parser.peek(), parser.peek_at(1)) {
+            (Some(Token::Ident(name)), Some(Token::Assign)) if !is_keyword(name) => {
+                let name = name.clone();
+                parser.pos += 2;
+                Some(name)
+            }
+            _ => None,
+        };
+
+        let expr = parser.parse_expr()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error(format!("unexpected {}", token)));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            target,
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Field written by an assignment formula
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Every identifier the expression reads, in first-use order
+    pub fn referenced_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_names(&self.expr, &mut names);
+        names
+    }
+
+    pub fn evaluate(&self, scope: &Scope) -> Result<f64, FormulaError> {
+        eval(&self.expr, scope)
+    }
+
+    /// Evaluate and write the result into the target field of `component`
+    pub fn apply(&self, scope: &Scope, component: &mut impl FormulaFields) -> Result<f64, FormulaError> {
+        let value = self.evaluate(scope)?;
+        if let Some(target) = &self.target {
+            if !component.set_field(target, value) {
+                return Err(FormulaError::UnboundVariable(target.clone()));
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+fn collect_names(expr: &Expr, names: &mut Vec<String>) {
+    match expr {
+        Expr::Number(_) => {}
+        Expr::Variable(name) => {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        Expr::Negate(inner) | Expr::Not(inner) => collect_names(inner, names),
+        Expr::Binary(_, lhs, rhs) => {
+            collect_names(lhs, names);
+            collect_names(rhs, names);
+        }
+        Expr::Call(_, args) => args.iter().for_each(|arg| collect_names(arg, names)),
+        Expr::Conditional { condition, then, otherwise } => {
+            collect_names(condition, names);
+            collect_names(then, names);
+            collect_names(otherwise, names);
+        }
+    }
+}
+
+fn truthy(value: f64) -> bool {
+    value != 0.0
+}
+
+fn boolean(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+fn eval(expr: &Expr, scope: &Scope) -> Result<f64, FormulaError> {
+    let value = match expr {
+        Expr::Number(n) => *n,
+        Expr::Variable(name) => scope.get(name).ok_or_else(|| FormulaError::UnboundVariable(name.clone()))?,
+        Expr::Negate(inner) => -eval(inner, scope)?,
+        Expr::Not(inner) => boolean(!truthy(eval(inner, scope)?)),
+        Expr::Binary(BinaryOp::And, lhs, rhs) => boolean(truthy(eval(lhs, scope)?) && truthy(eval(rhs, scope)?)),
+        Expr::Binary(BinaryOp::Or, lhs, rhs) => boolean(truthy(eval(lhs, scope)?) || truthy(eval(rhs, scope)?)),
+        Expr::Binary(op, lhs, rhs) => {
+            let (a, b) = (eval(lhs, scope)?, eval(rhs, scope)?);
+            match op {
+                BinaryOp::Add => a + b,
+                BinaryOp::Sub => a - b,
+                BinaryOp::Mul => a * b,
+                BinaryOp::Div | BinaryOp::Rem if b == 0.0 => return Err(FormulaError::DivisionByZero),
+                BinaryOp::Div => a / b,
+                BinaryOp::Rem => a % b,
+                BinaryOp::Pow => a.powf(b),
+                BinaryOp::Lt => boolean(a < b),
+                BinaryOp::Le => boolean(a <= b),
+                BinaryOp::Gt => boolean(a > b),
+                BinaryOp::Ge => boolean(a >= b),
+                BinaryOp::Eq => boolean(a == b),
+                BinaryOp::Ne => boolean(a != b),
+                BinaryOp::And | BinaryOp::Or => unreachable!("short-circuited above"),
+            }
+        }
+        Expr::Call(function, args) => {
+            let args = args.iter().map(|arg| eval(arg, scope)).collect::<Result<Vec<f64>, _>>()?;
+            match function {
+                Function::Pow => args[0].powf(args[1]),
+                Function::Min => args.iter().copied().fold(f64::INFINITY, f64::min),
+                Function::Max => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                Function::Exp => args[0].exp(),
+                Function::Ln => args[0].ln(),
+                Function::Sqrt => args[0].sqrt(),
+                Function::Abs => args[0].abs(),
+                Function::Clamp => args[0].clamp(args[1].min(args[2]), args[1].max(args[2])),
+            }
+        }
+        Expr::Conditional { condition, then, otherwise } => {
+            if truthy(eval(condition, scope)?) {
+                eval(then, scope)?
+            } else {
+                eval(otherwise, scope)?
+            }
+        }
+    };
+
+    if value.is_nan() {
+        return Err(FormulaError::NotANumber);
+    }
+    Ok(value)
+}
+
+// --- Tokenizer ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(&'static str),
+    Assign,
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Op(op) => write!(f, "`{}`", op),
+            Token::Assign => write!(f, "`=`"),
+            Token::LParen => write!(f, "`(`"),
+            Token::RParen => write!(f, "`)`"),
+            Token::Comma => write!(f, "`,`"),
+        }
+    }
+}
+
+const KEYWORDS: [&str; 7] = ["if", "else", "and", "or", "not", "true", "false"];
+
+fn is_keyword(name: &str) -> bool {
+    KEYWORDS.contains(&name)
+}
+
+/// Tokens, each with the character offset it starts at
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, FormulaError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Scientific notation: 1e-3, 2.5E6
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text.parse::<f64>().map_err(|_| FormulaError::Parse {
+                    position: start,
+                    message: format!("invalid number `{}`", text),
+                })?;
+                tokens.push((Token::Number(number), start));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+            }
+            '(' => {
+                tokens.push((Token::LParen, i));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, i));
+                i += 1;
+            }
+            ',' => {
+                tokens.push((Token::Comma, i));
+                i += 1;
+            }
+            _ => {
+                let (token, width) = match (c, next) {
+                    ('*', Some('*')) => (Token::Op("**"), 2),
+                    ('<', Some('=')) => (Token::Op("<="), 2),
+                    ('>', Some('=')) => (Token::Op(">="), 2),
+                    ('=', Some('=')) => (Token::Op("=="), 2),
+                    ('!', Some('=')) => (Token::Op("!="), 2),
+                    ('&', Some('&')) => (Token::Op("and"), 2),
+                    ('|', Some('|')) => (Token::Op("or"), 2),
+                    ('=', _) => (Token::Assign, 1),
+                    ('+', _) => (Token::Op("+"), 1),
+                    ('-', _) => (Token::Op("-"), 1),
+                    ('*', _) => (Token::Op("*"), 1),
+                    ('/', _) => (Token::Op("/"), 1),
+                    ('%', _) => (Token::Op("%"), 1),
+                    ('^', _) => (Token::Op("^"), 1),
+                    ('<', _) => (Token::Op("<"), 1),
+                    ('>', _) => (Token::Op(">"), 1),
+                    ('!', _) => (Token::Op("not"), 1),
+                    _ => {
+                        return Err(FormulaError::Parse {
+                            position: i,
+                            message: format!("unexpected character `{}`", c),
+                        })
+                    }
+                };
+                tokens.push((token, i));
+                i += width;
+            }
+        }
+    }
+
+    // Word operators share the symbolic operator tokens
+    Ok(tokens
+        .into_iter()
+        .map(|(token, offset)| match token {
+            Token::Ident(name) if name == "and" => (Token::Op("and"), offset),
+            Token::Ident(name) if name == "or" => (Token::Op("or"), offset),
+            Token::Ident(name) if name == "not" => (Token::Op("not"), offset),
+            other => (other, offset),
+        })
+        .collect())
+}
+
+// --- Parser (precedence climbing, lowest first) ---
+// conditional: or_expr ["if" or_expr "else" conditional]
+// or_expr:     and_expr ("or" and_expr)*
+// and_expr:    not_expr ("and" not_expr)*
+// not_expr:    "not" not_expr | comparison
+// comparison:  additive [("<"|"<="|">"|">="|"=="|"!=") additive]
+// additive:    term (("+"|"-") term)*
+// term:        unary (("*"|"/"|"%") unary)*
+// unary:       "-" unary | power
+// power:       primary [("^"|"**") unary]
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Length of the source in characters, where "unexpected end" errors point
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Error at the next token
+    fn error(&self, message: impl Into<String>) -> FormulaError {
+        self.error_at(self.pos, message)
+    }
+
+    /// Error at the token with index `pos`, reported by its character offset in the source
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> FormulaError {
+        FormulaError::Parse {
+            position: self.tokens.get(pos).map_or(self.end, |(_, offset)| *offset),
+            message: message.into(),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, FormulaError> {
+        let value = self.parse_or()?;
+        if !self.eat_keyword("if") {
+            return Ok(value);
+        }
+        let condition = self.parse_or()?;
+        if !self.eat_keyword("else") {
+            return Err(self.error("expected `else` after conditional"));
+        }
+        let otherwise = self.parse_expr()?;
+        Ok(Expr::Conditional {
+            condition: Box::new(condition),
+            then: Box::new(value),
+            otherwise: Box::new(otherwise),
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, FormulaError> {
+        let mut lhs = self.parse_and()?;
+        while self.eat_op(&["or"]).is_some() {
+            let rhs = self.parse_and()?;
+            lhs = Expr::Binary(BinaryOp::Or, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, FormulaError> {
+        let mut lhs = self.parse_not()?;
+        while self.eat_op(&["and"]).is_some() {
+            let rhs = self.parse_not()?;
+            lhs = Expr::Binary(BinaryOp::And, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, FormulaError> {
+        if self.eat_op(&["not"]).is_some() {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, FormulaError> {
+        let lhs = self.parse_additive()?;
+        let op = match self.eat_op(&["<", "<=", ">", ">=", "==", "!="]) {
+            Some("<") => BinaryOp::Lt,
+            Some("<=") => BinaryOp::Le,
+            Some(">") => BinaryOp::Gt,
+            Some(">=") => BinaryOp::Ge,
+            Some("==") => BinaryOp::Eq,
+            Some(_) => BinaryOp::Ne,
+            None => return Ok(lhs),
+        };
+        let rhs = self.parse_additive()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, FormulaError> {
+        let mut lhs = self.parse_term()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let rhs = self.parse_term()?;
+            let op = if op == "+" { BinaryOp::Add } else { BinaryOp::Sub };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, FormulaError> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let rhs = self.parse_unary()?;
+            let op = match op {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.eat_op(&["-"]).is_some() {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Result<Expr, FormulaError> {
+        let base = self.parse_primary()?;
+        if self.eat_op(&["^", "**"]).is_some() {
+            // Right-associative: 2 ^ 3 ^ 2 == 2 ^ 9
+            let exponent = self.parse_unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, FormulaError> {
+        let start = self.pos;
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::LParen) => {
+                let inner = self.parse_expr()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(self.error("expected `)`")),
+                }
+            }
+            Some(Token::Ident(name)) if name == "true" => Ok(Expr::Number(1.0)),
+            Some(Token::Ident(name)) if name == "false" => Ok(Expr::Number(0.0)),
+            Some(Token::Ident(name)) if is_keyword(&name) => Err(self.error_at(start, format!("unexpected `{}`", name))),
+            Some(Token::Ident(name)) => {
+                if self.peek() != Some(&Token::LParen) {
+                    return Ok(Expr::Variable(name));
+                }
+                self.pos += 1;
+                let function = Function::lookup(&name).ok_or_else(|| FormulaError::UnknownFunction(name.clone()))?;
+                let args = self.parse_args()?;
+                let (min, max) = function.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(FormulaError::Arity {
+                        function: name,
+                        found: args.len(),
+                    });
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Some(token) => Err(self.error_at(start, format!("unexpected {}", token))),
+            None => Err(self.error_at(start, "unexpected end of formula")),
+        }
+    }
+
+    fn parse_args(&mut self) -> Result<Vec<Expr>, FormulaError> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expr()?);
+            match self.peek() {
+                Some(Token::Comma) => self.pos += 1,
+                Some(Token::RParen) => {
+                    self.pos += 1;
+                    return Ok(args);
+                }
+                _ => return Err(self.error("expected `,` or `)` in argument list")),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FormulaError {
+    /// `position` is the character offset into the formula source
+    #[error("Parse error at position {position}: {message}")]
+    Parse { position: usize, message: String },
+
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+
+    #[error("Wrong number of arguments to {function}: {found}")]
+    Arity { function: String, found: usize },
+
+    #[error("Unbound variable: {0}")]
+    UnboundVariable(String),
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("Formula evaluated to NaN")]
+    NotANumber,
+}
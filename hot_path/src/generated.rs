@@ -1,29 +1,36 @@
 // Generated types for Familiar Engine Hot Path
-// DO NOT EDIT - regenerated on every build
+// DO NOT EDIT - regenerated on every build from assets/sample_schema.json
 
 use serde::{Serialize, Deserialize};
+use async_graphql::{Enum, InputObject, SimpleObject};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
-// Core enums from cold path
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum AccessScope {
-    View,
-    Edit,
-    Admin,
-}
+// Schema identity - checked when loading persisted data and law files
+pub const SCHEMA_VERSION: &str = "1.0.0";
+pub const SCHEMA_GENERATED_BY: &str = "familiar-cold-path";
+pub const SCHEMA_HASH: &str = "a388a198f293ee70";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+// Enums from cold path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility {
+    #[default]
     Private,
     Household,
     Org,
     Public,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessScope {
+    View,
+    Edit,
+    Admin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
 #[serde(rename_all = "kebab-case")]
 pub enum Cardinality {
     Actor,
@@ -33,8 +40,39 @@ pub enum Cardinality {
     Target,
 }
 
-// Base entity with versioning and soft deletion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataType {
+    Str,
+    Float,
+    Int,
+    Datetime,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum LawTrigger {
+    OnObservation,
+    OnTick,
+    OnAffinityMatch,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetadataValue {
+    Boolean(bool),
+    Integer(i64),
+    Number(f64),
+    DateTime(DateTime<Utc>),
+    String(String),
+}
+
+async_graphql::scalar!(MetadataValue);
+
+// Models from cold path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "BaseEntityInput")]
 pub struct BaseEntity {
     #[serde(default)]
     pub id: Option<Uuid>,
@@ -58,20 +96,38 @@ pub struct BaseEntity {
     pub security_level: i64,
     #[serde(default)]
     pub access_scope: Vec<AccessScope>,
-    #[serde(default = "default_version")]
+    #[serde(default = "default_base_entity_version")]
     pub version: i64,
     #[serde(default)]
     pub parent_version: Option<i64>,
 }
 
-// Domain entities from cold path
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "BaseComponentInput")]
+pub struct BaseComponent {
+    #[serde(default)]
+    pub id: Option<Uuid>,
+    pub name: String,
+    #[serde(default)]
+    pub metadata: Vec<Metadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "MetadataInput")]
+pub struct Metadata {
+    pub key: String,
+    pub value: MetadataValue,
+    pub r#type: MetadataType,
+}
+
+/// Individual memory moment with explicit authorship and thread binding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "MomentRecord", input_name = "MomentRecordInput")]
 pub struct Moment {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
-    
-    // Moment-specific fields
     pub thread_id: Uuid,
     pub author_id: Uuid,
     #[serde(default)]
@@ -82,158 +138,160 @@ pub struct Moment {
     pub cardinality: Option<Cardinality>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct BindingPoint {
-    pub thread_id: Uuid,
-    pub moment_id: Uuid,
-    pub cardinality: Cardinality,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Cross-thread connection as a composite of binding points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "BindingRecord", input_name = "BindingRecordInput")]
 pub struct Binding {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
-    
-    // Binding-specific fields
     #[serde(default)]
     pub points: Vec<BindingPoint>,
     #[serde(default)]
-    pub thread_ids: Vec<Uuid>, // Query optimization index
+    pub thread_ids: Vec<Uuid>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A binding point representing thread ↔ moment ↔ role (1-to-1-to-1 mapping)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "BindingPointInput")]
+pub struct BindingPoint {
+    pub thread_id: Uuid,
+    pub moment_id: Uuid,
+    pub cardinality: Cardinality,
+}
+
+/// Memory thread organizing related moments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "ThreadRecord", input_name = "ThreadRecordInput")]
 pub struct Thread {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Bond {
+/// Temporal memory filament
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "FilamentRecord", input_name = "FilamentRecordInput")]
+pub struct Filament {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
-    
-    // Bond-specific fields
-    pub affinity_score: f64,
-    pub bond_strength: f64,
-    #[serde(default)]
-    pub component_context: Vec<Uuid>,
-    #[serde(default)]
-    pub thread_ids: Vec<Uuid>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Filament {
+/// Recurring memory pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "MotifRecord", input_name = "MotifRecordInput")]
+pub struct Motif {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Motif {
+/// Cross-thread affinity bond
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(name = "BondRecord", input_name = "BondRecordInput")]
+pub struct Bond {
     // BaseEntity fields (flattened)
     #[serde(flatten)]
+    #[graphql(flatten)]
     pub base: BaseEntity,
+    #[serde(default)]
+    pub thread_ids: Vec<Uuid>,
+    pub affinity_score: f64,
+    pub bond_strength: f64,
+    #[serde(default)]
+    pub component_context: Vec<Uuid>,
 }
 
-// Law system from cold path - with mathematical specifications
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "LawInput")]
 pub struct Law {
-    pub name: String,  // e.g. 'decay', 'resonance', 'binding'
+    pub name: String,
     pub trigger: LawTrigger,
-    pub applies_to: Vec<String>,  // e.g. ['filament', 'moment']
-    
-    // Mathematical specification - the "physics" of this law
-    pub formula: String,  // Mathematical expression from cold path
-    pub variables: Vec<String>,  // Component fields this law reads/writes
-    pub constants: serde_json::Map<String, serde_json::Value>,  // Universal constants
+    pub applies_to: Vec<String>,
+    pub formula: String,
+    pub variables: Vec<String>,
+    #[graphql(skip)]
+    pub constants: serde_json::Map<String, serde_json::Value>,
     #[serde(default)]
-    pub constraints: Option<serde_json::Map<String, serde_json::Value>>,  // Min/max bounds
+    #[graphql(skip)]
+    pub constraints: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum LawTrigger {
-    OnObservation,
-    OnTick,
-    OnAffinityMatch,
-}
+/// Models that flatten BaseEntity
+pub const SCHEMA_ENTITY_MODELS: &[&str] = &["Moment", "Binding", "Thread", "Filament", "Motif", "Bond"];
 
-// Helper functions
-fn default_version() -> i64 {
-    1
+/// Implemented by every model that flattens BaseEntity
+pub trait SchemaEntity {
+    fn base(&self) -> &BaseEntity;
+    fn base_mut(&mut self) -> &mut BaseEntity;
 }
 
-impl Default for Visibility {
-    fn default() -> Self {
-        Self::Private
+impl SchemaEntity for Moment {
+    fn base(&self) -> &BaseEntity {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
     }
 }
 
-// Implementations for generated types
-impl Moment {
-    /// Check if this moment was created recently
-    pub fn is_recent(&self) -> bool {
-        if let Some(created_at) = &self.base.created_at {
-            let now = chrono::Utc::now();
-            let duration = now.signed_duration_since(*created_at);
-            duration.num_minutes() < 30 // Recent if created within 30 minutes
-        } else {
-            false
-        }
+impl SchemaEntity for Binding {
+    fn base(&self) -> &BaseEntity {
+        &self.base
     }
-    
-    /// Get the thread UUID (guaranteed present in Moment)
-    pub fn thread_uuid(&self) -> Uuid {
-        self.thread_id
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
+    }
+}
+
+impl SchemaEntity for Thread {
+    fn base(&self) -> &BaseEntity {
+        &self.base
     }
-    
-    /// Get the author UUID (guaranteed present in Moment)
-    pub fn author_uuid(&self) -> Uuid {
-        self.author_id
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
     }
 }
 
-impl Binding {
-    /// Get all unique thread IDs from binding points
-    pub fn get_thread_ids(&self) -> Vec<Uuid> {
-        let mut thread_ids: Vec<Uuid> = self.points
-            .iter()
-            .map(|point| point.thread_id)
-            .collect();
-        thread_ids.sort();
-        thread_ids.dedup();
-        thread_ids
+impl SchemaEntity for Filament {
+    fn base(&self) -> &BaseEntity {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
     }
-    
-    /// Get all unique moment IDs from binding points  
-    pub fn get_moment_ids(&self) -> Vec<Uuid> {
-        let mut moment_ids: Vec<Uuid> = self.points
-            .iter()
-            .map(|point| point.moment_id)
-            .collect();
-        moment_ids.sort();
-        moment_ids.dedup();
-        moment_ids
+}
+
+impl SchemaEntity for Motif {
+    fn base(&self) -> &BaseEntity {
+        &self.base
     }
-    
-    /// Check if this binding involves a specific thread
-    pub fn involves_thread(&self, thread_id: Uuid) -> bool {
-        // Check both the semantic points and the optimization index
-        self.points.iter().any(|point| point.thread_id == thread_id) ||
-        self.thread_ids.contains(&thread_id)
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
     }
 }
 
-impl BindingPoint {
-    /// Create a new binding point
-    pub fn new(thread_id: Uuid, moment_id: Uuid, cardinality: Cardinality) -> Self {
-        Self {
-            thread_id,
-            moment_id,
-            cardinality,
-        }
+impl SchemaEntity for Bond {
+    fn base(&self) -> &BaseEntity {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseEntity {
+        &mut self.base
     }
 }
+
+// Serde defaults declared in the schema
+fn default_base_entity_version() -> i64 {
+    1
+}
@@ -1,26 +1,44 @@
 use async_graphql::{
-    EmptySubscription, Object, Schema, Context, SimpleObject,
+    Data, ErrorExtensions, InputObject, Object, Schema, Context, ServerError, SimpleObject, Subscription,
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::connection::{Connection, CursorType, Edge, OpaqueCursor};
+use async_graphql::futures_util::{stream, Stream, StreamExt};
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::{
-    response::{Html, IntoResponse},
-    routing::get,
+    extract::WebSocketUpgrade,
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
     Router,
     Extension,
 };
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use crossbeam_channel::Sender;
-use crate::common::GqlCommand;
-use hecs::World;
-use std::sync::{Arc, Mutex};
+use crate::common::{CommandError, CommandOutcome, GqlCommand, Reply};
+use crate::changes::{Change, ChangeSet};
+use crate::components::Visibility;
+use crate::graphql_types::{Entity, Thread};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::security::{ApiKeys, Principal, ScopedSnapshot};
+use crate::snapshot::{EntitySnapshot, SnapshotStore, TickUpdate};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// How long a mutation waits for the memory system to apply it
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(SimpleObject)]
 pub struct EntityInfo {
     pub id: String,
     pub entity_type: String,
     pub display_text: String,
-    pub created_at: String,
+    pub created_at: Option<DateTime<Utc>>,
     pub strength: Option<f32>,
 }
 
@@ -30,6 +48,8 @@ pub struct MemoryStats {
     pub entities_by_type: Vec<TypeCount>,
     pub active_laws: usize,
     pub memory_usage_mb: f64,
+    /// Simulation tick the answer was read from
+    pub tick: u64,
 }
 
 #[derive(SimpleObject)]
@@ -63,6 +83,160 @@ pub struct DecayInfo {
     pub time_since_update: f64,
 }
 
+/// Who the request acts for: the principal of the API key it authenticated with
+fn principal(ctx: &Context<'_>) -> Arc<Principal> {
+    ctx.data_unchecked::<Arc<Principal>>().clone()
+}
+
+/// The snapshot published by the memory system for the current tick, as the request's principal sees it
+fn snapshot(ctx: &Context<'_>) -> ScopedSnapshot {
+    ScopedSnapshot::new(ctx.data_unchecked::<Arc<SnapshotStore>>().load(), principal(ctx))
+}
+
+impl From<&EntitySnapshot> for EntityInfo {
+    fn from(snapshot: &EntitySnapshot) -> Self {
+        Self {
+            id: snapshot.external_id(),
+            entity_type: snapshot.entity_type.clone(),
+            display_text: snapshot.display_text.clone(),
+            created_at: snapshot.base.as_ref().and_then(|base| base.created_at),
+            strength: snapshot.strength(),
+        }
+    }
+}
+
+/// Reads through GraphQL count as observations: the memory system runs
+/// `on_observation` laws on these entities lazily, on its next pass.
+pub(crate) fn observe(ctx: &Context<'_>, entities: Vec<hecs::Entity>) {
+    if entities.is_empty() {
+        return;
+    }
+    if let Ok(sender) = ctx.data::<Sender<GqlCommand>>() {
+        let _ = sender.send(GqlCommand::ObserveEntities { entities });
+    }
+}
+
+/// Filter shared by the paginated entity queries; every set field must match
+#[derive(InputObject, Default)]
+pub struct EntityFilter {
+    pub entity_type: Option<String>,
+    /// Entities carrying this tag
+    pub tag: Option<String>,
+    pub visibility: Option<Visibility>,
+    pub owner_id: Option<Uuid>,
+    pub min_strength: Option<f64>,
+    pub max_strength: Option<f64>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// `true` for soft-deleted entities only; live entities only by default
+    pub deleted: Option<bool>,
+}
+
+impl EntityFilter {
+    pub fn matches(&self, entity: &EntitySnapshot) -> bool {
+        let base = entity.base.as_ref();
+        let strength = entity.strength().map(f64::from);
+        let created_at = base.and_then(|base| base.created_at);
+
+        self.entity_type.as_ref().is_none_or(|wanted| *wanted == entity.entity_type)
+            && self.tag.as_ref().is_none_or(|tag| base.is_some_and(|base| base.tags.contains(tag)))
+            && self.visibility.is_none_or(|visibility| base.is_some_and(|base| base.visibility == visibility))
+            && self.owner_id.is_none_or(|owner| base.is_some_and(|base| base.owner_id == owner))
+            && self.min_strength.is_none_or(|min| strength.is_some_and(|s| s >= min))
+            && self.max_strength.is_none_or(|max| strength.is_some_and(|s| s <= max))
+            && self.created_after.is_none_or(|after| created_at.is_some_and(|at| at > after))
+            && self.created_before.is_none_or(|before| created_at.is_some_and(|at| at < before))
+            && entity.is_deleted() == self.deleted.unwrap_or(false)
+    }
+}
+
+/// Upper bound on `first`, whatever the client asks for
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Position of an entity in a result ordering: `rank`, then id to break ties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageKey {
+    rank: f64,
+    id: String,
+}
+
+impl PageKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank.total_cmp(&other.rank).then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// Deterministic orderings for paginated queries
+#[derive(Clone, Copy)]
+enum PageOrder {
+    /// Oldest first
+    Created,
+    /// Strongest first
+    Strength,
+}
+
+impl PageOrder {
+    fn key(self, entity: &EntitySnapshot) -> PageKey {
+        let rank = match self {
+            PageOrder::Created => entity
+                .base
+                .as_ref()
+                .and_then(|base| base.created_at)
+                .map_or(0.0, |at| at.timestamp_millis() as f64),
+            PageOrder::Strength => -entity.strength().map_or(0.0, f64::from),
+        };
+        PageKey { rank, id: entity.external_id() }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ConnectionTotals {
+    /// Matches across all pages
+    pub total_count: usize,
+}
+
+pub type EntityConnection = Connection<OpaqueCursor<PageKey>, EntityInfo, ConnectionTotals>;
+
+/// Order `matches`, cut the page after the `after` cursor and report it as observed
+fn paginate(
+    ctx: &Context<'_>,
+    matches: Vec<&EntitySnapshot>,
+    order: PageOrder,
+    first: Option<i32>,
+    after: Option<String>,
+    default_first: usize,
+) -> async_graphql::Result<EntityConnection> {
+    let first = match first {
+        Some(first) if first < 0 => {
+            return Err(CommandError::Validation("`first` must not be negative".to_string()).extend())
+        }
+        Some(first) => (first as usize).min(MAX_PAGE_SIZE),
+        None => default_first,
+    };
+    let after = after
+        .map(|cursor| OpaqueCursor::<PageKey>::decode_cursor(&cursor))
+        .transpose()
+        .map_err(|_| CommandError::Validation("invalid `after` cursor".to_string()).extend())?;
+
+    let mut ordered: Vec<(PageKey, &EntitySnapshot)> = matches.into_iter().map(|entity| (order.key(entity), entity)).collect();
+    ordered.sort_by(|a, b| a.0.cmp(&b.0));
+    let total_count = ordered.len();
+
+    // Keys rather than offsets, so a page stays put when earlier entities come or go
+    let start = after.map_or(0, |after| ordered.partition_point(|(key, _)| key.cmp(&after).is_le()));
+    let end = (start + first).min(total_count);
+    let page = &ordered[start..end];
+
+    observe(ctx, page.iter().map(|(_, entity)| entity.entity).collect());
+
+    let mut connection = Connection::with_additional_fields(start > 0, end < total_count, ConnectionTotals { total_count });
+    connection.edges.extend(
+        page.iter()
+            .map(|(key, entity)| Edge::new(OpaqueCursor(key.clone()), EntityInfo::from(*entity))),
+    );
+    Ok(connection)
+}
+
 /// Root query object for the Familiar Memory API.
 /// Provides read-only access to the memory simulation data.
 pub struct QueryRoot;
@@ -83,17 +257,14 @@ impl QueryRoot {
 
     /// Get comprehensive memory system statistics
     async fn memory_stats(&self, ctx: &Context<'_>) -> MemoryStats {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
+        let snapshot = snapshot(ctx);
         
         let mut type_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
-        let mut total = 0;
         let mut law_count = 0;
 
-        for (_entity, entity_type) in world.query::<&crate::components::EntityType>().iter() {
-            total += 1;
-            *type_counts.entry(entity_type.0.clone()).or_insert(0) += 1;
-            if entity_type.0 == "law" {
+        for entity in snapshot.entities() {
+            *type_counts.entry(entity.entity_type.clone()).or_insert(0) += 1;
+            if entity.entity_type == "law" {
                 law_count += 1;
             }
         }
@@ -103,232 +274,237 @@ impl QueryRoot {
             .collect();
 
         MemoryStats {
-            total_entities: total,
+            total_entities: snapshot.len(),
             entities_by_type,
             active_laws: law_count,
             memory_usage_mb: 0.0, // TODO: Calculate actual memory usage
+            tick: snapshot.tick(),
         }
     }
 
-    /// Query entities by type with optional limit
-    async fn entities_by_type(&self, ctx: &Context<'_>, entity_type: String, limit: Option<i32>) -> Vec<EntityInfo> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
-        
-        let mut entities = Vec::new();
-        let limit = limit.unwrap_or(50) as usize;
-
-        for (entity, (etype, display_text)) in world.query::<(&crate::components::EntityType, &crate::components::DisplayText)>().iter() {
-            if etype.0 == entity_type && entities.len() < limit {
-                let strength = world.get::<&crate::components::DecayComponent>(entity)
-                    .map(|decay| decay.strength)
-                    .ok();
-
-                entities.push(EntityInfo {
-                    id: format!("{:?}", entity),
-                    entity_type: etype.0.clone(),
-                    display_text: display_text.0.clone(),
-                    created_at: "2024-01-01T00:00:00Z".to_string(), // TODO: Get real timestamp
-                    strength,
-                });
-            }
-        }
+    /// Query entities by type, oldest first, as a Relay connection.
+    ///
+    /// Example:
+    /// ```graphql
+    /// {
+    ///   entitiesByType(entityType: "moment", first: 10, filter: { tag: "family" }) {
+    ///     totalCount
+    ///     pageInfo { hasNextPage endCursor }
+    ///     edges { cursor node { id displayText strength } }
+    ///   }
+    /// }
+    /// ```
+    async fn entities_by_type(
+        &self,
+        ctx: &Context<'_>,
+        entity_type: String,
+        first: Option<i32>,
+        after: Option<String>,
+        filter: Option<EntityFilter>,
+    ) -> async_graphql::Result<EntityConnection> {
+        let snapshot = snapshot(ctx);
+        let filter = filter.unwrap_or_default();
+
+        let matches: Vec<&EntitySnapshot> = snapshot.of_type(&entity_type).filter(|entity| filter.matches(entity)).collect();
+        paginate(ctx, matches, PageOrder::Created, first, after, 50)
+    }
 
-        entities
+    /// Look up any entity by UUID, typed by what it is.
+    ///
+    /// Example:
+    /// ```graphql
+    /// {
+    ///   entity(id: "6f1c2d3e-8a4b-4c5d-9e6f-7a8b9c0d1e2f") {
+    ///     id entityType createdAt tags
+    ///     ... on Moment { thread { name } bindings { points { cardinality } threads { name } } }
+    ///   }
+    /// }
+    /// ```
+    async fn entity(&self, ctx: &Context<'_>, id: Uuid) -> Option<Entity> {
+        let snapshot = snapshot(ctx);
+        let entity = snapshot.get(&id)?;
+        observe(ctx, vec![entity.entity]);
+        Entity::new(&snapshot, entity)
+    }
+
+    /// Live threads, oldest first.
+    ///
+    /// Example:
+    /// ```graphql
+    /// { threads { id name threadType createdAt moments { displayText strength } bonds { threads { name } } } }
+    /// ```
+    async fn threads(&self, ctx: &Context<'_>) -> Vec<Thread> {
+        let snapshot = snapshot(ctx);
+        let live: Vec<&EntitySnapshot> = snapshot.of_type("thread").filter(|thread| !thread.is_deleted()).collect();
+        observe(ctx, live.iter().map(|thread| thread.entity).collect());
+        live.into_iter().filter_map(|thread| Thread::new(&snapshot, thread)).collect()
     }
 
     /// Get all threads with their associated moments
     async fn threads_with_moments(&self, ctx: &Context<'_>) -> Vec<ThreadWithMoments> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
+        let snapshot = snapshot(ctx);
         
         let mut threads = Vec::new();
+        let mut observed = Vec::new();
 
         // Find all threads
-        for (_entity, (etype, display_text, thread_type, thread_id)) in world.query::<(
-            &crate::components::EntityType, 
-            &crate::components::DisplayText,
-            &crate::components::ThreadType,
-            &crate::components::ThreadId
-        )>().iter() {
-            if etype.0 == "thread" {
-                let mut moments = Vec::new();
-                
-                // Find moments for this thread
-                for (moment_entity, (moment_type, moment_text, moment_thread_id)) in world.query::<(
-                    &crate::components::EntityType,
-                    &crate::components::DisplayText, 
-                    &crate::components::ThreadId
-                )>().iter() {
-                    if moment_type.0 == "moment" && moment_thread_id.0 == thread_id.0 {
-                        let strength = world.get::<&crate::components::DecayComponent>(moment_entity)
-                            .map(|decay| decay.strength)
-                            .ok();
-
-                        moments.push(EntityInfo {
-                            id: format!("{:?}", moment_entity),
-                            entity_type: "moment".to_string(),
-                            display_text: moment_text.0.clone(),
-                            created_at: "2024-01-01T00:00:00Z".to_string(),
-                            strength,
-                        });
-                    }
-                }
+        for thread in snapshot.of_type("thread") {
+            let (Some(thread_id), Some(thread_type)) = (&thread.thread_id, &thread.thread_type) else {
+                continue;
+            };
+            observed.push(thread.entity);
 
-                threads.push(ThreadWithMoments {
-                    thread_id: thread_id.0.clone(),
-                    thread_name: display_text.0.clone(),
-                    thread_type: thread_type.0.clone(),
-                    moments,
-                });
-            }
+            // Find moments for this thread
+            let moments: Vec<EntityInfo> = snapshot
+                .of_type("moment")
+                .filter(|moment| moment.thread_id.as_ref() == Some(thread_id))
+                .inspect(|moment| observed.push(moment.entity))
+                .map(EntityInfo::from)
+                .collect();
+
+            threads.push(ThreadWithMoments {
+                thread_id: thread_id.clone(),
+                thread_name: thread.display_text.clone(),
+                thread_type: thread_type.clone(),
+                moments,
+            });
         }
 
+        observe(ctx, observed);
         threads
     }
 
-    /// Search entities by text content
-    async fn search_entities(&self, ctx: &Context<'_>, query: String, limit: Option<i32>) -> Vec<EntityInfo> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
-        
-        let mut entities = Vec::new();
-        let limit = limit.unwrap_or(20) as usize;
+    /// Search entities by text content, oldest first, as a Relay connection
+    async fn search_entities(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<i32>,
+        after: Option<String>,
+        filter: Option<EntityFilter>,
+    ) -> async_graphql::Result<EntityConnection> {
+        let snapshot = snapshot(ctx);
+        let filter = filter.unwrap_or_default();
         let query_lower = query.to_lowercase();
 
-        for (entity, (etype, display_text)) in world.query::<(&crate::components::EntityType, &crate::components::DisplayText)>().iter() {
-            if display_text.0.to_lowercase().contains(&query_lower) && entities.len() < limit {
-                let strength = world.get::<&crate::components::DecayComponent>(entity)
-                    .map(|decay| decay.strength)
-                    .ok();
-
-                entities.push(EntityInfo {
-                    id: format!("{:?}", entity),
-                    entity_type: etype.0.clone(),
-                    display_text: display_text.0.clone(),
-                    created_at: "2024-01-01T00:00:00Z".to_string(),
-                    strength,
-                });
-            }
-        }
-
-        entities
+        let matches: Vec<&EntitySnapshot> = snapshot
+            .entities()
+            .filter(|entity| entity.display_text.to_lowercase().contains(&query_lower) && filter.matches(entity))
+            .collect();
+        paginate(ctx, matches, PageOrder::Created, first, after, 20)
     }
 
-    /// Get entities with strength above threshold (time-based filter)
-    async fn strong_entities(&self, ctx: &Context<'_>, min_strength: f64, limit: Option<i32>) -> Vec<EntityInfo> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
-        
-        let mut entities = Vec::new();
-        let limit = limit.unwrap_or(20) as usize;
-
-        for (entity, (etype, display_text, decay)) in world.query::<(
-            &crate::components::EntityType, 
-            &crate::components::DisplayText,
-            &crate::components::DecayComponent
-        )>().iter() {
-            if decay.strength >= min_strength as f32 && entities.len() < limit {
-                entities.push(EntityInfo {
-                    id: format!("{:?}", entity),
-                    entity_type: etype.0.clone(),
-                    display_text: display_text.0.clone(),
-                    created_at: "2024-01-01T00:00:00Z".to_string(),
-                    strength: Some(decay.strength),
-                });
-            }
-        }
+    /// Get entities with strength above threshold, strongest first, as a Relay connection
+    async fn strong_entities(
+        &self,
+        ctx: &Context<'_>,
+        min_strength: f64,
+        first: Option<i32>,
+        after: Option<String>,
+        filter: Option<EntityFilter>,
+    ) -> async_graphql::Result<EntityConnection> {
+        let snapshot = snapshot(ctx);
+        let filter = filter.unwrap_or_default();
 
-        entities.sort_by(|a, b| b.strength.partial_cmp(&a.strength).unwrap_or(std::cmp::Ordering::Equal));
-        entities
+        let matches: Vec<&EntitySnapshot> = snapshot
+            .entities()
+            .filter(|entity| entity.strength().is_some_and(|strength| strength >= min_strength as f32) && filter.matches(entity))
+            .collect();
+        paginate(ctx, matches, PageOrder::Strength, first, after, 20)
     }
 
     /// 🐛 DEBUG: Dump entire world state for inspection
     async fn debug_world_dump(&self, ctx: &Context<'_>) -> Vec<EntityInfo> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
-        
-        let mut entities = Vec::new();
-
-        for (entity, (etype, display_text)) in world.query::<(&crate::components::EntityType, &crate::components::DisplayText)>().iter() {
-            let strength = world.get::<&crate::components::DecayComponent>(entity)
-                .map(|decay| decay.strength)
-                .ok();
-
-            entities.push(EntityInfo {
-                id: format!("{:?}", entity),
-                entity_type: etype.0.clone(),
-                display_text: display_text.0.clone(),
-                created_at: "2024-01-01T00:00:00Z".to_string(),
-                strength,
-            });
-        }
-
-        entities
+        snapshot(ctx).entities().map(EntityInfo::from).collect()
     }
 
     /// 🐛 DEBUG: Get raw entity count by component type
     async fn debug_component_stats(&self, ctx: &Context<'_>) -> Vec<ComponentStat> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
-        
-        let mut stats = Vec::new();
-        
-        // Count entities with different component combinations
-        let mut entity_type_count = 0;
-        let mut display_text_count = 0;
-        let mut decay_component_count = 0;
-        
-        for (_entity, _) in world.query::<&crate::components::EntityType>().iter() {
-            entity_type_count += 1;
-        }
-        
-        for (_entity, _) in world.query::<&crate::components::DisplayText>().iter() {
-            display_text_count += 1;
-        }
-        
-        for (_entity, _) in world.query::<&crate::components::DecayComponent>().iter() {
-            decay_component_count += 1;
-        }
-        
-        stats.push(ComponentStat { component_name: "EntityType".to_string(), count: entity_type_count });
-        stats.push(ComponentStat { component_name: "DisplayText".to_string(), count: display_text_count });
-        stats.push(ComponentStat { component_name: "DecayComponent".to_string(), count: decay_component_count });
-        
-        stats
+        let snapshot = snapshot(ctx);
+        let count = |has: fn(&EntitySnapshot) -> bool| snapshot.entities().filter(|entity| has(entity)).count();
+
+        vec![
+            ComponentStat { component_name: "EntityType".to_string(), count: snapshot.len() },
+            ComponentStat { component_name: "DisplayText".to_string(), count: count(|e| !e.display_text.is_empty()) },
+            ComponentStat { component_name: "DecayComponent".to_string(), count: count(|e| e.decay.is_some()) },
+            ComponentStat { component_name: "BaseEntity".to_string(), count: count(|e| e.base.is_some()) },
+            ComponentStat { component_name: "Moment".to_string(), count: count(|e| e.moment.is_some()) },
+            ComponentStat { component_name: "Bond".to_string(), count: count(|e| e.bond.is_some()) },
+            ComponentStat { component_name: "Binding".to_string(), count: count(|e| e.binding.is_some()) },
+            ComponentStat { component_name: "ThreadId".to_string(), count: count(|e| e.thread_id.is_some()) },
+            ComponentStat { component_name: "TemporalPosition".to_string(), count: count(|e| e.temporal_position.is_some()) },
+            ComponentStat { component_name: "MemoryLayer".to_string(), count: count(|e| e.memory_layer.is_some()) },
+        ]
     }
 
     /// 🐛 DEBUG: Watch entity decay in real-time
     async fn debug_decay_watch(&self, ctx: &Context<'_>) -> Vec<DecayInfo> {
-        let world = ctx.data::<Arc<Mutex<World>>>().unwrap();
-        let world = world.lock().unwrap();
+        let snapshot = snapshot(ctx);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
         
-        let mut decay_info = Vec::new();
-
-        for (entity, (etype, display_text, decay)) in world.query::<(
-            &crate::components::EntityType,
-            &crate::components::DisplayText, 
-            &crate::components::DecayComponent
-        )>().iter() {
-            decay_info.push(DecayInfo {
-                entity_id: format!("{:?}", entity),
-                entity_type: etype.0.clone(),
-                display_text: display_text.0.clone(),
-                current_strength: decay.strength,
-                half_life: decay.half_life,
-                last_update: decay.last_update,
-                time_since_update: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs_f64() - decay.last_update,
-            });
-        }
+        let mut decay_info: Vec<DecayInfo> = snapshot
+            .entities()
+            .filter_map(|entity| {
+                let decay = entity.decay.as_ref()?;
+                Some(DecayInfo {
+                    entity_id: entity.external_id(),
+                    entity_type: entity.entity_type.clone(),
+                    display_text: entity.display_text.clone(),
+                    current_strength: decay.strength,
+                    half_life: decay.half_life,
+                    last_update: decay.last_update,
+                    time_since_update: now - decay.last_update,
+                })
+            })
+            .collect();
 
         decay_info.sort_by(|a, b| b.current_strength.partial_cmp(&a.current_strength).unwrap_or(std::cmp::Ordering::Equal));
         decay_info
     }
 }
 
+/// The entity a mutation created or modified, as applied by the memory system
+#[derive(SimpleObject)]
+pub struct MutationResult {
+    pub id: String,
+    pub entity_type: String,
+    pub strength: Option<f32>,
+}
+
+impl From<CommandOutcome> for MutationResult {
+    fn from(outcome: CommandOutcome) -> Self {
+        Self {
+            id: outcome.id.to_string(),
+            entity_type: outcome.entity_type,
+            strength: outcome.strength,
+        }
+    }
+}
+
+impl ErrorExtensions for CommandError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| e.set("code", self.code()))
+    }
+}
+
+/// Send a command to the memory system and wait until it has been applied
+async fn dispatch(ctx: &Context<'_>, command: impl FnOnce(Principal, Reply) -> GqlCommand) -> async_graphql::Result<MutationResult> {
+    let sender = ctx.data::<Sender<GqlCommand>>()?;
+    let (reply, response) = oneshot::channel();
+    sender.send(command(principal(ctx).as_ref().clone(), reply)).map_err(|_| CommandError::Unavailable.extend())?;
+
+    match tokio::time::timeout(COMMAND_TIMEOUT, response).await {
+        Ok(Ok(result)) => result.map(MutationResult::from).map_err(|e| e.extend()),
+        // The memory system went away without applying it
+        Ok(Err(_)) => Err(CommandError::Unavailable.extend()),
+        // Still queued or mid-tick: it may yet be applied, so retrying blindly could apply it twice
+        Err(_) => Err(CommandError::OutcomeUnknown.extend()),
+    }
+}
+
 /// Root mutation object for the Familiar Memory API.
 /// Provides write operations to create and modify memory entities.
 pub struct MutationRoot;
@@ -343,16 +519,17 @@ impl MutationRoot {
     /// Arguments:
     /// - `text`: The textual content of the memory
     /// - `thread_id`: The ID of the thread this moment belongs to
+    /// - `emotion`: Optional emotion label attached as an `emotion` component
+    /// - `confidence`: Classifier confidence for the emotion (0.0 to 1.0)
     /// 
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createMoment(text: "Alice laughs at Bob's joke", threadId: "Alice")
+    ///   createMoment(text: "Alice laughs at Bob's joke", threadId: "Alice", emotion: "joy", confidence: 0.9) { id entityType strength }
     /// }
     /// ```
-    async fn create_moment(&self, ctx: &Context<'_>, text: String, thread_id: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateMoment { text, thread_id }).is_ok()
+    async fn create_moment(&self, ctx: &Context<'_>, text: String, thread_id: String, emotion: Option<String>, confidence: Option<f32>) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateMoment { text, thread_id, emotion, confidence, principal, reply }).await
     }
 
     /// Creates a new Thread entity (person, place, event, or concept).
@@ -370,12 +547,11 @@ impl MutationRoot {
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createThread(name: "Alice", threadType: "person")
+    ///   createThread(name: "Alice", threadType: "person") { id entityType strength }
     /// }
     /// ```
-    async fn create_thread(&self, ctx: &Context<'_>, name: String, thread_type: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateThread { name, thread_type }).is_ok()
+    async fn create_thread(&self, ctx: &Context<'_>, name: String, thread_type: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateThread { name, thread_type, principal, reply }).await
     }
 
     /// Creates a new Filament (interpretive data about a thread).
@@ -390,12 +566,11 @@ impl MutationRoot {
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createFilament(content: "always the family comedian", threadName: "Bob")
+    ///   createFilament(content: "always the family comedian", threadName: "Bob") { id entityType strength }
     /// }
     /// ```
-    async fn create_filament(&self, ctx: &Context<'_>, content: String, thread_name: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateFilament { content, thread_name }).is_ok()
+    async fn create_filament(&self, ctx: &Context<'_>, content: String, thread_name: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateFilament { content, thread_name, principal, reply }).await
     }
 
     /// Creates a new Motif (aggregated meaning pattern from moments).
@@ -410,12 +585,11 @@ impl MutationRoot {
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createMotif(pattern: "family-warmth", strength: 0.9)
+    ///   createMotif(pattern: "family-warmth", strength: 0.9) { id entityType strength }
     /// }
     /// ```
-    async fn create_motif(&self, ctx: &Context<'_>, pattern: String, strength: f32) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateMotif { pattern, strength }).is_ok()
+    async fn create_motif(&self, ctx: &Context<'_>, pattern: String, strength: f32) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateMotif { pattern, strength, principal, reply }).await
     }
 
     /// Creates a new Bond between two threads.
@@ -431,12 +605,11 @@ impl MutationRoot {
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createBond(thread1: "Alice", thread2: "Bob", affinity: 0.88)
+    ///   createBond(thread1: "Alice", thread2: "Bob", affinity: 0.88) { id entityType strength }
     /// }
     /// ```
-    async fn create_bond(&self, ctx: &Context<'_>, thread1: String, thread2: String, affinity: f32) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateBond { thread1, thread2, affinity }).is_ok()
+    async fn create_bond(&self, ctx: &Context<'_>, thread1: String, thread2: String, affinity: f32) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateBond { thread1, thread2, affinity, principal, reply }).await
     }
 
     /// Creates a new Binding linking a moment to a secondary thread.
@@ -451,46 +624,303 @@ impl MutationRoot {
     /// Example:
     /// ```graphql
     /// mutation {
-    ///   createBinding(momentId: "123", threadId: "Kitchen")
+    ///   createBinding(momentId: "6f1c2d3e-8a4b-4c5d-9e6f-7a8b9c0d1e2f", threadId: "Kitchen") { id entityType strength }
     /// }
     /// ```
-    async fn create_binding(&self, ctx: &Context<'_>, moment_id: String, thread_id: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::CreateBinding { moment_id, thread_id }).is_ok()
+    async fn create_binding(&self, ctx: &Context<'_>, moment_id: String, thread_id: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::CreateBinding { moment_id, thread_id, principal, reply }).await
     }
 
     /// Update component strength for any entity (mutable component operation)
-    async fn update_strength(&self, ctx: &Context<'_>, entity_id: String, new_strength: f32) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::UpdateStrength { entity_id, new_strength }).is_ok()
+    async fn update_strength(&self, ctx: &Context<'_>, entity_id: String, new_strength: f32) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::UpdateStrength { entity_id, new_strength, principal, reply }).await
     }
 
     /// Update display text for any entity (mutable component operation)
-    async fn update_display_text(&self, ctx: &Context<'_>, entity_id: String, new_text: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::UpdateDisplayText { entity_id, new_text }).is_ok()
+    async fn update_display_text(&self, ctx: &Context<'_>, entity_id: String, new_text: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::UpdateDisplayText { entity_id, new_text, principal, reply }).await
     }
 
     /// Add a tag to an entity (append-only operation on immutable entity field)
-    async fn add_entity_tag(&self, ctx: &Context<'_>, entity_id: String, tag: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::AddEntityTag { entity_id, tag }).is_ok()
+    async fn add_entity_tag(&self, ctx: &Context<'_>, entity_id: String, tag: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::AddEntityTag { entity_id, tag, principal, reply }).await
     }
 
     /// Soft delete an entity (append-only operation - sets deleted_at timestamp)
-    async fn soft_delete_entity(&self, ctx: &Context<'_>, entity_id: String) -> bool {
-        let sender = ctx.data::<Sender<GqlCommand>>().unwrap();
-        sender.send(GqlCommand::SoftDeleteEntity { entity_id }).is_ok()
+    async fn soft_delete_entity(&self, ctx: &Context<'_>, entity_id: String) -> async_graphql::Result<MutationResult> {
+        dispatch(ctx, |principal, reply| GqlCommand::SoftDeleteEntity { entity_id, principal, reply }).await
+    }
+}
+
+/// A decay strength change pushed to subscribers
+#[derive(SimpleObject, Clone)]
+pub struct StrengthChange {
+    pub entity_id: String,
+    pub entity_type: String,
+    pub display_text: String,
+    pub old_strength: f64,
+    pub new_strength: f64,
+    pub tick: u64,
+}
+
+/// What one simulation tick did
+#[derive(SimpleObject, Clone)]
+pub struct TickSummary {
+    pub tick: u64,
+    pub captured_at: f64,
+    pub spawned: usize,
+    pub despawned: usize,
+    pub mutated: usize,
+    pub total_entities: usize,
+}
+
+/// One published tick as the subscriber's principal sees it
+struct ScopedUpdate {
+    changes: Arc<ChangeSet>,
+    previous: ScopedSnapshot,
+    snapshot: ScopedSnapshot,
+}
+
+impl ScopedUpdate {
+    /// Whether the principal could see the entity a change is about, before or after the tick
+    fn visible(&self, change: &Change) -> bool {
+        change.id().is_some_and(|id| match change {
+            Change::Despawned { .. } => self.previous.get(&id).is_some(),
+            _ => self.snapshot.get(&id).is_some(),
+        })
+    }
+}
+
+/// Every tick the memory system publishes; ticks a slow subscriber fell behind on are skipped
+fn tick_updates(ctx: &Context<'_>) -> impl Stream<Item = ScopedUpdate> {
+    let receiver = ctx.data_unchecked::<Arc<SnapshotStore>>().subscribe();
+    let principal = principal(ctx);
+    stream::unfold(receiver, move |mut receiver| {
+        let principal = principal.clone();
+        async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(TickUpdate { changes, previous, snapshot }) => {
+                        let update = ScopedUpdate {
+                            changes,
+                            previous: ScopedSnapshot::new(previous, principal.clone()),
+                            snapshot: ScopedSnapshot::new(snapshot, principal),
+                        };
+                        return Some((update, receiver));
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
+
+/// `DecayComponent.strength` changes in a tick the principal may see, as (entity id, old, new)
+fn strength_changes(update: &ScopedUpdate) -> impl Iterator<Item = (Uuid, f64, f64)> + '_ {
+    update.changes.iter().filter(|change| update.visible(change)).filter_map(|change| match change {
+        Change::Mutated { id: Some(id), component: "DecayComponent", field: "strength", old, new, .. } => {
+            Some((*id, old.as_f64()?, new.as_f64()?))
+        }
+        _ => None,
+    })
+}
+
+fn strength_change(update: &ScopedUpdate, id: Uuid, old_strength: f64, new_strength: f64) -> StrengthChange {
+    let entity = update.snapshot.get(&id);
+    StrengthChange {
+        entity_id: id.to_string(),
+        entity_type: entity.map(|e| e.entity_type.clone()).unwrap_or_default(),
+        display_text: entity.map(|e| e.display_text.clone()).unwrap_or_default(),
+        old_strength,
+        new_strength,
+        tick: update.snapshot.tick(),
+    }
+}
+
+/// Root subscription object for the Familiar Memory API (GraphQL over WebSocket at `/ws`).
+/// Streams are fed by the simulation loop, one update per tick.
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Entities as they are created, optionally only those of one type.
+    ///
+    /// Example:
+    /// ```graphql
+    /// subscription {
+    ///   entityCreated(entityType: "binding") { id entityType displayText strength }
+    /// }
+    /// ```
+    async fn entity_created(&self, ctx: &Context<'_>, entity_type: Option<String>) -> impl Stream<Item = EntityInfo> {
+        tick_updates(ctx).flat_map(move |update| {
+            let created: Vec<EntityInfo> = update
+                .changes
+                .iter()
+                .filter_map(|change| match change {
+                    Change::Spawned { id: Some(id), entity_type: spawned, .. }
+                        if entity_type.as_ref().is_none_or(|wanted| wanted == spawned) =>
+                    {
+                        update.snapshot.get(id).map(EntityInfo::from)
+                    }
+                    _ => None,
+                })
+                .collect();
+            stream::iter(created)
+        })
+    }
+
+    /// Strength changes of one entity, reported once they add up to at least `min_delta`
+    /// since the last report (decay moves strength a little every tick).
+    ///
+    /// Example:
+    /// ```graphql
+    /// subscription {
+    ///   strengthChanged(entityId: "6f1c2d3e-8a4b-4c5d-9e6f-7a8b9c0d1e2f", minDelta: 0.05) { oldStrength newStrength tick }
+    /// }
+    /// ```
+    async fn strength_changed(
+        &self,
+        ctx: &Context<'_>,
+        entity_id: String,
+        min_delta: Option<f64>,
+    ) -> async_graphql::Result<impl Stream<Item = StrengthChange>> {
+        let id: Uuid = entity_id
+            .trim()
+            .parse()
+            .map_err(|_| CommandError::Validation(format!("`{}` is not a valid entity id (expected a UUID)", entity_id)).extend())?;
+        let min_delta = min_delta.unwrap_or(0.0).abs();
+        let mut reported: Option<f64> = None;
+
+        Ok(tick_updates(ctx).flat_map(move |update| {
+            let mut changes = Vec::new();
+            for (changed, old, new) in strength_changes(&update).filter(|(changed, _, _)| *changed == id) {
+                let last = *reported.get_or_insert(old);
+                if (new - last).abs() >= min_delta && new != last {
+                    reported = Some(new);
+                    changes.push(strength_change(&update, changed, last, new));
+                }
+            }
+            stream::iter(changes)
+        }))
+    }
+
+    /// Entities whose strength drops below `threshold` (reported once per crossing).
+    ///
+    /// Example:
+    /// ```graphql
+    /// subscription {
+    ///   entityDecayedBelow(threshold: 0.3) { entityId entityType displayText newStrength }
+    /// }
+    /// ```
+    async fn entity_decayed_below(&self, ctx: &Context<'_>, threshold: f64) -> impl Stream<Item = StrengthChange> {
+        tick_updates(ctx).flat_map(move |update| {
+            let crossed: Vec<StrengthChange> = strength_changes(&update)
+                .filter(|(_, old, new)| *old >= threshold && *new < threshold)
+                .map(|(id, old, new)| strength_change(&update, id, old, new))
+                .collect();
+            stream::iter(crossed)
+        })
+    }
+
+    /// One summary per simulation tick, counting only entities the subscriber may see.
+    ///
+    /// Example:
+    /// ```graphql
+    /// subscription { tick { tick spawned despawned mutated totalEntities } }
+    /// ```
+    async fn tick(&self, ctx: &Context<'_>) -> impl Stream<Item = TickSummary> {
+        tick_updates(ctx).map(|update| {
+            let mut summary = TickSummary {
+                tick: update.snapshot.tick(),
+                captured_at: update.snapshot.captured_at(),
+                spawned: 0,
+                despawned: 0,
+                mutated: 0,
+                total_entities: update.snapshot.len(),
+            };
+            for change in update.changes.iter().filter(|change| update.visible(change)) {
+                match change {
+                    Change::Spawned { .. } => summary.spawned += 1,
+                    Change::Despawned { .. } => summary.despawned += 1,
+                    Change::Mutated { .. } => summary.mutated += 1,
+                }
+            }
+            summary
+        })
+    }
+}
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+/// Header carrying the API key; `Authorization: Bearer <key>` works too
+const API_KEY_HEADER: &str = "x-api-key";
+
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        return key.to_str().ok();
     }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
 }
 
-pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+/// The same key in a WebSocket `connection_init` payload, e.g. `{"X-API-Key": "..."}`
+fn payload_api_key(payload: &serde_json::Value) -> Option<&str> {
+    let fields = payload.as_object()?;
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_str())
+    };
+    field(API_KEY_HEADER).or_else(|| field("authorization").and_then(|value| value.strip_prefix("Bearer ")))
+}
+
+fn unauthenticated() -> async_graphql::Error {
+    async_graphql::Error::new("Missing or unknown API key").extend_with(|_, e| e.set("code", "UNAUTHENTICATED"))
+}
 
 async fn graphql_handler(
-    Extension(schema): Extension<AppSchema>, 
-    req: GraphQLRequest
-) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
+    Extension(schema): Extension<AppSchema>,
+    Extension(keys): Extension<Arc<ApiKeys>>,
+    headers: HeaderMap,
+    req: GraphQLRequest,
+) -> Response {
+    let Some(principal) = keys.authenticate(api_key(&headers)) else {
+        let error = unauthenticated();
+        let error = ServerError { extensions: error.extensions, ..ServerError::new(error.message, None) };
+        let response = GraphQLResponse::from(async_graphql::Response::from_errors(vec![error]));
+        return (StatusCode::UNAUTHORIZED, response).into_response();
+    };
+    let request = req.into_inner().data(Arc::new(principal));
+    GraphQLResponse::from(schema.execute(request).await).into_response()
+}
+
+/// Subscriptions authenticate once per connection, from the upgrade headers or the `connection_init` payload
+async fn graphql_ws_handler(
+    Extension(schema): Extension<AppSchema>,
+    Extension(keys): Extension<Arc<ApiKeys>>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let from_headers = api_key(&headers).and_then(|key| keys.authenticate(Some(key)));
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .on_connection_init(move |payload| async move {
+                    let principal = from_headers
+                        .or_else(|| keys.authenticate(payload_api_key(&payload)))
+                        .ok_or_else(unauthenticated)?;
+                    let mut data = Data::default();
+                    data.insert(Arc::new(principal));
+                    Ok(data)
+                })
+                .serve()
+        })
 }
 
 async fn graphiql() -> impl IntoResponse {
@@ -517,20 +947,25 @@ async fn graphiql() -> impl IntoResponse {
         crossorigin
         src="https://unpkg.com/react-dom@18/umd/react-dom.development.js"
     ></script>
+    <script
+        crossorigin
+        src="https://unpkg.com/graphql-ws@5.14.0/umd/graphql-ws.min.js"
+    ></script>
     <script
         crossorigin
         src="https://unpkg.com/graphiql@3.0.6/graphiql.min.js"
     ></script>
     
     <script>
-        const graphQLFetcher = graphiQLParams =>
-            fetch('/', {
-                method: 'post',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify(graphiQLParams),
-            })
-            .then(response => response.json())
-            .catch(() => response.text());
+        // Queries and mutations over HTTP, subscriptions over the /ws WebSocket
+        const graphQLFetcher = GraphiQL.createFetcher({
+            url: '/',
+            // The WebSocket can't carry headers, so the ones from the Headers tab go in connection_init
+            wsClient: graphqlWs.createClient({
+                url: `ws://${window.location.host}/ws`,
+                connectionParams: () => JSON.parse(localStorage.getItem('graphiql:headers') || '{}'),
+            }),
+        });
 
         const root = ReactDOM.createRoot(document.getElementById('graphiql'));
         root.render(
@@ -558,20 +993,33 @@ async fn graphiql() -> impl IntoResponse {
 # 🧪 CREATE TEST ENTITIES:
 #
 # mutation {
-#   createMoment(text: "Test memory", threadId: "test")
-#   createFilament(content: "Fast-decaying thought", threadName: "test")  
-#   createMotif(pattern: "recurring-theme", strength: 0.9)
+#   createThread(name: "test", threadType: "concept") { id }
+#   createMoment(text: "Test memory", threadId: "test") { id entityType strength }
+#   createFilament(content: "Fast-decaying thought", threadName: "test") { id entityType strength }
+#   createMotif(pattern: "recurring-theme", strength: 0.9) { id entityType strength }
 # }
 #
+# 🧵 TYPED ENTITIES AND RELATIONS:
+#
+# { threads { name createdAt moments { displayText bindings { points { cardinality } threads { name } } } bonds { threads { name } } } }
+# { entity(id: "<uuid>") { __typename createdAt tags ... on Moment { thread { name } } } }
+#
 # 🔬 ENTITY FILTERING:
 #
-# { entitiesByType(entityType: "moment", limit: 10) { displayText strength } }
-# { strongEntities(minStrength: 0.5) { entityType displayText strength } }
-# { searchEntities(query: "test") { entityType displayText } }
+# { entitiesByType(entityType: "moment", first: 10) { totalCount pageInfo { hasNextPage endCursor } edges { node { displayText strength } } } }
+# { strongEntities(minStrength: 0.5, filter: { entityType: "motif" }) { edges { node { entityType displayText strength } } } }
+# { searchEntities(query: "test", after: "<endCursor>") { edges { node { entityType displayText } } } }
+#
+# 📡 LIVE UPDATES (subscriptions over ws://127.0.0.1:8000/ws):
+#
+# subscription { tick { tick spawned despawned mutated totalEntities } }
+# subscription { entityCreated(entityType: "moment") { id displayText } }
+# subscription { entityDecayedBelow(threshold: 0.5) { entityId displayText newStrength } }
 #
 # ⚖️ PHYSICS LAWS ACTIVE:
 # - Decay: Affects moments, filaments, motifs (exponential decay over time)
-# - Resonance: Amplifies filaments when strength > 0.85
+# - Resonance: Amplifies a filament above strength 0.85 once a bond or binding affinity crosses the threshold
+# - Binding, bonding, consolidation: link moments and threads, fold fading moments into filaments and motifs
 #
 # Press Ctrl+Enter to run queries!
 
@@ -586,19 +1034,26 @@ async fn graphiql() -> impl IntoResponse {
     "#)
 }
 
-/// Runs the GraphQL server.
-pub async fn run_graphql_server(sender: Sender<GqlCommand>, world: Arc<Mutex<World>>) {
-    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+/// Runs the GraphQL server. Every request acts for the principal its API key maps to.
+pub async fn run_graphql_server(sender: Sender<GqlCommand>, snapshots: Arc<SnapshotStore>, keys: ApiKeys, graphiql_enabled: bool) {
+    let schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
         .data(sender)
-        .data(world)
+        .data(snapshots)
         .finish();
 
+    let root = if graphiql_enabled { get(graphiql).post(graphql_handler) } else { post(graphql_handler) };
     let app = Router::new()
-        .route("/", get(graphiql).post(graphql_handler))
-        .layer(Extension(schema));
+        .route("/", root)
+        .route("/ws", get(graphql_ws_handler))
+        .layer(Extension(schema))
+        .layer(Extension(Arc::new(keys)));
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 8000));
-    println!("🚀 GraphiQL IDE listening on http://{}", addr);
+    if graphiql_enabled {
+        println!("🚀 GraphiQL IDE listening on http://{}", addr);
+    } else {
+        println!("🚀 GraphQL endpoint listening on http://{} (GraphiQL disabled)", addr);
+    }
 
     let listener = TcpListener::bind(&addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
@@ -0,0 +1,403 @@
+// Typed GraphQL objects for the schema entities.
+// Thread, Moment, Filament, Motif, Bond and Binding all implement the `Entity` interface, whose
+// fields come from BaseEntity. Each object keeps the snapshot it was read from, so relation
+// resolvers (Thread.moments, Bond.threads, ...) answer from the same tick as their parent and
+// only ever reach entities the request's principal may see.
+
+use async_graphql::{Context, Interface, Object};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::components::{self, AccessScope, BaseComponent, BaseEntity, Visibility};
+use crate::graphql::observe;
+use crate::security::ScopedSnapshot;
+use crate::snapshot::EntitySnapshot;
+
+/// What every typed entity carries: its BaseEntity and the snapshot it was read from
+#[derive(Clone)]
+struct Node {
+    snapshot: ScopedSnapshot,
+    id: Uuid,
+    entity_type: String,
+    display_text: String,
+    strength: Option<f32>,
+    base: BaseEntity,
+    components: Vec<BaseComponent>,
+}
+
+impl Node {
+    /// `None` for entities outside the index or without a BaseEntity
+    fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        Some(Self {
+            snapshot: snapshot.clone(),
+            id: entity.id?,
+            entity_type: entity.entity_type.clone(),
+            display_text: entity.display_text.clone(),
+            strength: entity.strength(),
+            base: entity.base.clone()?,
+            components: entity.components.clone(),
+        })
+    }
+
+    /// Live entities of this snapshot picked by `matches`, typed by `typed` and reported as observed
+    fn related<T>(
+        &self,
+        ctx: &Context<'_>,
+        matches: impl Fn(&EntitySnapshot) -> bool,
+        typed: fn(&ScopedSnapshot, &EntitySnapshot) -> Option<T>,
+    ) -> Vec<T> {
+        let mut observed = Vec::new();
+        let related = self
+            .snapshot
+            .entities()
+            .filter(|entity| !entity.is_deleted() && matches(entity))
+            .filter_map(|entity| {
+                let object = typed(&self.snapshot, entity)?;
+                observed.push(entity.entity);
+                Some(object)
+            })
+            .collect();
+        observe(ctx, observed);
+        related
+    }
+
+    /// One entity of this snapshot by id, typed by `typed`
+    fn get<T>(&self, id: &Uuid, typed: fn(&ScopedSnapshot, &EntitySnapshot) -> Option<T>) -> Option<T> {
+        self.snapshot.get(id).and_then(|entity| typed(&self.snapshot, entity))
+    }
+
+    /// The thread whose `ThreadId` name is `name`
+    fn thread_named(&self, name: &str) -> Option<Thread> {
+        self.snapshot
+            .of_type("thread")
+            .find(|thread| thread.thread_id.as_deref() == Some(name) && !thread.is_deleted())
+            .and_then(|thread| Thread::new(&self.snapshot, thread))
+    }
+}
+
+/// Declares an entity object: the `Entity` interface fields followed by its own fields
+macro_rules! entity_object {
+    ($(#[$doc:meta])* $object:ident { $($data:ident: $ty:ty),* $(,)? } impl { $($field:tt)* }) => {
+        $(#[$doc])*
+        pub struct $object {
+            node: Node,
+            $($data: $ty,)*
+        }
+
+        #[Object]
+        impl $object {
+            /// Stable UUID of the entity
+            async fn id(&self) -> Uuid {
+                self.node.id
+            }
+
+            async fn entity_type(&self) -> String {
+                self.node.entity_type.clone()
+            }
+
+            async fn display_text(&self) -> String {
+                self.node.display_text.clone()
+            }
+
+            async fn org_id(&self) -> Uuid {
+                self.node.base.org_id
+            }
+
+            async fn owner_id(&self) -> Uuid {
+                self.node.base.owner_id
+            }
+
+            async fn created_at(&self) -> Option<DateTime<Utc>> {
+                self.node.base.created_at
+            }
+
+            async fn updated_at(&self) -> Option<DateTime<Utc>> {
+                self.node.base.updated_at
+            }
+
+            /// Set once the entity has been soft-deleted
+            async fn deleted_at(&self) -> Option<DateTime<Utc>> {
+                self.node.base.deleted_at
+            }
+
+            async fn tags(&self) -> Vec<String> {
+                self.node.base.tags.clone()
+            }
+
+            async fn sub_type(&self) -> Option<String> {
+                self.node.base.sub_type.clone()
+            }
+
+            async fn visibility(&self) -> Visibility {
+                self.node.base.visibility
+            }
+
+            async fn access_scope(&self) -> Vec<AccessScope> {
+                self.node.base.access_scope.clone()
+            }
+
+            async fn security_level(&self) -> i64 {
+                self.node.base.security_level
+            }
+
+            async fn version(&self) -> i64 {
+                self.node.base.version
+            }
+
+            async fn parent_version(&self) -> Option<i64> {
+                self.node.base.parent_version
+            }
+
+            /// Current decay strength, for entities that decay
+            async fn strength(&self) -> Option<f32> {
+                self.node.strength
+            }
+
+            /// Schema-declared components attached to the entity (time, emotion, ...)
+            async fn components(&self) -> Vec<BaseComponent> {
+                self.node.components.clone()
+            }
+
+            $($field)*
+        }
+    };
+}
+
+/// Any schema entity, with the fields it inherits from BaseEntity
+// clippy mistakes the repeated `ty = ...` of the field list for duplicated attributes
+#[allow(clippy::duplicated_attributes)]
+#[derive(Interface)]
+#[graphql(
+    field(name = "id", ty = "Uuid"),
+    field(name = "entity_type", ty = "String"),
+    field(name = "display_text", ty = "String"),
+    field(name = "org_id", ty = "Uuid"),
+    field(name = "owner_id", ty = "Uuid"),
+    field(name = "created_at", ty = "Option<DateTime<Utc>>"),
+    field(name = "updated_at", ty = "Option<DateTime<Utc>>"),
+    field(name = "deleted_at", ty = "Option<DateTime<Utc>>"),
+    field(name = "tags", ty = "Vec<String>"),
+    field(name = "sub_type", ty = "Option<String>"),
+    field(name = "visibility", ty = "Visibility"),
+    field(name = "access_scope", ty = "Vec<AccessScope>"),
+    field(name = "security_level", ty = "i64"),
+    field(name = "version", ty = "i64"),
+    field(name = "parent_version", ty = "Option<i64>"),
+    field(name = "strength", ty = "Option<f32>"),
+    field(name = "components", ty = "Vec<BaseComponent>")
+)]
+pub enum Entity {
+    Thread(Thread),
+    Moment(Moment),
+    Filament(Filament),
+    Motif(Motif),
+    Bond(Bond),
+    Binding(Binding),
+}
+
+impl Entity {
+    /// The typed object for an entity, by its `EntityType`; `None` for untyped entities
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        match entity.entity_type.as_str() {
+            "thread" => Thread::new(snapshot, entity).map(Entity::Thread),
+            "moment" => Moment::new(snapshot, entity).map(Entity::Moment),
+            "filament" => Filament::new(snapshot, entity).map(Entity::Filament),
+            "motif" => Motif::new(snapshot, entity).map(Entity::Motif),
+            "bond" => Bond::new(snapshot, entity).map(Entity::Bond),
+            "binding" => Binding::new(snapshot, entity).map(Entity::Binding),
+            _ => None,
+        }
+    }
+}
+
+impl Thread {
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        Some(Self {
+            node: Node::new(snapshot, entity)?,
+            name: entity.thread_id.clone().unwrap_or_else(|| entity.display_text.clone()),
+            thread_type: entity.thread_type.clone(),
+        })
+    }
+}
+
+entity_object! {
+    /// A memory thread: a person, place, event or concept that moments belong to
+    Thread { name: String, thread_type: Option<String> }
+    impl {
+        /// Thread name, as used by `createMoment(threadId:)`
+        async fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        async fn thread_type(&self) -> Option<String> {
+            self.thread_type.clone()
+        }
+
+        /// Moments belonging to this thread, oldest first
+        async fn moments(&self, ctx: &Context<'_>) -> Vec<Moment> {
+            let id = self.node.id;
+            self.node.related(ctx, |entity| entity.moment.as_ref().is_some_and(|moment| moment.thread_id == id), Moment::new)
+        }
+
+        /// Filaments interpreting this thread
+        async fn filaments(&self, ctx: &Context<'_>) -> Vec<Filament> {
+            self.node.related(
+                ctx,
+                |entity| entity.entity_type == "filament" && entity.thread_name.as_ref() == Some(&self.name),
+                Filament::new,
+            )
+        }
+
+        /// Bonds this thread takes part in
+        async fn bonds(&self, ctx: &Context<'_>) -> Vec<Bond> {
+            let id = self.node.id;
+            self.node.related(ctx, |entity| entity.bond.as_ref().is_some_and(|bond| bond.thread_ids.contains(&id)), Bond::new)
+        }
+
+        /// Bindings with a point on this thread
+        async fn bindings(&self, ctx: &Context<'_>) -> Vec<Binding> {
+            let id = self.node.id;
+            self.node.related(ctx, |entity| entity.binding.as_ref().is_some_and(|binding| binding.involves_thread(id)), Binding::new)
+        }
+    }
+}
+
+impl Moment {
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        Some(Self { node: Node::new(snapshot, entity)?, moment: entity.moment.clone()? })
+    }
+}
+
+entity_object! {
+    /// A single memory, scoped to one thread
+    Moment { moment: components::Moment }
+    impl {
+        /// The full schema record, including fields without a resolver of their own
+        async fn record(&self) -> &components::Moment {
+            &self.moment
+        }
+
+        async fn author_id(&self) -> Uuid {
+            self.moment.author_id
+        }
+
+        /// The thread this moment belongs to
+        async fn thread(&self) -> Option<Thread> {
+            self.node.get(&self.moment.thread_id, Thread::new)
+        }
+
+        /// Bindings with a point on this moment
+        async fn bindings(&self, ctx: &Context<'_>) -> Vec<Binding> {
+            let id = self.node.id;
+            self.node.related(
+                ctx,
+                |entity| entity.binding.as_ref().is_some_and(|binding| binding.get_moment_ids().contains(&id)),
+                Binding::new,
+            )
+        }
+    }
+}
+
+impl Filament {
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        Some(Self { node: Node::new(snapshot, entity)?, thread_name: entity.thread_name.clone() })
+    }
+}
+
+entity_object! {
+    /// Interpretive data about a thread
+    Filament { thread_name: Option<String> }
+    impl {
+        /// The thread this filament describes
+        async fn thread(&self) -> Option<Thread> {
+            self.thread_name.as_deref().and_then(|name| self.node.thread_named(name))
+        }
+    }
+}
+
+impl Motif {
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        Some(Self { node: Node::new(snapshot, entity)? })
+    }
+}
+
+entity_object! {
+    /// A recurring pattern aggregated from moments
+    Motif {}
+    impl {}
+}
+
+/// Whether `id` names an entity the principal may see; records only keep ids that do
+fn visible(snapshot: &ScopedSnapshot, id: &Uuid) -> bool {
+    snapshot.get(id).is_some()
+}
+
+impl Bond {
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        let mut bond = entity.bond.clone()?;
+        bond.thread_ids.retain(|id| visible(snapshot, id));
+        Some(Self { node: Node::new(snapshot, entity)?, bond })
+    }
+}
+
+entity_object! {
+    /// An affinity bond between threads
+    Bond { bond: components::Bond }
+    impl {
+        /// The full schema record, including fields without a resolver of their own; thread ids
+        /// the principal can't see are left out
+        async fn record(&self) -> &components::Bond {
+            &self.bond
+        }
+
+        async fn affinity_score(&self) -> f64 {
+            self.bond.affinity_score
+        }
+
+        async fn bond_strength(&self) -> f64 {
+            self.bond.bond_strength
+        }
+
+        /// The bonded threads
+        async fn threads(&self) -> Vec<Thread> {
+            self.bond.thread_ids.iter().filter_map(|id| self.node.get(id, Thread::new)).collect()
+        }
+    }
+}
+
+impl Binding {
+    /// Points on a thread or moment the principal can't see are left out, rather than leaking their ids
+    pub fn new(snapshot: &ScopedSnapshot, entity: &EntitySnapshot) -> Option<Self> {
+        let mut binding = entity.binding.clone()?;
+        binding.points.retain(|point| visible(snapshot, &point.thread_id) && visible(snapshot, &point.moment_id));
+        binding.thread_ids.retain(|id| visible(snapshot, id));
+        Some(Self { node: Node::new(snapshot, entity)?, binding })
+    }
+}
+
+entity_object! {
+    /// A cross-thread connection made of binding points
+    Binding { binding: components::Binding }
+    impl {
+        /// The full schema record, including fields without a resolver of their own; points and
+        /// thread ids the principal can't see are left out
+        async fn record(&self) -> &components::Binding {
+            &self.binding
+        }
+
+        /// Each thread ↔ moment ↔ role the binding connects
+        async fn points(&self) -> &[components::BindingPoint] {
+            &self.binding.points
+        }
+
+        /// Every thread the binding touches
+        async fn threads(&self) -> Vec<Thread> {
+            self.binding.get_thread_ids().iter().filter_map(|id| self.node.get(id, Thread::new)).collect()
+        }
+
+        /// Every moment the binding touches
+        async fn moments(&self) -> Vec<Moment> {
+            self.binding.get_moment_ids().iter().filter_map(|id| self.node.get(id, Moment::new)).collect()
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// Stable identity for ECS entities.
+// Clients address entities by `BaseEntity.id`; hecs handles are internal and get reused,
+// so the memory system keeps a bidirectional UUID ↔ Entity index, updated on spawn/despawn.
+
+use hecs::{DynamicBundle, Entity, World};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::components::with_base_entity;
+
+#[derive(Debug, Default)]
+pub struct EntityIndex {
+    by_uuid: HashMap<Uuid, Entity>,
+    by_entity: HashMap<Entity, Uuid>,
+}
+
+impl EntityIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every schema entity already in the world
+    pub fn rebuild(world: &World) -> Self {
+        let mut index = Self::new();
+        for entity in world.iter().map(|entity_ref| entity_ref.entity()) {
+            index.track(world, entity);
+        }
+        index
+    }
+
+    /// Spawn a bundle and index it under its `BaseEntity.id` (if it carries a schema entity)
+    pub fn spawn(&mut self, world: &mut World, components: impl DynamicBundle) -> Entity {
+        let entity = world.spawn(components);
+        self.track(world, entity);
+        entity
+    }
+
+    /// Despawn an entity and drop it from the index
+    pub fn despawn(&mut self, world: &mut World, entity: Entity) -> Result<(), hecs::NoSuchEntity> {
+        self.forget(entity);
+        world.despawn(entity)
+    }
+
+    /// Index an entity that was spawned elsewhere; returns its UUID
+    pub fn track(&mut self, world: &World, entity: Entity) -> Option<Uuid> {
+        let uuid = with_base_entity(world, entity, |base| base.id).flatten()?;
+        self.insert(uuid, entity);
+        Some(uuid)
+    }
+
+    pub fn insert(&mut self, uuid: Uuid, entity: Entity) {
+        if let Some(previous) = self.by_uuid.insert(uuid, entity) {
+            self.by_entity.remove(&previous);
+        }
+        if let Some(previous) = self.by_entity.insert(entity, uuid) {
+            if previous != uuid {
+                self.by_uuid.remove(&previous);
+            }
+        }
+    }
+
+    pub fn forget(&mut self, entity: Entity) -> Option<Uuid> {
+        let uuid = self.by_entity.remove(&entity)?;
+        self.by_uuid.remove(&uuid);
+        Some(uuid)
+    }
+
+    pub fn entity(&self, uuid: &Uuid) -> Option<Entity> {
+        self.by_uuid.get(uuid).copied()
+    }
+
+    pub fn uuid(&self, entity: Entity) -> Option<Uuid> {
+        self.by_entity.get(&entity).copied()
+    }
+
+    /// Resolve an external id string (a UUID) to a live entity
+    pub fn resolve(&self, world: &World, id: &str) -> Result<Entity, IndexError> {
+        let uuid: Uuid = id.trim().parse().map_err(|_| IndexError::InvalidId(id.to_string()))?;
+        self.entity(&uuid)
+            .filter(|entity| world.contains(*entity))
+            .ok_or(IndexError::NotFound(uuid))
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_uuid.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_uuid.is_empty()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexError {
+    #[error("`{0}` is not a valid entity id (expected a UUID)")]
+    InvalidId(String),
+
+    #[error("No entity with id {0}")]
+    NotFound(Uuid),
+}
@@ -0,0 +1,272 @@
+// Law loading and validation.
+// The cold path exports its Law instances (`python cli.py laws-dump`) as a stamped JSON/YAML file;
+// each law is deserialized into the generated `Law` type, checked for consistency and compiled.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::components::Law;
+use crate::formula::{Formula, FormulaError, FormulaFields, Scope};
+use crate::migration::{check_compatibility, SchemaError, SchemaStamp};
+
+/// Component fields a law may declare in `variables`; field laws read and write these (DecayComponent)
+pub const FIELD_VARIABLES: [&str; 3] = ["strength", "half_life", "last_update"];
+
+/// Further variables only the structural systems bind, so only predicate laws may declare them
+pub const PREDICATE_VARIABLES: [&str; 2] = ["position", "affinity"];
+
+/// Values the hot path derives per entity (or entity pair) rather than reading from a field
+pub const DERIVED_VARIABLES: [&str; 3] = ["time_elapsed", "distance", "shared_bindings"];
+
+/// Structural actions a law formula can wrap a predicate in, e.g. `create_binding_if(...)`
+pub const LAW_ACTIONS: [&str; 3] = ["create_binding_if", "consolidate_if", "form_bond_if"];
+
+/// On-disk law export: schema stamp plus the law instances
+#[derive(Debug, Clone, Deserialize)]
+pub struct LawFile {
+    #[serde(flatten)]
+    pub schema: SchemaStamp,
+    pub laws: Vec<Law>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LawKind {
+    /// `field = expr` - writes a component field on every governed entity
+    Field { target: String },
+    /// `action(predicate)` - a structural law whose system acts when the predicate holds
+    Predicate { action: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Bounds {
+    pub fn clamp(&self, value: f64) -> f64 {
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+}
+
+/// A validated law ready to run as a system
+#[derive(Debug, Clone)]
+pub struct CompiledLaw {
+    pub law: Law,
+    pub kind: LawKind,
+    pub formula: Formula,
+    pub constants: HashMap<String, f64>,
+    pub bounds: HashMap<String, Bounds>,
+}
+
+impl CompiledLaw {
+    /// Validate a law against the hot path's variable vocabulary and compile its formula
+    pub fn compile(law: Law) -> Result<Self, LawError> {
+        let name = law.name.clone();
+        let action = split_action(&law.formula);
+
+        for variable in &law.variables {
+            if FIELD_VARIABLES.contains(&variable.as_str()) {
+                continue;
+            }
+            if !PREDICATE_VARIABLES.contains(&variable.as_str()) {
+                return Err(LawError::UnknownVariable { law: name, variable: variable.clone() });
+            }
+            // Field laws only see DecayComponent fields; this one would never run
+            if action.is_none() {
+                return Err(LawError::PredicateOnlyVariable { law: name, variable: variable.clone() });
+            }
+        }
+
+        let mut constants = HashMap::new();
+        for (key, value) in &law.constants {
+            let number = value.as_f64().ok_or_else(|| LawError::InvalidConstant {
+                law: name.clone(),
+                constant: key.clone(),
+            })?;
+            constants.insert(key.clone(), number);
+        }
+
+        let (kind, formula) = match action {
+            Some((action, predicate)) => (
+                LawKind::Predicate { action: action.to_string() },
+                compile_formula(&name, predicate)?,
+            ),
+            None => {
+                let formula = compile_formula(&name, &law.formula)?;
+                let target = formula
+                    .target()
+                    .ok_or_else(|| LawError::MissingTarget { law: name.clone() })?
+                    .to_string();
+                if !law.variables.contains(&target) {
+                    return Err(LawError::UnknownVariable { law: name, variable: target });
+                }
+                (LawKind::Field { target }, formula)
+            }
+        };
+
+        // Every name the formula reads must be a declared variable, a constant or derived
+        for referenced in formula.referenced_names() {
+            let declared = law.variables.contains(&referenced)
+                || constants.contains_key(&referenced)
+                || DERIVED_VARIABLES.contains(&referenced.as_str());
+            if !declared {
+                return Err(LawError::MissingConstant { law: name, name: referenced });
+            }
+        }
+
+        let bounds = parse_constraints(&law)?;
+
+        Ok(Self { law, kind, formula, constants, bounds })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.law.name
+    }
+
+    pub fn applies_to(&self, entity_type: &str) -> bool {
+        self.law.applies_to.iter().any(|t| t == entity_type)
+    }
+
+    pub fn constant(&self, name: &str) -> Option<f64> {
+        self.constants.get(name).copied()
+    }
+
+    /// Scope pre-populated with the law's constants
+    pub fn scope(&self) -> Scope {
+        let mut scope = Scope::new();
+        for (name, value) in &self.constants {
+            scope.set(name.clone(), *value);
+        }
+        scope
+    }
+
+    /// Clamp constrained fields after the formula has been applied
+    pub fn apply_constraints(&self, component: &mut impl FormulaFields) {
+        for (field, bounds) in &self.bounds {
+            if let Some(value) = component.get_field(field) {
+                component.set_field(field, bounds.clamp(value));
+            }
+        }
+    }
+}
+
+/// Read a law export (JSON, or YAML for .yml/.yaml) and compile every law in it
+pub fn load_laws(path: impl AsRef<Path>) -> Result<Vec<CompiledLaw>, LawError> {
+    let path = path.as_ref();
+    let display = path.display().to_string();
+    let raw = std::fs::read_to_string(path).map_err(|e| LawError::Io {
+        path: display.clone(),
+        reason: e.to_string(),
+    })?;
+
+    let is_yaml = matches!(path.extension().and_then(|ext| ext.to_str()), Some("yml") | Some("yaml"));
+    let file: LawFile = if is_yaml {
+        serde_yaml::from_str(&raw).map_err(|e| LawError::Parse { path: display, reason: e.to_string() })?
+    } else {
+        serde_json::from_str(&raw).map_err(|e| LawError::Parse { path: display, reason: e.to_string() })?
+    };
+
+    // Law files produced under an incompatible schema are refused outright
+    check_compatibility(&file.schema)?;
+
+    let mut compiled: Vec<CompiledLaw> = Vec::with_capacity(file.laws.len());
+    for law in file.laws {
+        if compiled.iter().any(|existing| existing.name() == law.name) {
+            return Err(LawError::Duplicate(law.name));
+        }
+        compiled.push(CompiledLaw::compile(law)?);
+    }
+    Ok(compiled)
+}
+
+fn compile_formula(law: &str, source: &str) -> Result<Formula, LawError> {
+    Formula::compile(source).map_err(|source| LawError::Formula { law: law.to_string(), source })
+}
+
+/// Split `create_binding_if(<predicate>)` into the action and its predicate
+fn split_action(formula: &str) -> Option<(&str, &str)> {
+    let formula = formula.trim();
+    let open = formula.find('(')?;
+    let action = formula[..open].trim();
+    if !LAW_ACTIONS.contains(&action) || !formula.ends_with(')') {
+        return None;
+    }
+    Some((action, &formula[open + 1..formula.len() - 1]))
+}
+
+/// Constraints look like `{"strength": {"min": 0.1, "max": 1.0}}`
+fn parse_constraints(law: &Law) -> Result<HashMap<String, Bounds>, LawError> {
+    let mut bounds = HashMap::new();
+    let Some(constraints) = &law.constraints else {
+        return Ok(bounds);
+    };
+
+    let invalid = |field: &str, reason: &str| LawError::InvalidConstraint {
+        law: law.name.clone(),
+        field: field.to_string(),
+        reason: reason.to_string(),
+    };
+
+    for (field, spec) in constraints {
+        if !law.variables.contains(field) {
+            return Err(invalid(field, "constrains a field the law doesn't declare"));
+        }
+        let spec = spec.as_object().ok_or_else(|| invalid(field, "expected an object with min/max"))?;
+
+        let mut field_bounds = Bounds { min: None, max: None };
+        for (key, value) in spec {
+            let number = value.as_f64().ok_or_else(|| invalid(field, "bounds must be numbers"))?;
+            match key.as_str() {
+                "min" => field_bounds.min = Some(number),
+                "max" => field_bounds.max = Some(number),
+                _ => return Err(invalid(field, "only min and max are supported")),
+            }
+        }
+        if let (Some(min), Some(max)) = (field_bounds.min, field_bounds.max) {
+            if min > max {
+                return Err(invalid(field, "min is greater than max"));
+            }
+        }
+        bounds.insert(field.clone(), field_bounds);
+    }
+    Ok(bounds)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LawError {
+    #[error("Failed to read law file {path}: {reason}")]
+    Io { path: String, reason: String },
+
+    #[error("Failed to parse law file {path}: {reason}")]
+    Parse { path: String, reason: String },
+
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+
+    #[error("Law `{0}` is defined more than once")]
+    Duplicate(String),
+
+    #[error("Law `{law}` has an invalid formula: {source}")]
+    Formula { law: String, source: FormulaError },
+
+    #[error("Law `{law}` formula must assign to a field (e.g. `strength = ...`)")]
+    MissingTarget { law: String },
+
+    #[error("Law `{law}` uses unknown variable `{variable}`")]
+    UnknownVariable { law: String, variable: String },
+
+    #[error("Law `{law}` is a field law, but `{variable}` is only available to structural laws (e.g. `create_binding_if(...)`)")]
+    PredicateOnlyVariable { law: String, variable: String },
+
+    #[error("Law `{law}` references `{name}`, which is neither a declared variable nor a constant")]
+    MissingConstant { law: String, name: String },
+
+    #[error("Law `{law}` constant `{constant}` is not a number")]
+    InvalidConstant { law: String, constant: String },
+
+    #[error("Law `{law}` has an invalid constraint on `{field}`: {reason}")]
+    InvalidConstraint { law: String, field: String, reason: String },
+}
@@ -1,9 +1,24 @@
 pub mod common;
 pub mod components;
 pub mod ecs;
+pub mod index;       // UUID ↔ hecs::Entity identity index
+pub mod changes;     // Per-tick change sets (spawned, despawned, mutated)
 pub mod generated;   // Direct code generation from JSON schema
 pub mod systems;     // ECS systems implementing physics laws
+pub mod formula;     // Expression engine for law formulas
+pub mod laws;        // Law file loading and validation
+pub mod binding;     // Binding law: automatic moment ⟷ moment bindings
+pub mod consolidation; // Consolidation law: decaying moments → filaments and motifs
+pub mod bonding;     // Bonding law: thread ⟷ thread bonds from shared bindings
 pub mod schemas;
 pub mod config;
 pub mod graphql;
-pub mod persistence; 
\ No newline at end of file
+pub mod graphql_types; // Typed entity objects behind the GraphQL `Entity` interface
+pub mod snapshot;    // Immutable per-tick world snapshot read by GraphQL
+pub mod security;    // Security enforcement law: principals and entity access checks
+pub mod persistence;
+pub mod event_log;   // Append-only, checksummed segment log backing primary memory
+pub mod replay;      // Event-sourced world reconstruction from primary memory
+pub mod checkpoint;  // Periodic world snapshots next to the log, and log compaction
+pub mod migration;   // Schema version checks and payload migrations
+pub mod schema_diff; // Breaking-change report between two schema exports 
\ No newline at end of file
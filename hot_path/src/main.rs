@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
 use tokio::runtime::Runtime;
@@ -6,36 +7,104 @@ use hecs::World;
 use uuid::Uuid;
 use colored::*;
 
-mod common;
-mod components;
-mod ecs;
-mod generated;
-mod systems;
-mod schemas;
-mod config;
-mod graphql;
-mod persistence;
-
-use common::GqlCommand;
+use familiar_hot_path::{changes, checkpoint, components, config, ecs, event_log, graphql, index, migration, persistence, replay, schema_diff, snapshot, systems};
+use familiar_hot_path::persistence::EventType;
+use familiar_hot_path::security::Principal;
+use familiar_hot_path::common::{CommandError, CommandOutcome, GqlCommand, Reply};
 
 // Helper function for UUID generation
 fn uuid4() -> Uuid {
     Uuid::new_v4()
 }
 
+/// Read-only principals can't create entities
+fn authorize_create(principal: &Principal) -> Result<(), CommandError> {
+    if principal.can_create() {
+        Ok(())
+    } else {
+        Err(CommandError::Forbidden(format!("{:?} principals can't create entities", principal.role)))
+    }
+}
+
 struct MemorySystem {
     world: World,
-    world_ref: std::sync::Arc<std::sync::Mutex<World>>,
+    index: index::EntityIndex,
+    snapshots: Arc<snapshot::SnapshotStore>,
+    tick: u64,
+    changes: changes::ChangeSet,
     command_receiver: Receiver<GqlCommand>,
     last_status_update: SystemTime,
     entity_count_history: Vec<usize>,
-    start_time: SystemTime,
     law_specifications: systems::LawSpecifications,
+    scheduler: systems::LawScheduler,
+    persistence: persistence::MemoryPersistence,
+    /// Log version the world reflects: where the restore stopped, then each entry appended since
+    log_sequence: u64,
 }
 
 impl MemorySystem {
-    fn new(rx: Receiver<GqlCommand>, world_ref: std::sync::Arc<std::sync::Mutex<World>>) -> Self {
+    fn new(
+        rx: Receiver<GqlCommand>,
+        snapshots: Arc<snapshot::SnapshotStore>,
+        law_specifications: systems::LawSpecifications,
+        scheduler: systems::LawScheduler,
+        owner: &Principal,
+        restored: replay::Replayed,
+        mut persistence: persistence::MemoryPersistence,
+    ) -> Self {
+        let law_names: Vec<&str> = law_specifications.laws.iter().map(|law| law.name()).collect();
+        println!("{}", format!("⚖️  Loaded {} physics laws ({})", law_names.len(), law_names.join(", ")).bright_purple());
+
+        let replay::Replayed { world, index, report } = restored;
+        persistence.restored(report.last_sequence);
+        if let Some(reason) = persistence.read_only() {
+            println!("{} {}", "🔒 Primary memory is read-only -".yellow(), reason);
+            println!("{}", "   Mutations are refused and law changes aren't logged until a full restore".yellow());
+        }
+        let mut log_sequence = report.last_sequence;
+        let (world, index, changes) = if world.is_empty() {
+            let (world, index, changes) = Self::seed_world(owner);
+            // A read-only log keeps the seed in memory only
+            let writable = persistence.read_only().is_none();
+            for change in changes.iter().filter(|_| writable) {
+                let entry = replay::entry(&world, change.entity(), EventType::EntityCreated);
+                match entry.map(|entry| persistence.append_primary_memory(entry)) {
+                    Some(Ok(record)) => log_sequence = record.entry.sequence,
+                    Some(Err(e)) => println!("{} {}", "❌ Failed to persist the initial thread:".bright_red(), e),
+                    None => {}
+                }
+            }
+            println!("{}", "🧵 Memory System initialized with initial thread and physics systems".bright_green());
+            (world, index, changes)
+        } else {
+            // Restored entities are already in primary memory: publish them as they are, not as changes
+            let captured_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs_f64();
+            snapshots.publish(snapshot::WorldSnapshot::capture(&world, &index, 0, captured_at), Arc::new(changes::ChangeSet::new(0)));
+            println!("{} {} entities from primary memory", "🧵 Memory System restored".bright_green(), world.len().to_string().bright_yellow());
+            (world, index, changes::ChangeSet::new(1))
+        };
+        let entity_count = world.len() as usize;
+
+        Self {
+            world,
+            index,
+            snapshots,
+            tick: 0,
+            changes,
+            command_receiver: rx,
+            last_status_update: SystemTime::now(),
+            entity_count_history: vec![entity_count],
+            law_specifications,
+            scheduler,
+            persistence,
+            log_sequence,
+        }
+    }
+
+    /// A fresh world holding just the "Memory Lane" thread, and tick 1's change set recording it
+    fn seed_world(owner: &Principal) -> (World, index::EntityIndex, changes::ChangeSet) {
         let mut world = World::new();
+        let mut index = index::EntityIndex::new();
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -51,15 +120,15 @@ impl MemorySystem {
         let initial_thread = components::Thread {
             base: components::BaseEntity {
                 id: Some(uuid4()),
-                org_id: uuid4(),
-                owner_id: uuid4(),
+                org_id: owner.org_id,
+                owner_id: owner.user_id,
                 created_at: Some(chrono::Utc::now()),
                 updated_at: None,
                 deleted_at: None,
                 tags: vec!["initial".to_string()],
                 component_ids: vec![],
                 sub_type: Some("thread".to_string()),
-                visibility: components::Visibility::Private,
+                visibility: components::Visibility::Org,
                 security_level: 0,
                 access_scope: vec![components::AccessScope::View],
                 version: 1,
@@ -67,343 +136,685 @@ impl MemorySystem {
             },
         };
         
-        world.spawn((
+        let initial = index.spawn(&mut world, (
             initial_thread,
             components::DisplayText("Memory Lane".to_string()), 
             components::ThreadType("pathway".to_string()),
             components::EntityType("thread".to_string()), 
             initial_decay
         ));
-
-        // Load law specifications from schema (not as entities, but as system configs)
-        let law_specifications = systems::LawSpecifications::from_schema();
-
-        // Note: World sync will be handled differently since World doesn't implement Clone
-        // We'll implement entity sharing through queries instead
-
-        println!("{}", "⚖️  Loaded 2 physics systems (decay, resonance)".bright_purple());
-        println!("{}", "🧵 Memory System initialized with initial thread and physics systems".bright_green());
-
-        Self {
-            world,
-            world_ref,
-            command_receiver: rx,
-            last_status_update: SystemTime::now(),
-            entity_count_history: vec![1], // Just the initial thread
-            start_time: SystemTime::now(),
-            law_specifications,
-        }
+        // Everything that happens before the first snapshot belongs to tick 1
+        let mut changes = changes::ChangeSet::new(1);
+        changes.spawned(&world, &index, initial);
+        (world, index, changes)
     }
 
     fn run(&mut self) {
         loop {
-            // Process GraphQL commands
-            self.process_commands();
+            // Process GraphQL commands (each one is logged to primary memory as it is applied)
+            let replies = self.process_commands();
+            let command_changes = self.changes.len();
             
             // Run ECS systems
             self.run_ecs_systems();
             
-            // Sync world state for GraphQL queries (lightweight approach)
-            self.sync_world_state();
+            // Log what the laws changed, then publish this tick's world for GraphQL queries
+            let changes = Arc::new(self.end_tick());
+            self.persist_law_changes(&changes, command_changes);
+            self.publish_snapshot(&changes);
+
+            // Answer mutations only once their effect is visible to GraphQL readers
+            for (reply, result) in replies {
+                // The client may have gone away; the command is applied either way
+                let _ = reply.send(result);
+            }
             
             // Show status updates every 5 seconds
             if self.last_status_update.elapsed().unwrap() > Duration::from_secs(5) {
                 self.show_status();
                 self.last_status_update = SystemTime::now();
             }
+
+            if self.persistence.checkpoint_due() {
+                self.checkpoint();
+            }
             
             // Sleep briefly to avoid busy-waiting
             thread::sleep(Duration::from_millis(100));
         }
     }
 
-    fn sync_world_state(&self) {
-        // Simple approach: serialize essential data for GraphQL queries
-        if let Ok(mut shared_world) = self.world_ref.try_lock() {
-            // Clear and rebuild shared world with current entities
-            shared_world.clear();
-            
-            // Copy entities with essential components for debugging
-            for (entity, (entity_type, display_text)) in self.world.query::<(&components::EntityType, &components::DisplayText)>().iter() {
-                let decay = self.world.get::<&components::DecayComponent>(entity);
-                
-                // Create new entity in shared world with all components at once
-                if let Ok(decay_comp) = decay {
-                    shared_world.spawn((
-                        components::EntityType(entity_type.0.clone()),
-                        components::DisplayText(display_text.0.clone()),
-                        components::DecayComponent {
-                            strength: decay_comp.strength,
-                            half_life: decay_comp.half_life,
-                            last_update: decay_comp.last_update,
-                        },
-                    ));
-                } else {
-                    shared_world.spawn((
-                        components::EntityType(entity_type.0.clone()),
-                        components::DisplayText(display_text.0.clone()),
-                    ));
+    /// Append `entity`, as it is now, to primary memory; entities without a schema model aren't logged
+    fn persist(&mut self, entity: hecs::Entity, event_type: EventType) -> Result<(), persistence::PersistenceError> {
+        match replay::entry(&self.world, entity, event_type) {
+            Some(entry) => self.append(entry),
+            None => Ok(()),
+        }
+    }
+
+    /// Append one entry, which the world already reflects
+    fn append(&mut self, entry: persistence::PrimaryMemoryEntry) -> Result<(), persistence::PersistenceError> {
+        let record = self.persistence.append_primary_memory(entry)?;
+        self.log_sequence = record.entry.sequence;
+        Ok(())
+    }
+
+    /// Log the entity a command created or changed, with the version the command gave it
+    fn persist_command(&mut self, outcome: &CommandOutcome, event_type: EventType) -> Result<(), persistence::PersistenceError> {
+        let entity = self.index.entity(&outcome.id).ok_or(persistence::PersistenceError::EntityNotFound(outcome.id))?;
+        self.persist(entity, event_type)
+    }
+
+    /// Log what the laws changed this tick (the changes from index `from` on): one entry per entity,
+    /// at its end-of-tick state. Decay alone isn't logged - replay catches it up from `last_update`.
+    /// As with commands, a change the log refuses is fatal: the world would run ahead of its log,
+    /// and the next checkpoint would bake the unlogged change in.
+    fn persist_law_changes(&mut self, changes: &changes::ChangeSet, from: usize) {
+        if self.persistence.read_only().is_some() {
+            return;
+        }
+        // Per entity: the first event, and whether it was spawned and/or removed this tick
+        let mut pending: Vec<(Uuid, EventType, bool, bool)> = Vec::new();
+        for change in changes.iter().skip(from) {
+            let (Some(id), Some(event_type)) = (change.id(), change.event_type()) else {
+                continue;
+            };
+            if matches!(change, changes::Change::Mutated { component: "DecayComponent", .. }) {
+                continue;
+            }
+            let removed = event_type == EventType::EntityRemoved;
+            match pending.iter_mut().find(|(pending_id, ..)| *pending_id == id) {
+                Some((_, _, _, was_removed)) => *was_removed |= removed,
+                None => pending.push((id, event_type, event_type == EventType::EntityCreated, removed)),
+            }
+        }
+
+        let previous = self.snapshots.load();
+        for (id, event_type, spawned, removed) in pending {
+            let result = match (spawned, removed, self.index.entity(&id)) {
+                // Spawned and collected within the tick: it never reached primary memory
+                (true, true, _) => continue,
+                (false, true, _) => {
+                    let version = previous.get(&id).and_then(|entity| entity.base.as_ref()).map_or(1, |base| base.version);
+                    self.append(replay::removal_entry(id, version.max(0) as u64))
                 }
+                (_, false, Some(entity)) => self.persist(entity, event_type),
+                (_, false, None) => continue,
+            };
+            if let Err(e) = result {
+                eprintln!("{} {}: {}", "❌ Failed to persist a law change, stopping:".bright_red(), id, e);
+                std::process::exit(1);
             }
         }
     }
 
-    // Note: Removed sync_world_ref since World doesn't implement Clone
-    // TODO: Implement proper entity data sharing mechanism for GraphQL queries
+    /// Snapshot the world next to the log and compact what the kept checkpoints cover
+    fn checkpoint(&mut self) {
+        match self.persistence.checkpoint(&self.world, self.log_sequence) {
+            Ok(Some(report)) => println!("{} log version {}: {} entities, {} entries compacted",
+                "📸 Checkpoint at".bright_blue(),
+                report.sequence.to_string().bright_white(),
+                report.entities.to_string().bright_green(),
+                report.compacted.to_string().bright_yellow()
+            ),
+            Ok(None) => {}
+            Err(e) => println!("{} {}", "❌ Checkpoint failed:".bright_red(), e),
+        }
+    }
 
-    fn process_commands(&mut self) {
+    /// Close the current tick and hand over what changed during it
+    fn end_tick(&mut self) -> changes::ChangeSet {
+        self.tick += 1;
+        std::mem::replace(&mut self.changes, changes::ChangeSet::new(self.tick + 1))
+    }
+
+    fn publish_snapshot(&self, changes: &Arc<changes::ChangeSet>) {
+        let captured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+        // Only entities the change set touched are recaptured; readers keep whichever snapshot they loaded
+        let snapshot = snapshot::WorldSnapshot::advance(&self.snapshots.load(), &self.world, &self.index, changes, captured_at);
+        self.snapshots.publish(snapshot, changes.clone());
+    }
+
+    fn process_commands(&mut self) -> Vec<(Reply, Result<CommandOutcome, CommandError>)> {
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs_f64();
 
-        for cmd in self.command_receiver.try_iter() {
-            match cmd {
-                GqlCommand::CreateMoment { text, thread_id } => {
-                    let moment = components::Moment {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec!["moment".to_string()],
-                            component_ids: vec![],
-                            sub_type: Some("moment".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                        thread_id: thread_id.parse().unwrap_or_default(),
-                        author_id: uuid4(),
-                        binding_hint: None,
-                        binding_id: None,
-                        cardinality: None,
-                    };
-                    let decay = components::DecayComponent {
-                        strength: 1.0,
-                        half_life: 300.0, // 5 minutes
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        moment,
-                        components::DisplayText(text.clone()), 
-                        components::ThreadId(thread_id.clone()), 
-                        components::EntityType("moment".to_string()), 
-                        decay
-                    ));
-                    println!("{} {}", "✨ Created moment:".bright_yellow(), text.bright_white());
+        let mut replies = Vec::new();
+        for cmd in self.command_receiver.try_iter().collect::<Vec<_>>() {
+            // A partly restored world takes no changes: they couldn't be logged after what it never saw
+            if let Some(reason) = self.persistence.read_only() {
+                if !matches!(cmd, GqlCommand::ObserveEntities { .. }) {
+                    let error = CommandError::ReadOnly(reason.to_string());
+                    replies.extend(cmd.into_reply().map(|reply| (reply, Err(error))));
+                    continue;
+                }
+            }
+            // Every mutation is answered once applied, logged and published, with the result or error
+            let changes_before = self.changes.len();
+            let (result, reply, event_type) = match cmd {
+                GqlCommand::CreateMoment { text, thread_id, emotion, confidence, principal, reply } => {
+                    (self.create_moment(text, thread_id, emotion, confidence, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::CreateThread { name, thread_type } => {
-                    let thread = components::Thread {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec![thread_type.clone()],
-                            component_ids: vec![],
-                            sub_type: Some("thread".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                    };
-                    let decay = components::DecayComponent {
-                        strength: 1.0,
-                        half_life: 600.0, // 10 minutes
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        thread,
-                        components::DisplayText(name.clone()), 
-                        components::ThreadType(thread_type.clone()), 
-                        components::ThreadId(name.clone()),
-                        components::EntityType("thread".to_string()), 
-                        decay
-                    ));
-                    println!("{} {} ({})", "🧵 Created thread:".bright_blue(), name.bright_white(), thread_type.bright_cyan());
+                GqlCommand::CreateThread { name, thread_type, principal, reply } => {
+                    (self.create_thread(name, thread_type, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::CreateFilament { content, thread_name } => {
-                    let filament = components::Filament {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec!["filament".to_string()],
-                            component_ids: vec![],
-                            sub_type: Some("filament".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                    };
-                    let decay = components::DecayComponent {
-                        strength: 1.0,
-                        half_life: 45.0,
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        filament,
-                        components::DisplayText(content.clone()), 
-                        components::ThreadName(thread_name.clone()), 
-                        components::EntityType("filament".to_string()), 
-                        decay
-                    ));
-                    println!("{} {} on {}", "🌱 Created filament:".bright_green(), content.bright_white(), thread_name.bright_cyan());
+                GqlCommand::CreateFilament { content, thread_name, principal, reply } => {
+                    (self.create_filament(content, thread_name, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::CreateMotif { pattern, strength } => {
-                    let motif = components::Motif {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec!["motif".to_string()],
-                            component_ids: vec![],
-                            sub_type: Some("motif".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                    };
-                    let decay = components::DecayComponent {
-                        strength: strength.max(0.1),
-                        half_life: 90.0,
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        motif,
-                        components::DisplayText(pattern.clone()), 
-                        components::EntityType("motif".to_string()), 
-                        decay
-                    ));
-                    println!("{} {} (strength: {})", "🎨 Created motif:".bright_magenta(), pattern.bright_white(), strength.to_string().bright_yellow());
+                GqlCommand::CreateMotif { pattern, strength, principal, reply } => {
+                    (self.create_motif(pattern, strength, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::CreateBond { thread1, thread2, affinity } => {
-                    let bond = components::Bond {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec!["bond".to_string(), thread1.clone(), thread2.clone()],
-                            component_ids: vec![],
-                            sub_type: Some("bond".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                        thread_ids: vec![],
-                        affinity_score: affinity as f64,
-                        bond_strength: (affinity * 0.8) as f64,
-                        component_context: vec![],
-                    };
-                    let decay = components::DecayComponent {
-                        strength: affinity,
-                        half_life: 120.0,
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        bond, 
-                        components::DisplayText(format!("{} ⟷ {}", thread1, thread2)), 
-                        components::EntityType("bond".to_string()), 
-                        decay
-                    ));
-                    println!("{} {} ⟷ {} (affinity: {})", "🔗 Created bond:".bright_red(), thread1.bright_white(), thread2.bright_white(), affinity.to_string().bright_yellow());
+                GqlCommand::CreateBond { thread1, thread2, affinity, principal, reply } => {
+                    (self.create_bond(thread1, thread2, affinity, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::CreateBinding { moment_id, thread_id } => {
-                    let thread_uuid = thread_id.parse().unwrap_or_default();
-                    let moment_uuid = moment_id.parse().unwrap_or_default();
-                    
-                    let binding_point = components::BindingPoint::new(
-                        thread_uuid,
-                        moment_uuid,
-                        components::Cardinality::Actor
-                    );
-                    
-                    let binding = components::Binding {
-                        base: components::BaseEntity {
-                            id: Some(uuid4()),
-                            org_id: uuid4(),
-                            owner_id: uuid4(),
-                            created_at: Some(chrono::Utc::now()),
-                            updated_at: None,
-                            deleted_at: None,
-                            tags: vec!["binding".to_string()],
-                            component_ids: vec![],
-                            sub_type: Some("binding".to_string()),
-                            visibility: components::Visibility::Private,
-                            security_level: 0,
-                            access_scope: vec![components::AccessScope::View],
-                            version: 1,
-                            parent_version: None,
-                        },
-                        points: vec![binding_point],
-                        thread_ids: vec![thread_uuid],
-                    };
-                    let decay = components::DecayComponent {
-                        strength: 0.8,
-                        half_life: 60.0,
-                        last_update: current_time,
-                    };
-                    self.world.spawn((
-                        binding,
-                        components::DisplayText(format!("Binding {} → {}", moment_id, thread_id)),
-                        components::EntityType("binding".to_string()),
-                        decay
-                    ));
-                    println!("{} {} → {}", "🔗 Created binding:".bright_cyan(), moment_id[0..8].bright_white(), thread_id[0..8].bright_white());
+                GqlCommand::CreateBinding { moment_id, thread_id, principal, reply } => {
+                    (self.create_binding(moment_id, thread_id, current_time, &principal), reply, EventType::EntityCreated)
                 }
-                GqlCommand::UpdateStrength { entity_id, new_strength } => {
-                    // Parse entity ID (for now just log, real implementation would find entity by ID)
-                    println!("{} {} to {}", "⚡ Update strength:".bright_yellow(), entity_id[0..8].bright_white(), new_strength.to_string().bright_green());
-                    
-                    // TODO: Implement entity lookup by ID and component mutation
-                    // This requires adding entity ID tracking to components
+                GqlCommand::UpdateStrength { entity_id, new_strength, principal, reply } => {
+                    (self.update_strength(entity_id, new_strength, current_time, &principal), reply, EventType::ComponentUpdated)
                 }
-                GqlCommand::UpdateDisplayText { entity_id, new_text } => {
-                    println!("{} {} to '{}'", "📝 Update text:".bright_yellow(), entity_id[0..8].bright_white(), new_text.bright_white());
-                    
-                    // TODO: Implement entity lookup by ID and component mutation
+                GqlCommand::UpdateDisplayText { entity_id, new_text, principal, reply } => {
+                    (self.update_display_text(entity_id, new_text, &principal), reply, EventType::ComponentUpdated)
                 }
-                GqlCommand::AddEntityTag { entity_id, tag } => {
-                    println!("{} {} with tag '{}'", "🏷️ Add tag:".bright_yellow(), entity_id[0..8].bright_white(), tag.bright_cyan());
-                    
-                    // TODO: Implement append-only tag addition to BaseEntity
+                GqlCommand::AddEntityTag { entity_id, tag, principal, reply } => {
+                    (self.add_entity_tag(entity_id, tag, &principal), reply, EventType::EntityTagAdded)
                 }
-                GqlCommand::SoftDeleteEntity { entity_id } => {
-                    println!("{} {}", "🗑️ Soft delete:".bright_red(), entity_id[0..8].bright_white());
-                    
-                    // TODO: Implement soft deletion by setting deleted_at timestamp
+                GqlCommand::SoftDeleteEntity { entity_id, principal, reply } => {
+                    (self.soft_delete_entity(entity_id, &principal), reply, EventType::EntitySoftDeleted)
+                }
+                GqlCommand::ObserveEntities { entities } => {
+                    // Observation laws run lazily on the next scheduler pass
+                    self.scheduler.observe(entities);
+                    continue;
+                }
+            };
+
+            // Commands that changed nothing (e.g. a tag the entity already had) leave no entry. A change
+            // the log refuses is fatal: kept in memory it would vanish on the next restart, and nobody
+            // has seen it yet - the client's reply and the snapshot come after this tick.
+            if let Ok(outcome) = &result {
+                if self.changes.len() > changes_before {
+                    if let Err(e) = self.persist_command(outcome, event_type) {
+                        eprintln!("{} {}", "❌ Failed to persist a command, stopping:".bright_red(), e);
+                        std::process::exit(1);
+                    }
                 }
             }
+            if let Err(e) = &result {
+                println!("{} {}", "❌".bright_red(), e);
+            }
+            replies.push((reply, result));
+        }
+        replies
+    }
+
+    fn create_moment(
+        &mut self,
+        text: String,
+        thread_id: String,
+        emotion: Option<String>,
+        confidence: Option<f32>,
+        current_time: f64,
+        principal: &Principal,
+    ) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        if text.trim().is_empty() {
+            return Err(CommandError::Validation("moment text must not be empty".to_string()));
+        }
+        if let Some(confidence) = confidence {
+            if !(0.0..=1.0).contains(&confidence) {
+                return Err(CommandError::Validation(format!("confidence must be between 0 and 1, got {}", confidence)));
+            }
+        }
+        let (thread_uuid, thread_name) = self.find_thread(principal, &thread_id)?;
+
+        let moment = components::Moment {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["moment".to_string()],
+                component_ids: vec![],
+                sub_type: Some("moment".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+            thread_id: thread_uuid,
+            author_id: principal.user_id,
+            binding_hint: None,
+            binding_id: None,
+            cardinality: None,
+        };
+        let decay = components::DecayComponent {
+            strength: 1.0,
+            half_life: 300.0, // 5 minutes
+            last_update: current_time,
+        };
+        let created_at = moment.base.created_at.unwrap_or_else(chrono::Utc::now);
+        let entity = self.index.spawn(&mut self.world, (
+            moment,
+            components::DisplayText(text.clone()), 
+            components::ThreadId(thread_name), 
+            components::EntityType("moment".to_string()), 
+            components::TemporalPosition::at(created_at),
+            decay
+        ));
+
+        // Typed metadata declared by the cold path schema
+        let _ = components::attach_component(&mut self.world, entity, components::BaseComponent::time(created_at));
+        if let Some(emotion) = emotion {
+            let confidence = confidence.unwrap_or(1.0) as f64;
+            let _ = components::attach_component(&mut self.world, entity, components::BaseComponent::emotion(emotion, confidence));
+        }
+        println!("{} {}", "✨ Created moment:".bright_yellow(), text.bright_white());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn create_thread(&mut self, name: String, thread_type: String, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        if name.trim().is_empty() {
+            return Err(CommandError::Validation("thread name must not be empty".to_string()));
+        }
+        if self.find_thread(principal, &name).is_ok() {
+            return Err(CommandError::Validation(format!("thread `{}` already exists", name)));
+        }
+
+        let thread = components::Thread {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec![thread_type.clone()],
+                component_ids: vec![],
+                sub_type: Some("thread".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+        };
+        let decay = components::DecayComponent {
+            strength: 1.0,
+            half_life: 600.0, // 10 minutes
+            last_update: current_time,
+        };
+        let entity = self.index.spawn(&mut self.world, (
+            thread,
+            components::DisplayText(name.clone()), 
+            components::ThreadType(thread_type.clone()), 
+            components::ThreadId(name.clone()),
+            components::EntityType("thread".to_string()), 
+            decay
+        ));
+        println!("{} {} ({})", "🧵 Created thread:".bright_blue(), name.bright_white(), thread_type.bright_cyan());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn create_filament(&mut self, content: String, thread_name: String, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        if content.trim().is_empty() {
+            return Err(CommandError::Validation("filament content must not be empty".to_string()));
+        }
+        let (_, thread_name) = self.find_thread(principal, &thread_name)?;
+
+        let filament = components::Filament {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["filament".to_string()],
+                component_ids: vec![],
+                sub_type: Some("filament".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+        };
+        let decay = components::DecayComponent {
+            strength: 1.0,
+            half_life: 45.0,
+            last_update: current_time,
+        };
+        let entity = self.index.spawn(&mut self.world, (
+            filament,
+            components::DisplayText(content.clone()), 
+            components::ThreadName(thread_name.clone()), 
+            components::EntityType("filament".to_string()), 
+            decay
+        ));
+        println!("{} {} on {}", "🌱 Created filament:".bright_green(), content.bright_white(), thread_name.bright_cyan());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn create_motif(&mut self, pattern: String, strength: f32, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        if pattern.trim().is_empty() {
+            return Err(CommandError::Validation("motif pattern must not be empty".to_string()));
+        }
+        if !(0.0..=1.0).contains(&strength) {
+            return Err(CommandError::Validation(format!("strength must be between 0 and 1, got {}", strength)));
+        }
+
+        let motif = components::Motif {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["motif".to_string()],
+                component_ids: vec![],
+                sub_type: Some("motif".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+        };
+        let decay = components::DecayComponent {
+            strength: strength.max(0.1),
+            half_life: 90.0,
+            last_update: current_time,
+        };
+        let entity = self.index.spawn(&mut self.world, (
+            motif,
+            components::DisplayText(pattern.clone()), 
+            components::EntityType("motif".to_string()), 
+            decay
+        ));
+        println!("{} {} (strength: {})", "🎨 Created motif:".bright_magenta(), pattern.bright_white(), strength.to_string().bright_yellow());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn create_bond(&mut self, thread1: String, thread2: String, affinity: f32, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        if !(0.0..=1.0).contains(&affinity) {
+            return Err(CommandError::Validation(format!("affinity must be between 0 and 1, got {}", affinity)));
+        }
+        let (uuid1, thread1) = self.find_thread(principal, &thread1)?;
+        let (uuid2, thread2) = self.find_thread(principal, &thread2)?;
+        if uuid1 == uuid2 {
+            return Err(CommandError::Validation("a bond needs two different threads".to_string()));
+        }
+
+        let bond = components::Bond {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["bond".to_string(), thread1.clone(), thread2.clone()],
+                component_ids: vec![],
+                sub_type: Some("bond".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+            thread_ids: vec![uuid1, uuid2],
+            affinity_score: affinity as f64,
+            bond_strength: (affinity * 0.8) as f64,
+            component_context: vec![],
+        };
+        let decay = components::DecayComponent {
+            strength: affinity,
+            half_life: 120.0,
+            last_update: current_time,
+        };
+        let entity = self.index.spawn(&mut self.world, (
+            bond, 
+            components::DisplayText(format!("{} ⟷ {}", thread1, thread2)), 
+            components::EntityType("bond".to_string()), 
+            decay
+        ));
+        println!("{} {} ⟷ {} (affinity: {})", "🔗 Created bond:".bright_red(), thread1.bright_white(), thread2.bright_white(), affinity.to_string().bright_yellow());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn create_binding(&mut self, moment_id: String, thread_id: String, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        authorize_create(principal)?;
+        let moment_entity = self.resolve_entity(principal, &moment_id, components::AccessScope::View)?;
+        let moment_uuid = self.world
+            .get::<&components::Moment>(moment_entity)
+            .ok()
+            .and_then(|moment| moment.base.id)
+            .ok_or_else(|| CommandError::Validation(format!("{} is not a moment", moment_id)))?;
+        let (thread_uuid, thread_name) = self.find_thread(principal, &thread_id)?;
+
+        let binding_point = components::BindingPoint::new(
+            thread_uuid,
+            moment_uuid,
+            components::Cardinality::Actor
+        );
+        
+        let binding = components::Binding {
+            base: components::BaseEntity {
+                id: Some(uuid4()),
+                org_id: principal.org_id,
+                owner_id: principal.user_id,
+                created_at: Some(chrono::Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["binding".to_string()],
+                component_ids: vec![],
+                sub_type: Some("binding".to_string()),
+                visibility: components::Visibility::Private,
+                security_level: 0,
+                access_scope: vec![components::AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+            points: vec![binding_point],
+            thread_ids: vec![thread_uuid],
+        };
+        let decay = components::DecayComponent {
+            strength: 0.8,
+            half_life: 60.0,
+            last_update: current_time,
+        };
+        let entity = self.index.spawn(&mut self.world, (
+            binding,
+            components::DisplayText(format!("Binding {} → {}", moment_uuid, thread_name)),
+            components::EntityType("binding".to_string()),
+            decay
+        ));
+        println!("{} {} → {}", "🔗 Created binding:".bright_cyan(), moment_uuid.to_string()[0..8].bright_white(), thread_name.bright_white());
+        self.changes.spawned(&self.world, &self.index, entity);
+        self.outcome(entity)
+    }
+
+    fn update_strength(&mut self, entity_id: String, new_strength: f32, current_time: f64, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        if !(0.0..=1.0).contains(&new_strength) {
+            return Err(CommandError::Validation(format!("strength must be between 0 and 1, got {}", new_strength)));
+        }
+        let entity = self.resolve_live_entity(principal, &entity_id, components::AccessScope::Edit)?;
+        let changes_before = self.changes.len();
+        {
+            let mut decay = self.world
+                .get::<&mut components::DecayComponent>(entity)
+                .map_err(|_| CommandError::Validation(format!("{} has no strength to update", entity_id)))?;
+            self.changes.mutated(&self.index, entity, "DecayComponent", "strength", decay.strength, new_strength);
+            decay.strength = new_strength;
+            decay.last_update = current_time;
+        }
+        // Only a new value is a new version; the same strength isn't logged
+        if self.changes.len() > changes_before {
+            components::with_base_entity_mut(&mut self.world, entity, |base| base.record_update());
+        }
+        println!("{} {} to {}", "⚡ Update strength:".bright_yellow(), self.short_id(entity).bright_white(), new_strength.to_string().bright_green());
+        self.outcome(entity)
+    }
+
+    fn update_display_text(&mut self, entity_id: String, new_text: String, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        if new_text.trim().is_empty() {
+            return Err(CommandError::Validation("display text must not be empty".to_string()));
+        }
+        let entity = self.resolve_live_entity(principal, &entity_id, components::AccessScope::Edit)?;
+        let old_text = self.world.get::<&components::DisplayText>(entity).map(|text| text.0.clone()).ok();
+        if old_text.as_ref() != Some(&new_text) {
+            let _ = self.world.insert_one(entity, components::DisplayText(new_text.clone()));
+            self.changes.mutated(&self.index, entity, "DisplayText", "text", old_text, &new_text);
+            components::with_base_entity_mut(&mut self.world, entity, |base| base.record_update());
+        }
+        println!("{} {} to '{}'", "📝 Update text:".bright_yellow(), self.short_id(entity).bright_white(), new_text.bright_white());
+        self.outcome(entity)
+    }
+
+    fn add_entity_tag(&mut self, entity_id: String, tag: String, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        if tag.trim().is_empty() {
+            return Err(CommandError::Validation("tag must not be empty".to_string()));
+        }
+        let entity = self.resolve_live_entity(principal, &entity_id, components::AccessScope::Edit)?;
+        // Tags are append-only
+        let tags = components::with_base_entity_mut(&mut self.world, entity, |base| {
+            let old_tags = base.tags.clone();
+            if !base.tags.contains(&tag) {
+                base.tags.push(tag.clone());
+                base.record_update();
+            }
+            (old_tags, base.tags.clone())
+        });
+        if let Some((old_tags, new_tags)) = tags {
+            self.changes.mutated(&self.index, entity, "BaseEntity", "tags", old_tags, new_tags);
         }
+        println!("{} {} with tag '{}'", "🏷️ Add tag:".bright_yellow(), self.short_id(entity).bright_white(), tag.bright_cyan());
+        self.outcome(entity)
+    }
+
+    fn soft_delete_entity(&mut self, entity_id: String, principal: &Principal) -> Result<CommandOutcome, CommandError> {
+        let entity = self.resolve_live_entity(principal, &entity_id, components::AccessScope::Admin)?;
+        // Soft deletion keeps the entity (and its index entry) but marks it deleted
+        let deleted_at = components::with_base_entity_mut(&mut self.world, entity, |base| {
+            base.deleted_at = Some(chrono::Utc::now());
+            base.record_update();
+            base.deleted_at
+        });
+        self.changes.mutated(&self.index, entity, "BaseEntity", "deleted_at", None::<chrono::DateTime<chrono::Utc>>, deleted_at.flatten());
+        println!("{} {}", "🗑️ Soft delete:".bright_red(), self.short_id(entity).bright_white());
+        self.outcome(entity)
+    }
+
+    /// First 8 characters of the resolved UUID, for logging - never the raw (untrimmed) input
+    fn short_id(&self, entity: hecs::Entity) -> String {
+        self.index.uuid(entity).map(|uuid| uuid.to_string()[0..8].to_string()).unwrap_or_default()
+    }
+
+    /// What a mutation returns: the entity's UUID, type and current strength
+    fn outcome(&self, entity: hecs::Entity) -> Result<CommandOutcome, CommandError> {
+        let id = self.index.uuid(entity).ok_or(CommandError::Unavailable)?;
+        let entity_type = self.world
+            .get::<&components::EntityType>(entity)
+            .map(|entity_type| entity_type.0.clone())
+            .unwrap_or_default();
+        let strength = self.world.get::<&components::DecayComponent>(entity).map(|decay| decay.strength).ok();
+        Ok(CommandOutcome { id, entity_type, strength })
     }
 
     fn run_ecs_systems(&mut self) {
         // Run all ECS systems (which delegate to physics systems)
-        ecs::run_systems(&mut self.world, &self.law_specifications);
+        let report = ecs::run_systems(
+            &mut self.world,
+            &mut self.index,
+            &self.law_specifications,
+            &mut self.scheduler,
+            &mut self.changes,
+        );
+        if report.bindings_created > 0 {
+            println!("{} {} new binding(s) from the binding law", "🔗 Bound moments:".bright_cyan(), report.bindings_created.to_string().bright_yellow());
+        }
+        if report.filaments_created + report.motifs_created > 0 {
+            println!("{} {} moments into {} filament(s) and {} motif(s)",
+                "🌱 Consolidated".bright_green(),
+                report.moments_consolidated.to_string().bright_yellow(),
+                report.filaments_created.to_string().bright_green(),
+                report.motifs_created.to_string().bright_magenta()
+            );
+        }
+        if report.bonds_created + report.bonds_collected > 0 {
+            println!("{} {} formed, {} collected",
+                "🔗 Bonds:".bright_red(),
+                report.bonds_created.to_string().bright_green(),
+                report.bonds_collected.to_string().bright_red()
+            );
+        }
+        if report.affinity_matches > 0 {
+            println!("{} {} bond/binding affinity crossing(s)", "💞 Affinity match:".bright_magenta(), report.affinity_matches.to_string().bright_yellow());
+        }
+    }
+
+    /// Look up an entity by its external UUID and check `principal` may act on it with `scope`.
+    /// Entities the principal can't even view are reported as missing, not forbidden.
+    fn resolve_entity(&self, principal: &Principal, entity_id: &str, scope: components::AccessScope) -> Result<hecs::Entity, CommandError> {
+        let entity = self.index.resolve(&self.world, entity_id).map_err(|e| match e {
+            index::IndexError::InvalidId(_) => CommandError::Validation(e.to_string()),
+            index::IndexError::NotFound(_) => CommandError::NotFound(e.to_string()),
+        })?;
+        let id = self.index.uuid(entity).ok_or(CommandError::Unavailable)?;
+        let not_found = || CommandError::NotFound(index::IndexError::NotFound(id).to_string());
+        let access = components::with_base_entity(&self.world, entity, |base| {
+            if !principal.can_view(base) {
+                return Err(not_found());
+            }
+            principal.check(base, scope).map_err(|e| CommandError::Forbidden(format!("{}: {}", entity_id, e)))
+        });
+        access.unwrap_or_else(|| Err(not_found()))?;
+        Ok(entity)
+    }
+
+    /// Like `resolve_entity`, but soft-deleted entities can no longer be modified
+    fn resolve_live_entity(&self, principal: &Principal, entity_id: &str, scope: components::AccessScope) -> Result<hecs::Entity, CommandError> {
+        let entity = self.resolve_entity(principal, entity_id, scope)?;
+        let deleted = components::with_base_entity(&self.world, entity, |base| base.deleted_at.is_some()).unwrap_or(false);
+        if deleted {
+            return Err(CommandError::NotFound(format!("entity {} has been deleted", entity_id)));
+        }
+        Ok(entity)
+    }
+
+    /// Resolve a thread `principal` can see by UUID or by name (threads are addressed by name through GraphQL)
+    fn find_thread(&self, principal: &Principal, thread: &str) -> Result<(Uuid, String), CommandError> {
+        let by_uuid = thread.parse::<Uuid>().ok().and_then(|uuid| self.index.entity(&uuid));
+        for (entity, (components::Thread { base }, thread_id)) in self.world
+            .query::<(&components::Thread, &components::ThreadId)>()
+            .iter()
+        {
+            if (Some(entity) == by_uuid || thread_id.0 == thread) && base.deleted_at.is_none() && principal.can_view(base) {
+                if let Some(id) = base.id {
+                    return Ok((id, thread_id.0.clone()));
+                }
+            }
+        }
+        Err(CommandError::NotFound(format!("thread `{}`", thread)))
     }
 
     fn show_status(&mut self) {
@@ -465,7 +876,7 @@ impl MemorySystem {
         counts
     }
 
-    // Note: Law loading is now handled by systems::LawSpecifications::from_schema()
+    // Note: Laws are loaded from the cold path's law export (see load_law_specifications)
     // Laws are no longer entities - they are system configurations
 }
 
@@ -477,38 +888,225 @@ fn main() {
         debug_mode();
         return;
     }
+
+    // Compare two schema exports: --schema-diff old.json new.json
+    if args.len() > 1 && args[1] == "--schema-diff" {
+        if args.len() != 4 {
+            eprintln!("Usage: {} --schema-diff <old_schema.json> <new_schema.json>", args[0]);
+            std::process::exit(2);
+        }
+        std::process::exit(schema_diff_mode(&args[2], &args[3]));
+    }
+
+    // Check the primary memory log's checksums and chain: --verify-log [dir]
+    if args.len() > 1 && args[1] == "--verify-log" {
+        let dir = match args.get(2) {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => load_settings().persistence_dir(),
+        };
+        std::process::exit(verify_log_mode(&dir));
+    }
     
     println!("{}", "🧵 Familiar Memory System Starting...".bright_green().bold());
+    println!("{} {}", "📐 Schema:".bright_blue(), migration::SchemaStamp::current().to_string().bright_white());
     println!("{}", "🚀 GraphiQL IDE will be available at http://127.0.0.1:8000".bright_blue());
 
     // Load configuration  
-    let _settings = config::Settings::new().unwrap_or_else(|e| {
-        eprintln!("Failed to load settings: {}", e);
-        // Create a basic default settings
-        config::Settings {
-            api_key: "default_key".to_string(),
-        }
-    });
+    let settings = load_settings();
+
+    // Load physics laws before anything starts - an inconsistent law file is fatal
+    let law_specifications = load_law_specifications(&settings);
+
+    // Open primary memory and rebuild the world from it before GraphQL can see it
+    let persistence = open_persistence(&settings);
+    let restored = restore_world(&settings);
 
     // Set up GraphQL command channel
     let (tx, rx) = crossbeam_channel::unbounded();
 
-    // Create shared world reference for GraphQL queries
-    let world_ref = std::sync::Arc::new(std::sync::Mutex::new(World::new()));
-    let world_for_gql = world_ref.clone();
+    // Snapshot slot shared with GraphQL: the memory system publishes, queries read
+    let snapshots = Arc::new(snapshot::SnapshotStore::new());
+    let snapshots_for_gql = snapshots.clone();
 
     // Spawn the GraphQL server in a separate thread
     let gql_sender = tx.clone();
+    let api_keys = settings.api_keys();
+    if api_keys.required() {
+        println!("{} {} key(s), sent as an X-API-Key header", "🔑 API keys:".bright_blue(), api_keys.len());
+    } else {
+        println!("{}", "⚠️  No API key configured - the GraphQL endpoint is open".yellow());
+    }
+    let graphiql = settings.graphiql;
     thread::spawn(move || {
         let rt = Runtime::new().unwrap();
-        rt.block_on(graphql::run_graphql_server(gql_sender, world_for_gql));
+        rt.block_on(graphql::run_graphql_server(gql_sender, snapshots_for_gql, api_keys, graphiql));
     });
 
     // Initialize and run the memory system
-    let mut memory_system = MemorySystem::new(rx, world_ref);
+    let scheduler = systems::LawScheduler::new(
+        Duration::from_millis(settings.tick_interval_ms),
+        settings.affinity_threshold,
+    );
+    let mut memory_system = MemorySystem::new(rx, snapshots, law_specifications, scheduler, &settings.principal, restored, persistence);
     memory_system.run();
 }
 
+/// Load the configuration; failing to is fatal - a fallback would start with a well-known API key
+fn load_settings() -> config::Settings {
+    config::Settings::new().unwrap_or_else(|e| {
+        eprintln!("{} {}", "❌ Failed to load settings:".bright_red(), e);
+        eprintln!("{} {}/config/default.toml", "   Config file:".bright_white(), env!("CARGO_MANIFEST_DIR"));
+        std::process::exit(1);
+    })
+}
+
+/// Load and validate the law export, exiting with a clear error if it is inconsistent
+fn load_law_specifications(settings: &config::Settings) -> systems::LawSpecifications {
+    let path = settings.laws_file();
+    match systems::LawSpecifications::load(&path) {
+        Ok(specs) => specs,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Failed to load physics laws:".bright_red(), e);
+            eprintln!("{} {}", "   Law file:".bright_white(), path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Open the primary memory log (truncating a torn last record); failing to is fatal
+fn open_persistence(settings: &config::Settings) -> persistence::MemoryPersistence {
+    let dir = settings.persistence_dir();
+    match persistence::MemoryPersistence::open(&dir, &settings.persistence) {
+        Ok(persistence) => persistence,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Failed to open primary memory:".bright_red(), e);
+            eprintln!("{} {}", "   Log directory:".bright_white(), dir.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Restore the newest checkpoint within the replay limit and replay the log after it. Only the
+/// verified prefix of a damaged log is replayed; a log that can't be read, or a limit that points
+/// before what compaction kept, is fatal rather than silently starting from the wrong state.
+/// A world rebuilt from less than the whole log leaves primary memory read-only (see `MemorySystem::new`).
+fn restore_world(settings: &config::Settings) -> replay::Replayed {
+    let dir = settings.persistence_dir();
+    let fail = |what: &str, e: &dyn std::fmt::Display| -> ! {
+        eprintln!("{} {}", format!("❌ Failed to {}:", what).bright_red(), e);
+        eprintln!("{} {}", "   Log directory:".bright_white(), dir.display());
+        std::process::exit(1);
+    };
+
+    let mut limit = settings.persistence.replay_limit();
+    if let Some(until) = limit.until {
+        println!("{} {}", "⏪ Replaying primary memory up to".bright_yellow(), until.to_rfc3339().bright_white());
+    }
+    if let Some(sequence) = limit.until_sequence {
+        println!("{} {}", "⏪ Replaying primary memory up to entry".bright_yellow(), sequence.to_string().bright_white());
+    }
+
+    let verification = event_log::verify(&dir);
+    if let Some(e) = &verification.corruption {
+        println!("{} {}", "⚠️  Primary memory is damaged:".yellow(), e.to_string().bright_red());
+        println!("{} {}", "   Replaying only up to entry".yellow(), verification.last_sequence);
+        let verified = verification.last_sequence;
+        limit.until_sequence = Some(limit.until_sequence.map_or(verified, |sequence| sequence.min(verified)));
+    }
+
+    let migrations = migration::Migrations::default();
+    let mut replayed = match checkpoint::latest(&dir, limit) {
+        Ok(Some(checkpoint)) => {
+            println!("{} {} ({} entities)",
+                "📸 Restoring checkpoint at log version".bright_blue(),
+                checkpoint.sequence.to_string().bright_white(),
+                checkpoint.entities.len().to_string().bright_green()
+            );
+            checkpoint.restore(&migrations)
+        }
+        Ok(None) => replay::Replayed::new(),
+        Err(e) => fail("read checkpoints", &e),
+    };
+    // Without a checkpoint at or after it, nothing before the anchor can be rebuilt any more
+    match event_log::anchor(&dir) {
+        Ok(Some(anchor)) if replayed.report.last_sequence < anchor.sequence => fail(
+            "restore primary memory",
+            &format!("the log is compacted up to entry {} and no checkpoint within the replay limit covers it", anchor.sequence),
+        ),
+        Ok(_) => {}
+        Err(e) => fail("read primary memory", &e),
+    }
+    if let Err(e) = replayed.replay_dir(&dir, limit, &migrations) {
+        fail("read primary memory", &e);
+    }
+
+    let report = &replayed.report;
+    for (sequence, reason) in &report.skipped {
+        println!("{} entry {}: {}", "⚠️  Skipped".yellow(), sequence, reason);
+    }
+    if report.applied + report.skipped.len() > 0 {
+        println!("{} {} entities from {} records (log version {})",
+            "📼 Restored".bright_blue(),
+            replayed.entity_count().to_string().bright_green(),
+            report.applied.to_string().bright_yellow(),
+            report.last_sequence.to_string().bright_white()
+        );
+    }
+    replayed
+}
+
+/// 📐 SCHEMA DIFF MODE: Report changes between two schema exports.
+/// Exit code 1 when any change is breaking, 2 when the files can't be compared.
+fn schema_diff_mode(old_path: &str, new_path: &str) -> i32 {
+    let diff = match schema_diff::diff_files(old_path, new_path) {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("{} {}", "❌".bright_red(), e);
+            return 2;
+        }
+    };
+
+    println!("{}", "━━━ Schema Diff ━━━".bright_purple().bold());
+    println!("{} → {}",
+        diff.old_version.as_deref().unwrap_or("?").bright_white(),
+        diff.new_version.as_deref().unwrap_or("?").bright_white()
+    );
+
+    for change in &diff.changes {
+        let label = match change.kind {
+            schema_diff::ChangeKind::Breaking => "✗ breaking     ".bright_red(),
+            schema_diff::ChangeKind::EnumWidening => "~ enum-widening".bright_yellow(),
+            schema_diff::ChangeKind::Additive => "+ additive     ".bright_green(),
+        };
+        println!("{} {}", label, change);
+    }
+
+    println!("\n{} {} breaking, {} enum-widening, {} additive",
+        "Summary:".bright_white(),
+        diff.count(schema_diff::ChangeKind::Breaking).to_string().bright_red(),
+        diff.count(schema_diff::ChangeKind::EnumWidening).to_string().bright_yellow(),
+        diff.count(schema_diff::ChangeKind::Additive).to_string().bright_green()
+    );
+
+    if diff.has_breaking_changes() { 1 } else { 0 }
+}
+
+fn verify_log_mode(dir: &std::path::Path) -> i32 {
+    println!("{} {}", "🔍 Verifying primary memory log:".bright_blue(), dir.display().to_string().bright_white());
+    let verification = event_log::verify(dir);
+    match verification.corruption {
+        None => {
+            println!("{} {} entries, checksums and chain intact", "✅".bright_green(), verification.verified);
+            0
+        }
+        Some(e) => {
+            println!("{} {} entries verified before the first bad record:", "❌".bright_red(), verification.verified);
+            println!("   {}", e.to_string().bright_red());
+            1
+        }
+    }
+}
+
 /// 🐛 DEBUG MODE: Simple command-line world inspector
 fn debug_mode() {
     println!("{}", "🐛 Debug Mode - ECS World Inspector".bright_yellow().bold());
@@ -568,10 +1166,11 @@ fn debug_mode() {
     println!("\n{} {}", "Total entities:".bright_white(), entity_count.to_string().bright_green());
     
     // Show law specifications
-    let law_specs = systems::LawSpecifications::from_schema();
+    let law_specs = load_law_specifications(&load_settings());
     println!("\n{}", "━━━ Physics Laws ━━━".bright_purple());
-    println!("🔄 Decay applies to: {:?}", law_specs.decay_law.applies_to);
-    println!("⚡ Resonance threshold: {}", law_specs.resonance_law.threshold);
+    for law in &law_specs.laws {
+        println!("⚖️  {} ({:?}) applies to {:?}: {}", law.name(), law.law.trigger, law.law.applies_to, law.formula);
+    }
     
     println!("\n{}", "🚀 Run with GraphQL interface: cargo run".bright_green());
     println!("{}", "📊 GraphiQL available at: http://127.0.0.1:8000".bright_blue());
@@ -0,0 +1,209 @@
+// Schema compatibility checks and payload migrations.
+// Persisted entries and law files are stamped with the schema they were produced under;
+// anything from an incompatible schema is refused, older compatible payloads are upgraded on load.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::generated::{SCHEMA_ENTITY_MODELS, SCHEMA_GENERATED_BY, SCHEMA_HASH, SCHEMA_VERSION};
+
+/// Schema identity carried by persisted data and exported law files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaStamp {
+    pub schema_version: String,
+    #[serde(default)]
+    pub generated_by: Option<String>,
+    #[serde(default)]
+    pub schema_hash: Option<String>,
+}
+
+impl SchemaStamp {
+    /// Stamp for the schema this binary was built against
+    pub fn current() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            generated_by: Some(SCHEMA_GENERATED_BY.to_string()),
+            schema_hash: Some(SCHEMA_HASH.to_string()),
+        }
+    }
+}
+
+/// Data written before entries were stamped: the first release of this major version, hash unknown
+impl Default for SchemaStamp {
+    fn default() -> Self {
+        Self {
+            schema_version: format!("{}.0.0", SchemaVersion::current().major),
+            generated_by: None,
+            schema_hash: None,
+        }
+    }
+}
+
+impl fmt::Display for SchemaStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.schema_hash {
+            Some(hash) => write!(f, "v{} ({})", self.schema_version, hash),
+            None => write!(f, "v{}", self.schema_version),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SchemaVersion {
+    pub fn parse(version: &str) -> Result<Self, SchemaError> {
+        let parts: Vec<u64> = version
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| SchemaError::InvalidVersion(version.to_string()))?;
+        match parts.as_slice() {
+            [major, minor, patch] => Ok(Self { major: *major, minor: *minor, patch: *patch }),
+            _ => Err(SchemaError::InvalidVersion(version.to_string())),
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::parse(SCHEMA_VERSION).expect("generated SCHEMA_VERSION is semver")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Same version and content hash - load as-is
+    Identical,
+    /// Older minor/patch (or same version with a different hash) - run migrations first
+    Migratable { from: SchemaVersion },
+}
+
+/// Decide whether data stamped with `stamp` can be loaded by this build.
+/// A different major version, or any newer version than ours (patch included - we can't know
+/// what it added), is refused.
+pub fn check_compatibility(stamp: &SchemaStamp) -> Result<Compatibility, SchemaError> {
+    let found = SchemaVersion::parse(&stamp.schema_version)?;
+    let current = SchemaVersion::current();
+
+    let incompatible = || SchemaError::Incompatible {
+        found: stamp.to_string(),
+        expected: SchemaStamp::current().to_string(),
+    };
+
+    if found.major != current.major || found > current {
+        return Err(incompatible());
+    }
+    let same_hash = stamp.schema_hash.as_deref().is_none_or(|hash| hash == SCHEMA_HASH);
+    if found == current && same_hash {
+        Ok(Compatibility::Identical)
+    } else {
+        Ok(Compatibility::Migratable { from: found })
+    }
+}
+
+/// Upgrades a raw payload of one model to the current schema before typed deserialization
+pub trait Migration: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Model this migration targets; "BaseEntity" covers every model that flattens it
+    fn model(&self) -> &'static str;
+
+    /// Whether payloads written under `from` need this migration
+    fn applies_to(&self, _from: &SchemaVersion) -> bool {
+        true
+    }
+
+    fn migrate(&self, payload: &mut Value) -> Result<(), SchemaError>;
+}
+
+/// Fills in versioning fields that predate `parent_version`
+pub struct BaseEntityVersioning;
+
+impl Migration for BaseEntityVersioning {
+    fn name(&self) -> &'static str {
+        "base_entity_versioning"
+    }
+
+    fn model(&self) -> &'static str {
+        "BaseEntity"
+    }
+
+    fn migrate(&self, payload: &mut Value) -> Result<(), SchemaError> {
+        let object = payload.as_object_mut().ok_or_else(|| SchemaError::Migration {
+            model: self.model().to_string(),
+            reason: "payload is not an object".to_string(),
+        })?;
+
+        let version = object.get("version").and_then(Value::as_i64).unwrap_or(1);
+        object.insert("version".to_string(), Value::from(version));
+        if object.get("parent_version").is_none_or(Value::is_null) {
+            let parent = if version > 1 { Value::from(version - 1) } else { Value::Null };
+            object.insert("parent_version".to_string(), parent);
+        }
+        Ok(())
+    }
+}
+
+/// Ordered set of migrations applied on load
+pub struct Migrations {
+    steps: Vec<Box<dyn Migration>>,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Self {
+            steps: vec![Box::new(BaseEntityVersioning)],
+        }
+    }
+}
+
+impl Migrations {
+    pub fn empty() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn register(&mut self, migration: impl Migration + 'static) {
+        self.steps.push(Box::new(migration));
+    }
+
+    /// Run every migration targeting `model` on a payload written under `from`
+    pub fn apply(&self, model: &str, from: &SchemaVersion, payload: &mut Value) -> Result<usize, SchemaError> {
+        let mut applied = 0;
+        for step in &self.steps {
+            let targets_model = step.model() == model
+                || (step.model() == "BaseEntity" && SCHEMA_ENTITY_MODELS.contains(&model));
+            if targets_model && step.applies_to(from) {
+                step.migrate(payload)?;
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Check the stamp, migrate if needed, then deserialize into the generated type
+    pub fn load<T: DeserializeOwned>(&self, stamp: &SchemaStamp, model: &str, mut payload: Value) -> Result<T, SchemaError> {
+        if let Compatibility::Migratable { from } = check_compatibility(stamp)? {
+            self.apply(model, &from, &mut payload)?;
+        }
+        serde_json::from_value(payload).map_err(|e| SchemaError::Deserialization(format!("{}: {}", model, e)))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaError {
+    #[error("Incompatible schema: data written under {found}, hot path built against {expected}")]
+    Incompatible { found: String, expected: String },
+
+    #[error("Invalid schema version: {0}")]
+    InvalidVersion(String),
+
+    #[error("Migration failed for {model}: {reason}")]
+    Migration { model: String, reason: String },
+
+    #[error("Deserialization error: {0}")]
+    Deserialization(String),
+}
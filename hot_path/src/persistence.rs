@@ -1,21 +1,106 @@
 use std::collections::HashMap;
+use std::path::Path;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use tokio::time::{interval, Duration};
 
+use crate::checkpoint::{self, Checkpoint, CheckpointError};
+use crate::event_log::{self, EventLog, LogError, LogRecord, Records, SyncPolicy, Verification};
+use crate::migration::{Migrations, SchemaError, SchemaStamp};
+use crate::replay::ReplayLimit;
+
 // Note: These will be used when full persistence integration is implemented
 #[allow(unused_imports)]
 use crate::components::{MemoryLayer, MemoryLayerType, ConsolidationStatus};
 
-/// Persistence layer that manages working memory (Redis) and primary memory (DB)
+/// Where and how primary memory is stored (`[persistence]` in the config)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PersistenceConfig {
+    /// Directory of the event log, relative to the working directory unless absolute
+    #[serde(default = "default_dir")]
+    pub dir: String,
+    /// Size at which the log starts a new segment file
+    #[serde(default = "default_segment_bytes")]
+    pub segment_bytes: u64,
+    #[serde(default)]
+    pub fsync: SyncPolicy,
+    /// Longest a record may stay unsynced under `fsync = "interval"`
+    #[serde(default = "default_sync_interval_ms")]
+    pub sync_interval_ms: u64,
+    /// Rebuild the world only from entries up to this time (RFC 3339) on startup
+    #[serde(default)]
+    pub replay_until: Option<DateTime<Utc>>,
+    /// Rebuild the world only from entries up to this sequence number (log version) on startup
+    #[serde(default)]
+    pub replay_until_sequence: Option<u64>,
+    /// How often the whole world is checkpointed next to the log (0 disables checkpoints)
+    #[serde(default = "default_checkpoint_interval_secs")]
+    pub checkpoint_interval_secs: u64,
+    /// Checkpoints kept on disk; the log is compacted up to the oldest of them
+    #[serde(default = "default_keep_checkpoints")]
+    pub keep_checkpoints: usize,
+}
+
+fn default_dir() -> String {
+    "../data/primary_memory".to_string()
+}
+
+fn default_segment_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_sync_interval_ms() -> u64 {
+    1000
+}
+
+fn default_checkpoint_interval_secs() -> u64 {
+    300
+}
+
+fn default_keep_checkpoints() -> usize {
+    2
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_dir(),
+            segment_bytes: default_segment_bytes(),
+            fsync: SyncPolicy::default(),
+            sync_interval_ms: default_sync_interval_ms(),
+            replay_until: None,
+            replay_until_sequence: None,
+            checkpoint_interval_secs: default_checkpoint_interval_secs(),
+            keep_checkpoints: default_keep_checkpoints(),
+        }
+    }
+}
+
+impl PersistenceConfig {
+    /// How much of the log startup replays
+    pub fn replay_limit(&self) -> ReplayLimit {
+        ReplayLimit { until: self.replay_until, until_sequence: self.replay_until_sequence }
+    }
+}
+
+/// Persistence layer that manages working memory (Redis) and primary memory (the event log)
 pub struct MemoryPersistence {
     // Redis client would go here in real implementation
     // redis_client: redis::Client,
-    
-    // ChronicleDB client would go here in real implementation
-    // chronicle_client: ChronicleClient,
-    
+
+    // Append-only primary memory; None for a working-memory-only instance
+    primary: Option<EventLog>,
+
+    // Why appends are refused, when the world was restored from only part of the log
+    read_only: Option<String>,
+
+    // Checkpoints kept next to the log, and how often to take one
+    keep_checkpoints: usize,
+    checkpoint_interval: Option<std::time::Duration>,
+    last_checkpoint: std::time::Instant,
+
     // Local cache for working memory
     working_memory: HashMap<Uuid, WorkingMemoryEntry>,
     
@@ -23,6 +108,12 @@ pub struct MemoryPersistence {
     consolidation_queue: Vec<Uuid>,
 }
 
+impl Default for MemoryPersistence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkingMemoryEntry {
     pub entity_id: Uuid,
@@ -37,29 +128,95 @@ pub struct WorkingMemoryEntry {
 pub struct PrimaryMemoryEntry {
     pub entity_id: Uuid,
     pub version: u64,
+    /// Version this one was derived from; None for an entity's first version
+    #[serde(default)]
+    pub parent_version: Option<u64>,
     pub data: Vec<u8>,
     pub timestamp: DateTime<Utc>,
     pub event_type: EventType,
+    /// SHA-256 (hex) of this entry's content and `prev_checksum`; set by the log on append
     pub checksum: String,
+    #[serde(default)]
+    pub schema: SchemaStamp, // Schema the payload was serialized under
+    /// Checksum of the entry appended before this one; empty for the first
+    #[serde(default)]
+    pub prev_checksum: String,
+    /// Position in the log, from 1 - the log's version once this entry is appended; set by the log
+    #[serde(default)]
+    pub sequence: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PrimaryMemoryEntry {
+    /// Hash of every field but `checksum` itself, each length-prefixed so no two entries collide by concatenation
+    pub fn compute_checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        let mut field = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        field(self.prev_checksum.as_bytes());
+        field(&self.sequence.to_le_bytes());
+        field(self.entity_id.as_bytes());
+        field(&self.version.to_le_bytes());
+        field(&serde_json::to_vec(&self.parent_version).unwrap_or_default());
+        field(self.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true).as_bytes());
+        field(&serde_json::to_vec(&self.event_type).unwrap_or_default());
+        field(&serde_json::to_vec(&self.schema).unwrap_or_default());
+        field(&self.data);
+
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Deserialize the stored entity, refusing entries written under an incompatible schema
+    pub fn decode<T: serde::de::DeserializeOwned>(&self, model: &str, migrations: &Migrations) -> Result<T, PersistenceError> {
+        let payload: serde_json::Value = serde_json::from_slice(&self.data)
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        Ok(migrations.load(&self.schema, model, payload)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EventType {
     EntityCreated,
     ComponentUpdated,
     EntityTagAdded,
     EntitySoftDeleted,
     EntityConsolidated,
+    /// Despawned from the world (e.g. a faded bond); replay drops it
+    EntityRemoved,
 }
 
 impl MemoryPersistence {
+    /// Working memory only: appends to primary memory fail
     pub fn new() -> Self {
         Self {
+            primary: None,
+            read_only: None,
+            keep_checkpoints: default_keep_checkpoints(),
+            checkpoint_interval: None,
+            last_checkpoint: std::time::Instant::now(),
             working_memory: HashMap::new(),
             consolidation_queue: Vec::new(),
         }
     }
 
+    /// Open (or create) the primary memory log in `dir`
+    pub fn open(dir: &Path, config: &PersistenceConfig) -> Result<Self, PersistenceError> {
+        let log = EventLog::open(
+            dir,
+            config.segment_bytes,
+            config.fsync,
+            std::time::Duration::from_millis(config.sync_interval_ms),
+        )?;
+        Ok(Self {
+            primary: Some(log),
+            keep_checkpoints: config.keep_checkpoints,
+            checkpoint_interval: (config.checkpoint_interval_secs > 0)
+                .then(|| std::time::Duration::from_secs(config.checkpoint_interval_secs)),
+            ..Self::new()
+        })
+    }
+
     /// Store entity in working memory (Redis in real implementation)
     pub async fn store_working_memory(&mut self, entity_id: Uuid, data: Vec<u8>, ttl_seconds: Option<u64>) -> Result<(), PersistenceError> {
         let entry = WorkingMemoryEntry {
@@ -92,12 +249,93 @@ impl MemoryPersistence {
         }
     }
 
-    /// Append-only write to primary memory (ChronicleDB in real implementation)
-    pub async fn append_primary_memory(&self, entry: PrimaryMemoryEntry) -> Result<(), PersistenceError> {
-        // TODO: ChronicleDB implementation
+    fn primary_log(&mut self) -> Result<&mut EventLog, PersistenceError> {
+        self.primary.as_mut().ok_or_else(|| PersistenceError::Database("primary memory is not open".to_string()))
+    }
+
+    /// Record the log version the restored world reflects. When the log holds entries past it - a
+    /// replay limit, or a damaged tail only partly replayed - primary memory turns read-only: new
+    /// entries would land after changes the world never saw, and the next restart would lose them.
+    pub fn restored(&mut self, sequence: u64) {
+        let last = self.primary.as_ref().map_or(0, EventLog::last_sequence);
+        if sequence < last {
+            self.read_only = Some(format!("the world was restored up to entry {} of {}", sequence, last));
+        }
+    }
+
+    /// Why appends are refused, if they are
+    pub fn read_only(&self) -> Option<&str> {
+        self.read_only.as_deref()
+    }
+
+    /// Append-only write to primary memory; the log chains and checksums the entry
+    pub fn append_primary_memory(&mut self, entry: PrimaryMemoryEntry) -> Result<LogRecord, PersistenceError> {
+        if let Some(reason) = &self.read_only {
+            return Err(PersistenceError::ReadOnly(reason.clone()));
+        }
         // Event sourcing: all changes are appended, never updated
-        println!("📚 Appending to primary memory: {:?} v{}", entry.entity_id, entry.version);
-        Ok(())
+        Ok(self.primary_log()?.append(entry)?)
+    }
+
+    /// Check every primary memory entry and the chain between them
+    pub fn verify_primary_memory(&self) -> Option<Verification> {
+        self.primary.as_ref().map(|log| event_log::verify(log.dir()))
+    }
+
+    /// Every primary memory entry, oldest first
+    pub fn primary_memory(&self) -> Result<Vec<LogRecord>, PersistenceError> {
+        match &self.primary {
+            Some(log) => Ok(log.read_all()?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Force appended entries to disk, whatever the fsync policy
+    pub fn sync(&mut self) -> Result<(), PersistenceError> {
+        Ok(self.primary_log()?.sync()?)
+    }
+
+    /// Whether `checkpoint_interval_secs` has passed since the last checkpoint attempt; never while
+    /// read-only, as that world lags the log
+    pub fn checkpoint_due(&self) -> bool {
+        self.primary.is_some() && self.read_only.is_none() && self.checkpoint_interval.is_some_and(|interval| self.last_checkpoint.elapsed() >= interval)
+    }
+
+    /// Checkpoint `world`, which reflects the log up to entry `sequence`, then compact the log up
+    /// to the oldest checkpoint kept. None when nothing was appended since the last checkpoint.
+    /// Refused while `sequence` lags the log's version: a checkpoint stamped past what the world
+    /// saw would let compaction delete entries it never applied.
+    pub fn checkpoint(&mut self, world: &hecs::World, sequence: u64) -> Result<Option<CheckpointReport>, PersistenceError> {
+        self.last_checkpoint = std::time::Instant::now();
+        let keep = self.keep_checkpoints;
+        let log = self.primary_log()?;
+        if sequence < log.last_sequence() {
+            return Err(PersistenceError::WorldBehindLog { world: sequence, log: log.last_sequence() });
+        }
+        // The checkpoint must never be ahead of what is durable in the log
+        log.sync()?;
+        let dir = log.dir().to_path_buf();
+        let previous = checkpoint::latest(&dir, ReplayLimit::none())?;
+        let covered = previous.as_ref().map_or(0, |checkpoint| checkpoint.sequence);
+        if log.last_sequence() == covered {
+            return Ok(None);
+        }
+
+        let mut entries = Vec::new();
+        for record in Records::open(&dir)? {
+            let record = record?;
+            if record.entry.sequence > covered {
+                entries.push(record.entry);
+            }
+        }
+        let checkpoint = Checkpoint::capture(world, previous.as_ref(), &entries);
+        checkpoint::write(&dir, &checkpoint)?;
+
+        let compacted = match checkpoint::prune(&dir, keep)? {
+            Some(oldest) => log.compact(oldest)?.len(),
+            None => 0,
+        };
+        Ok(Some(CheckpointReport { sequence: checkpoint.sequence, entities: checkpoint.entities.len(), compacted }))
     }
 
     /// Queue entity for consolidation from working to primary memory
@@ -115,18 +353,21 @@ impl MemoryPersistence {
         for entity_id in entity_ids {
             if let Some(working_entry) = self.working_memory.get(&entity_id) {
                 let data = working_entry.data.clone();
-                let checksum = self.calculate_checksum(&data);
-                
+
                 let primary_entry = PrimaryMemoryEntry {
                     entity_id,
                     version: 1, // TODO: Get actual version from entity
+                    parent_version: None,
                     data,
                     timestamp: Utc::now(),
                     event_type: EventType::EntityConsolidated,
-                    checksum,
+                    checksum: String::new(),
+                    schema: SchemaStamp::current(),
+                    prev_checksum: String::new(),
+                    sequence: 0,
                 };
 
-                self.append_primary_memory(primary_entry).await?;
+                self.append_primary_memory(primary_entry)?;
                 processed += 1;
             }
         }
@@ -165,11 +406,6 @@ impl MemoryPersistence {
         }
     }
 
-    fn calculate_checksum(&self, data: &[u8]) -> String {
-        // Simple checksum for now - real implementation would use proper hashing
-        format!("{:x}", data.len())
-    }
-
     /// Start background consolidation process
     pub async fn start_background_consolidation(&mut self) {
         let mut consolidation_interval = interval(Duration::from_secs(30));
@@ -196,6 +432,16 @@ impl MemoryPersistence {
     }
 }
 
+/// What `MemoryPersistence::checkpoint` wrote and dropped
+#[derive(Debug, Clone, Copy)]
+pub struct CheckpointReport {
+    /// Log version the checkpoint reflects
+    pub sequence: u64,
+    pub entities: usize,
+    /// Log entries compaction removed
+    pub compacted: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct MemoryStats {
     pub working_memory_entries: usize,
@@ -216,4 +462,19 @@ pub enum PersistenceError {
     
     #[error("Entity not found: {0}")]
     EntityNotFound(Uuid),
+
+    #[error("Schema error: {0}")]
+    Schema(#[from] SchemaError),
+
+    #[error("Event log error: {0}")]
+    Log(#[from] LogError),
+
+    #[error("Checkpoint error: {0}")]
+    Checkpoint(#[from] CheckpointError),
+
+    #[error("Primary memory is read-only: {0}")]
+    ReadOnly(String),
+
+    #[error("The world reflects log entry {world} but the log is at {log}")]
+    WorldBehindLog { world: u64, log: u64 },
 } 
\ No newline at end of file
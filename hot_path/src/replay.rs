@@ -0,0 +1,332 @@
+// Event-sourced world reconstruction from primary memory.
+// Every entry carries the entity's full state after the event as an `EntityRecord`: the schema
+// model (stamped, so it goes through the migrations on the way back in) plus the runtime
+// components around it. Replay is therefore an upsert per entry, except `EntityRemoved`, which
+// drops the entity. Replaying only a prefix of the log (up to a timestamp or sequence number)
+// rebuilds the world as it was at that point.
+
+use chrono::{DateTime, Utc};
+use hecs::{Entity, EntityBuilder, World};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::components::{
+    with_base_entity, Affinity, AttachedComponents, BaseComponent, Binding, Bond, DecayComponent,
+    DisplayText, EntityType, Filament, MemoryLayer, Moment, Motif, SchemaEntity, TemporalPosition, Thread, ThreadId,
+    ThreadName, ThreadType,
+};
+use crate::event_log::{LogError, Records};
+use crate::index::EntityIndex;
+use crate::migration::{Migrations, SchemaStamp};
+use crate::persistence::{EventType, PersistenceError, PrimaryMemoryEntry};
+
+/// Everything needed to respawn one entity: its schema model and the components around it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityRecord {
+    /// Schema model name, e.g. "Moment"
+    pub model: String,
+    /// The model itself, as serialized under the entry's schema stamp
+    pub entity: serde_json::Value,
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub display_text: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub thread_type: Option<String>,
+    #[serde(default)]
+    pub thread_name: Option<String>,
+    #[serde(default)]
+    pub decay: Option<DecayComponent>,
+    #[serde(default)]
+    pub affinity: Option<f64>,
+    #[serde(default)]
+    pub temporal_position: Option<TemporalPosition>,
+    #[serde(default)]
+    pub memory_layer: Option<MemoryLayer>,
+    #[serde(default)]
+    pub components: Vec<BaseComponent>,
+}
+
+impl EntityRecord {
+    /// Record a schema entity's current state; None for entities without a schema model
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        fn model<T: SchemaEntity + Serialize + hecs::Component>(
+            world: &World,
+            entity: Entity,
+            name: &str,
+        ) -> Option<(String, serde_json::Value)> {
+            let component = world.get::<&T>(entity).ok()?;
+            Some((name.to_string(), serde_json::to_value(&*component).ok()?))
+        }
+
+        let (model, value) = model::<Moment>(world, entity, "Moment")
+            .or_else(|| model::<Thread>(world, entity, "Thread"))
+            .or_else(|| model::<Binding>(world, entity, "Binding"))
+            .or_else(|| model::<Bond>(world, entity, "Bond"))
+            .or_else(|| model::<Filament>(world, entity, "Filament"))
+            .or_else(|| model::<Motif>(world, entity, "Motif"))?;
+
+        Some(Self {
+            model,
+            entity: value,
+            entity_type: text(world, entity, |c: &EntityType| &c.0),
+            display_text: text(world, entity, |c: &DisplayText| &c.0),
+            thread_id: text(world, entity, |c: &ThreadId| &c.0),
+            thread_type: text(world, entity, |c: &ThreadType| &c.0),
+            thread_name: text(world, entity, |c: &ThreadName| &c.0),
+            decay: world.get::<&DecayComponent>(entity).ok().map(|decay| (*decay).clone()),
+            affinity: world.get::<&Affinity>(entity).ok().map(|affinity| affinity.0),
+            temporal_position: world.get::<&TemporalPosition>(entity).ok().map(|position| (*position).clone()),
+            memory_layer: world.get::<&MemoryLayer>(entity).ok().map(|layer| (*layer).clone()),
+            components: world
+                .get::<&AttachedComponents>(entity)
+                .map(|attached| attached.0.clone())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Spawn the recorded entity, migrating its model from `schema` if needed
+    pub fn restore(
+        &self,
+        world: &mut World,
+        index: &mut EntityIndex,
+        schema: &SchemaStamp,
+        migrations: &Migrations,
+    ) -> Result<Entity, PersistenceError> {
+        let (mut builder, _id) = self.builder(schema, migrations)?;
+        Ok(index.spawn(world, builder.build()))
+    }
+
+    /// Decode the record into a bundle ready to spawn, along with the model's `BaseEntity.id`
+    fn builder(&self, schema: &SchemaStamp, migrations: &Migrations) -> Result<(EntityBuilder, Option<Uuid>), PersistenceError> {
+        fn load<T: SchemaEntity + DeserializeOwned + hecs::Component>(
+            builder: &mut EntityBuilder,
+            record: &EntityRecord,
+            schema: &SchemaStamp,
+            migrations: &Migrations,
+        ) -> Result<Option<Uuid>, PersistenceError> {
+            let model: T = migrations.load(schema, &record.model, record.entity.clone())?;
+            let id = model.base().id;
+            builder.add(model);
+            Ok(id)
+        }
+
+        let mut builder = EntityBuilder::new();
+        let id = match self.model.as_str() {
+            "Moment" => load::<Moment>(&mut builder, self, schema, migrations)?,
+            "Thread" => load::<Thread>(&mut builder, self, schema, migrations)?,
+            "Binding" => load::<Binding>(&mut builder, self, schema, migrations)?,
+            "Bond" => load::<Bond>(&mut builder, self, schema, migrations)?,
+            "Filament" => load::<Filament>(&mut builder, self, schema, migrations)?,
+            "Motif" => load::<Motif>(&mut builder, self, schema, migrations)?,
+            other => return Err(PersistenceError::Serialization(format!("unknown entity model `{}`", other))),
+        };
+
+        if let Some(entity_type) = &self.entity_type {
+            builder.add(EntityType(entity_type.clone()));
+        }
+        if let Some(text) = &self.display_text {
+            builder.add(DisplayText(text.clone()));
+        }
+        if let Some(thread_id) = &self.thread_id {
+            builder.add(ThreadId(thread_id.clone()));
+        }
+        if let Some(thread_type) = &self.thread_type {
+            builder.add(ThreadType(thread_type.clone()));
+        }
+        if let Some(thread_name) = &self.thread_name {
+            builder.add(ThreadName(thread_name.clone()));
+        }
+        if let Some(decay) = &self.decay {
+            builder.add(decay.clone());
+        }
+        if let Some(affinity) = self.affinity {
+            builder.add(Affinity(affinity));
+        }
+        if let Some(position) = &self.temporal_position {
+            builder.add(position.clone());
+        }
+        if let Some(layer) = &self.memory_layer {
+            builder.add(layer.clone());
+        }
+        if !self.components.is_empty() {
+            builder.add(AttachedComponents(self.components.clone()));
+        }
+        Ok((builder, id))
+    }
+}
+
+/// Clone the String inside a newtype component (EntityType, DisplayText, ThreadId, ...)
+fn text<T: hecs::Component>(world: &World, entity: Entity, inner: fn(&T) -> &String) -> Option<String> {
+    world.get::<&T>(entity).ok().map(|component| inner(&component).clone())
+}
+
+/// A primary memory entry carrying `entity`'s current state; the log fills in sequence and checksums
+pub fn entry(world: &World, entity: Entity, event_type: EventType) -> Option<PrimaryMemoryEntry> {
+    let record = EntityRecord::capture(world, entity)?;
+    let (entity_id, version, parent_version) =
+        with_base_entity(world, entity, |base| (base.id, base.version, base.parent_version))?;
+    Some(PrimaryMemoryEntry {
+        entity_id: entity_id?,
+        version: version.max(0) as u64,
+        parent_version: parent_version.map(|parent| parent.max(0) as u64),
+        data: serde_json::to_vec(&record).ok()?,
+        timestamp: Utc::now(),
+        event_type,
+        checksum: String::new(),
+        schema: SchemaStamp::current(),
+        prev_checksum: String::new(),
+        sequence: 0,
+    })
+}
+
+/// A primary memory entry for an entity that left the world; it carries no state
+pub fn removal_entry(entity_id: Uuid, version: u64) -> PrimaryMemoryEntry {
+    PrimaryMemoryEntry {
+        entity_id,
+        version,
+        parent_version: None,
+        data: b"null".to_vec(),
+        timestamp: Utc::now(),
+        event_type: EventType::EntityRemoved,
+        checksum: String::new(),
+        schema: SchemaStamp::current(),
+        prev_checksum: String::new(),
+        sequence: 0,
+    }
+}
+
+/// How far into the log to replay; both bounds are inclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayLimit {
+    /// Last timestamp to include
+    pub until: Option<DateTime<Utc>>,
+    /// Last sequence number (log version) to include
+    pub until_sequence: Option<u64>,
+}
+
+impl ReplayLimit {
+    /// Replay the whole log
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.until.is_some() || self.until_sequence.is_some()
+    }
+
+    /// Whether the state as of entry `sequence`, written at `timestamp`, is within the limit
+    pub fn admits(&self, sequence: u64, timestamp: Option<DateTime<Utc>>) -> bool {
+        self.until.is_none_or(|until| timestamp.is_none_or(|timestamp| timestamp <= until))
+            && self.until_sequence.is_none_or(|until| sequence <= until)
+    }
+}
+
+/// What a replay did
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    /// Entries applied to the world
+    pub applied: usize,
+    /// Entries that couldn't be decoded, with the reason; the rest of the log still applies
+    pub skipped: Vec<(u64, String)>,
+    /// Sequence number of the last entry applied or skipped
+    pub last_sequence: u64,
+    /// Timestamp of that entry
+    pub last_timestamp: Option<DateTime<Utc>>,
+    /// Set when reading stopped early at a damaged record
+    pub corruption: Option<LogError>,
+}
+
+/// A world rebuilt from primary memory
+pub struct Replayed {
+    pub world: World,
+    pub index: EntityIndex,
+    pub report: ReplayReport,
+}
+
+impl Replayed {
+    pub fn new() -> Self {
+        Self { world: World::new(), index: EntityIndex::new(), report: ReplayReport::default() }
+    }
+
+    /// Apply entries in log order until one falls past `limit`; entries the world already
+    /// reflects (up to `report.last_sequence`, e.g. from a checkpoint) are passed over
+    pub fn replay(&mut self, entries: impl IntoIterator<Item = PrimaryMemoryEntry>, limit: ReplayLimit, migrations: &Migrations) {
+        for entry in entries {
+            if entry.sequence <= self.report.last_sequence {
+                continue;
+            }
+            if !limit.admits(entry.sequence, Some(entry.timestamp)) {
+                break;
+            }
+            match apply(&mut self.world, &mut self.index, &entry, migrations) {
+                Ok(()) => self.report.applied += 1,
+                Err(e) => self.report.skipped.push((entry.sequence, e.to_string())),
+            }
+            self.report.last_sequence = entry.sequence;
+            self.report.last_timestamp = Some(entry.timestamp);
+        }
+    }
+
+    /// Replay the log in `dir` on top of this world, stopping at the first damaged record
+    pub fn replay_dir(&mut self, dir: &std::path::Path, limit: ReplayLimit, migrations: &Migrations) -> Result<(), LogError> {
+        let mut corruption = None;
+        let entries = Records::open(dir)?.map_while(|record| match record {
+            Ok(record) => Some(record.entry),
+            Err(e) => {
+                corruption = Some(e);
+                None
+            }
+        });
+        self.replay(entries, limit, migrations);
+        self.report.corruption = corruption;
+        Ok(())
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.world.len() as usize
+    }
+}
+
+impl Default for Replayed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rebuild the world from the log in `dir` alone, stopping at the first damaged record
+pub fn replay_dir(dir: &std::path::Path, limit: ReplayLimit, migrations: &Migrations) -> Result<Replayed, LogError> {
+    let mut replayed = Replayed::new();
+    replayed.replay_dir(dir, limit, migrations)?;
+    Ok(replayed)
+}
+
+/// Apply one entry: respawn the entity from its record, or drop it for `EntityRemoved`
+pub fn apply(world: &mut World, index: &mut EntityIndex, entry: &PrimaryMemoryEntry, migrations: &Migrations) -> Result<(), PersistenceError> {
+    if entry.event_type == EventType::EntityRemoved {
+        if let Some(entity) = index.entity(&entry.entity_id) {
+            let _ = index.despawn(world, entity);
+        }
+        return Ok(());
+    }
+
+    let record: EntityRecord = serde_json::from_slice(&entry.data)
+        .map_err(|e| PersistenceError::Serialization(format!("entry {}: {}", entry.sequence, e)))?;
+    // Decode before touching the world, so a bad entry leaves the previous version in place
+    let (mut builder, id) = record.builder(&entry.schema, migrations)?;
+    if id != Some(entry.entity_id) {
+        return Err(PersistenceError::Serialization(format!(
+            "entry {} is for {} but its record holds a different entity",
+            entry.sequence, entry.entity_id
+        )));
+    }
+
+    if let Some(existing) = index.entity(&entry.entity_id) {
+        let _ = index.despawn(world, existing);
+    }
+    index.spawn(world, builder.build());
+    Ok(())
+}
@@ -0,0 +1,280 @@
+// Compares two cold path schema exports model-by-model so a re-export that would
+// break the hot path (or silently fall back to serde defaults) is caught before build.
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    /// New optional field or new model - existing payloads still load
+    Additive,
+    /// New enum variants - old payloads load, exhaustive matches need updating
+    EnumWidening,
+    /// Removed/renamed field, type change, new required field, removed variant or model
+    Breaking,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Additive => write!(f, "additive"),
+            ChangeKind::EnumWidening => write!(f, "enum-widening"),
+            ChangeKind::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub model: String,
+    pub field: Option<String>,
+    pub kind: ChangeKind,
+    pub detail: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}.{}: {}", self.model, field, self.detail),
+            None => write!(f, "{}: {}", self.model, self.detail),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDiff {
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|change| change.kind == ChangeKind::Breaking)
+    }
+
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|change| change.kind == kind).count()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaDiffError {
+    #[error("Failed to read {path}: {reason}")]
+    Io { path: String, reason: String },
+
+    #[error("Invalid schema export: {0}")]
+    Invalid(String),
+}
+
+/// Load and diff two schema export files
+pub fn diff_files(old_path: &str, new_path: &str) -> Result<SchemaDiff, SchemaDiffError> {
+    let read = |path: &str| -> Result<Value, SchemaDiffError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| SchemaDiffError::Io {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?;
+        serde_json::from_str(&raw).map_err(|e| SchemaDiffError::Invalid(format!("{}: {}", path, e)))
+    };
+    diff_schemas(&read(old_path)?, &read(new_path)?)
+}
+
+/// Diff two parsed schema exports (`{ schema_version, generated_by, models }`)
+pub fn diff_schemas(old: &Value, new: &Value) -> Result<SchemaDiff, SchemaDiffError> {
+    let models = |schema: &Value| -> Result<Map<String, Value>, SchemaDiffError> {
+        schema
+            .get("models")
+            .and_then(Value::as_object)
+            .cloned()
+            .ok_or_else(|| SchemaDiffError::Invalid("missing `models` map".to_string()))
+    };
+    let old_models = models(old)?;
+    let new_models = models(new)?;
+
+    let mut diff = SchemaDiff {
+        old_version: old.get("schema_version").and_then(Value::as_str).map(str::to_string),
+        new_version: new.get("schema_version").and_then(Value::as_str).map(str::to_string),
+        changes: Vec::new(),
+    };
+
+    for (name, old_model) in &old_models {
+        match new_models.get(name) {
+            Some(new_model) => diff_model(name, old_model, new_model, &mut diff.changes),
+            None => diff.changes.push(SchemaChange {
+                model: name.clone(),
+                field: None,
+                kind: ChangeKind::Breaking,
+                detail: "model removed".to_string(),
+            }),
+        }
+    }
+    for name in new_models.keys().filter(|name| !old_models.contains_key(*name)) {
+        diff.changes.push(SchemaChange {
+            model: name.clone(),
+            field: None,
+            kind: ChangeKind::Additive,
+            detail: "model added".to_string(),
+        });
+    }
+
+    diff.changes.sort_by(|a, b| b.kind.cmp(&a.kind).then_with(|| a.model.cmp(&b.model)));
+    Ok(diff)
+}
+
+fn diff_model(name: &str, old: &Value, new: &Value, changes: &mut Vec<SchemaChange>) {
+    // Top-level enum models (Visibility, AccessScope, Cardinality)
+    if let (Some(old_values), Some(new_values)) = (enum_values(old), enum_values(new)) {
+        diff_enum_values(name, None, &old_values, &new_values, changes);
+        return;
+    }
+
+    let empty = Map::new();
+    let old_props = old.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    let new_props = new.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    let old_required = required_fields(old);
+    let new_required = required_fields(new);
+
+    let removed: Vec<&String> = old_props.keys().filter(|field| !new_props.contains_key(*field)).collect();
+    let mut added: Vec<&String> = new_props.keys().filter(|field| !old_props.contains_key(*field)).collect();
+
+    for field in &removed {
+        // A removed field with an added field of the same shape is most likely a rename; the
+        // field it was renamed to is claimed, so it is neither reported as added nor matched again
+        let signature = type_signature(&old_props[field.as_str()]);
+        let renamed_to = added
+            .iter()
+            .position(|candidate| type_signature(&new_props[candidate.as_str()]) == signature)
+            .map(|position| added.remove(position));
+        let detail = match renamed_to {
+            Some(to) => format!("field renamed to `{}` ({})", to, signature),
+            None => format!("field removed ({})", signature),
+        };
+        changes.push(field_change(name, field, ChangeKind::Breaking, detail));
+    }
+
+    for field in &added {
+        let signature = type_signature(&new_props[field.as_str()]);
+        if new_required.contains(&field.as_str()) {
+            changes.push(field_change(name, field, ChangeKind::Breaking, format!("new required field ({})", signature)));
+        } else {
+            changes.push(field_change(name, field, ChangeKind::Additive, format!("new optional field ({})", signature)));
+        }
+    }
+
+    for (field, old_prop) in old_props {
+        let Some(new_prop) = new_props.get(field) else {
+            continue;
+        };
+
+        let old_signature = type_signature(old_prop);
+        let new_signature = type_signature(new_prop);
+        if old_signature != new_signature {
+            changes.push(field_change(
+                name,
+                field,
+                ChangeKind::Breaking,
+                format!("type changed from {} to {}", old_signature, new_signature),
+            ));
+        } else if let (Some(old_values), Some(new_values)) = (nested_enum_values(old_prop), nested_enum_values(new_prop)) {
+            diff_enum_values(name, Some(field), &old_values, &new_values, changes);
+        }
+
+        let was_required = old_required.contains(&field.as_str());
+        let is_required = new_required.contains(&field.as_str());
+        if !was_required && is_required {
+            changes.push(field_change(name, field, ChangeKind::Breaking, "field became required".to_string()));
+        } else if was_required && !is_required {
+            changes.push(field_change(name, field, ChangeKind::Additive, "field became optional".to_string()));
+        }
+    }
+}
+
+fn diff_enum_values(model: &str, field: Option<&String>, old: &[String], new: &[String], changes: &mut Vec<SchemaChange>) {
+    let removed: Vec<&String> = old.iter().filter(|value| !new.contains(value)).collect();
+    let added: Vec<&String> = new.iter().filter(|value| !old.contains(value)).collect();
+
+    if !removed.is_empty() {
+        changes.push(SchemaChange {
+            model: model.to_string(),
+            field: field.cloned(),
+            kind: ChangeKind::Breaking,
+            detail: format!("enum values removed: {:?}", removed),
+        });
+    }
+    if !added.is_empty() {
+        changes.push(SchemaChange {
+            model: model.to_string(),
+            field: field.cloned(),
+            kind: ChangeKind::EnumWidening,
+            detail: format!("enum values added: {:?}", added),
+        });
+    }
+}
+
+fn field_change(model: &str, field: &str, kind: ChangeKind, detail: String) -> SchemaChange {
+    SchemaChange {
+        model: model.to_string(),
+        field: Some(field.to_string()),
+        kind,
+        detail,
+    }
+}
+
+fn required_fields(model: &Value) -> Vec<&str> {
+    model
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn enum_values(node: &Value) -> Option<Vec<String>> {
+    node.get("enum")
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).map(str::to_lowercase).collect())
+}
+
+/// Enum values on a property, looking through nullable `anyOf` and array `items`
+fn nested_enum_values(prop: &Value) -> Option<Vec<String>> {
+    if let Some(values) = enum_values(prop) {
+        return Some(values);
+    }
+    if let Some(items) = prop.get("items") {
+        return nested_enum_values(items);
+    }
+    prop.get("anyOf")
+        .and_then(Value::as_array)?
+        .iter()
+        .find_map(nested_enum_values)
+}
+
+/// Shape of a property as the Rust codegen sees it; enum values are compared separately
+fn type_signature(prop: &Value) -> String {
+    if let Some(reference) = prop.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    if let Some(variants) = prop.get("anyOf").and_then(Value::as_array) {
+        let non_null: Vec<String> = variants
+            .iter()
+            .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+            .map(type_signature)
+            .collect();
+        let inner = match non_null.as_slice() {
+            [single] => single.clone(),
+            many => format!("union<{}>", many.join("|")),
+        };
+        return if non_null.len() < variants.len() { format!("option<{}>", inner) } else { inner };
+    }
+    if prop.get("enum").is_some() {
+        return "enum".to_string();
+    }
+    match prop.get("type").and_then(Value::as_str) {
+        Some("string") => match prop.get("format").and_then(Value::as_str) {
+            Some(format) => format.to_string(),
+            None => "string".to_string(),
+        },
+        Some("array") => format!("array<{}>", prop.get("items").map(type_signature).unwrap_or_default()),
+        Some(other) => other.to_string(),
+        None => "any".to_string(),
+    }
+}
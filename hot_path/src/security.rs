@@ -0,0 +1,242 @@
+// Security enforcement law - the `security_enforcement` system law from the architecture doc.
+// Every GraphQL read and every command acts on behalf of a Principal. Access to an entity is
+// decided from its BaseEntity: `org_id` is a hard boundary, `security_level` is capped by the
+// principal's clearance, `visibility` decides who may see it and `access_scope` what others
+// may do with it beyond viewing. Owners (and org admins) have full control within those limits.
+// Requests authenticate with an API key; ApiKeys maps each key to the principal it acts for.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::components::{AccessScope, BaseEntity, Visibility};
+use crate::snapshot::{EntitySnapshot, WorldSnapshot};
+
+/// What a principal is allowed to do in its organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Read-only: may view what is shared with it, never create or change anything
+    Guest,
+    /// Full control of its own entities; others' entities as their `access_scope` allows
+    Member,
+    /// Full control of every entity in the organization
+    Admin,
+}
+
+impl Role {
+    /// Highest `security_level` this role may access
+    pub fn clearance(self) -> i64 {
+        match self {
+            Role::Guest => 0,
+            Role::Member => 1,
+            Role::Admin => 2,
+        }
+    }
+
+    /// Strongest scope this role may exercise on any entity, its own included
+    pub fn max_scope(self) -> AccessScope {
+        match self {
+            Role::Guest => AccessScope::View,
+            Role::Member | Role::Admin => AccessScope::Admin,
+        }
+    }
+}
+
+/// Who a request acts for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Principal {
+    pub user_id: Uuid,
+    pub org_id: Uuid,
+    /// Users sharing the principal's household; their `household` entities are visible to it
+    #[serde(default)]
+    pub household: Vec<Uuid>,
+    pub role: Role,
+    /// Strongest scope the principal's API key grants; caps what its role allows
+    #[serde(default = "full_scope")]
+    pub scope: AccessScope,
+}
+
+fn full_scope() -> AccessScope {
+    AccessScope::Admin
+}
+
+impl Default for Principal {
+    /// The single local user of an unauthenticated install
+    fn default() -> Self {
+        Self {
+            user_id: Uuid::from_u128(1),
+            org_id: Uuid::from_u128(0x100),
+            household: vec![],
+            role: Role::Member,
+            scope: full_scope(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AccessDenied {
+    #[error("entity belongs to another organization")]
+    OtherOrganization,
+
+    #[error("security level {level} is above clearance {clearance}")]
+    SecurityLevel { level: i64, clearance: i64 },
+
+    #[error("entity is {0:?} and not shared with this principal")]
+    NotVisible(Visibility),
+
+    #[error("{0:?} access is not granted")]
+    Scope(AccessScope),
+}
+
+/// View < Edit < Admin: a grant of one scope includes the weaker ones
+fn scope_rank(scope: AccessScope) -> u8 {
+    match scope {
+        AccessScope::View => 0,
+        AccessScope::Edit => 1,
+        AccessScope::Admin => 2,
+    }
+}
+
+impl Principal {
+    /// Strongest scope this principal may exercise: its role's, narrowed by its key's
+    pub fn max_scope(&self) -> AccessScope {
+        let role = self.role.max_scope();
+        if scope_rank(self.scope) < scope_rank(role) { self.scope } else { role }
+    }
+
+    /// Whether this principal may act on `base` with `scope`
+    pub fn check(&self, base: &BaseEntity, scope: AccessScope) -> Result<(), AccessDenied> {
+        // Organizations are a hard boundary; only public entities can be viewed across it
+        let same_org = base.org_id == self.org_id;
+        let public_view = scope == AccessScope::View && base.visibility == Visibility::Public;
+        if !same_org && !public_view {
+            return Err(AccessDenied::OtherOrganization);
+        }
+        if base.security_level > self.role.clearance() {
+            return Err(AccessDenied::SecurityLevel { level: base.security_level, clearance: self.role.clearance() });
+        }
+        if scope_rank(scope) > scope_rank(self.max_scope()) {
+            return Err(AccessDenied::Scope(scope));
+        }
+        if same_org && (base.owner_id == self.user_id || self.role == Role::Admin) {
+            return Ok(());
+        }
+
+        let visible = match base.visibility {
+            Visibility::Private => false,
+            Visibility::Household => self.household.contains(&base.owner_id),
+            Visibility::Org | Visibility::Public => true,
+        };
+        if !visible {
+            return Err(AccessDenied::NotVisible(base.visibility));
+        }
+
+        // Viewing is what visibility grants; anything more has to be in the entity's access_scope
+        let granted = base.access_scope.iter().any(|granted| scope_rank(*granted) >= scope_rank(scope));
+        if scope != AccessScope::View && !granted {
+            return Err(AccessDenied::Scope(scope));
+        }
+        Ok(())
+    }
+
+    pub fn can_view(&self, base: &BaseEntity) -> bool {
+        self.check(base, AccessScope::View).is_ok()
+    }
+
+    /// Whether this principal may create entities at all
+    pub fn can_create(&self) -> bool {
+        scope_rank(self.max_scope()) >= scope_rank(AccessScope::Edit)
+    }
+}
+
+/// API keys accepted by the GraphQL endpoint, each acting for one principal
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: HashMap<String, Principal>,
+    /// Who requests without a key act for; only set when no keys are configured at all
+    anonymous: Option<Principal>,
+}
+
+impl ApiKeys {
+    /// Empty keys are ignored; with none left, every request acts for `anonymous`
+    pub fn new(keys: impl IntoIterator<Item = (String, Principal)>, anonymous: Principal) -> Self {
+        let keys: HashMap<String, Principal> = keys.into_iter().filter(|(key, _)| !key.is_empty()).collect();
+        let anonymous = keys.is_empty().then_some(anonymous);
+        Self { keys, anonymous }
+    }
+
+    /// Whether requests have to present a key
+    pub fn required(&self) -> bool {
+        self.anonymous.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The principal a request presenting `key` (or none) acts for; `None` rejects the request
+    pub fn authenticate(&self, key: Option<&str>) -> Option<Principal> {
+        match key {
+            Some(key) if self.required() => self.keys.get(key).cloned(),
+            _ => self.anonymous.clone(),
+        }
+    }
+}
+
+/// A world snapshot as one principal sees it: entities it may not view are simply absent
+#[derive(Debug, Clone)]
+pub struct ScopedSnapshot {
+    snapshot: Arc<WorldSnapshot>,
+    principal: Arc<Principal>,
+}
+
+impl ScopedSnapshot {
+    pub fn new(snapshot: Arc<WorldSnapshot>, principal: Arc<Principal>) -> Self {
+        Self { snapshot, principal }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.snapshot.tick
+    }
+
+    pub fn captured_at(&self) -> f64 {
+        self.snapshot.captured_at
+    }
+
+    pub fn principal(&self) -> &Principal {
+        &self.principal
+    }
+
+    /// Entities without a BaseEntity carry no security data and are never visible
+    fn visible(&self, entity: &EntitySnapshot) -> bool {
+        entity.base.as_ref().is_some_and(|base| self.principal.can_view(base))
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = &EntitySnapshot> {
+        self.snapshot.entities().filter(|entity| self.visible(entity))
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&EntitySnapshot> {
+        self.snapshot.get(id).filter(|entity| self.visible(entity))
+    }
+
+    pub fn of_type<'a>(&'a self, entity_type: &'a str) -> impl Iterator<Item = &'a EntitySnapshot> + 'a {
+        self.entities().filter(move |entity| entity.entity_type == entity_type)
+    }
+
+    /// Number of entities visible to the principal
+    pub fn len(&self) -> usize {
+        self.entities().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities().next().is_none()
+    }
+}
@@ -0,0 +1,212 @@
+// Read-optimized world snapshot for GraphQL.
+// The memory system owns the live hecs::World; once per tick it builds an immutable WorldSnapshot
+// with full entity data and swaps it in atomically. Readers load the current Arc and keep a
+// consistent tick for as long as they hold it, without ever locking the simulation. Each tick only
+// the entities in its ChangeSet are recaptured; everything else is shared with the previous snapshot.
+// The same snapshot and change set are broadcast as a TickUpdate to GraphQL subscriptions.
+
+use arc_swap::ArcSwap;
+use hecs::{Entity, World};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::changes::ChangeSet;
+use crate::components::{
+    with_base_entity, Affinity, AttachedComponents, BaseComponent, BaseEntity, Binding, Bond, DecayComponent,
+    DisplayText, EntityType, MemoryLayer, Moment, TemporalPosition, ThreadId, ThreadName, ThreadType,
+};
+use crate::index::EntityIndex;
+
+/// One entity as of the snapshot's tick
+#[derive(Debug, Clone)]
+pub struct EntitySnapshot {
+    /// Handle in the live world, e.g. to report observations back to the memory system
+    pub entity: Entity,
+    pub id: Option<Uuid>,
+    pub entity_type: String,
+    pub display_text: String,
+    pub base: Option<BaseEntity>,
+    pub decay: Option<DecayComponent>,
+    pub moment: Option<Moment>,
+    pub bond: Option<Bond>,
+    pub binding: Option<Binding>,
+    pub thread_id: Option<String>,
+    pub thread_type: Option<String>,
+    pub thread_name: Option<String>,
+    pub temporal_position: Option<TemporalPosition>,
+    pub memory_layer: Option<MemoryLayer>,
+    pub affinity: Option<f64>,
+    pub components: Vec<BaseComponent>,
+}
+
+impl EntitySnapshot {
+    fn capture(world: &World, index: &EntityIndex, entity: Entity) -> Self {
+        Self {
+            entity,
+            id: index.uuid(entity),
+            entity_type: text(world, entity, |c: &EntityType| &c.0).unwrap_or_default(),
+            display_text: text(world, entity, |c: &DisplayText| &c.0).unwrap_or_default(),
+            base: with_base_entity(world, entity, BaseEntity::clone),
+            decay: world.get::<&DecayComponent>(entity).ok().map(|decay| (*decay).clone()),
+            moment: world.get::<&Moment>(entity).ok().map(|moment| (*moment).clone()),
+            bond: world.get::<&Bond>(entity).ok().map(|bond| (*bond).clone()),
+            binding: world.get::<&Binding>(entity).ok().map(|binding| (*binding).clone()),
+            thread_id: text(world, entity, |c: &ThreadId| &c.0),
+            thread_type: text(world, entity, |c: &ThreadType| &c.0),
+            thread_name: text(world, entity, |c: &ThreadName| &c.0),
+            temporal_position: world.get::<&TemporalPosition>(entity).ok().map(|position| (*position).clone()),
+            memory_layer: world.get::<&MemoryLayer>(entity).ok().map(|layer| (*layer).clone()),
+            affinity: world.get::<&Affinity>(entity).ok().map(|affinity| affinity.0),
+            components: world
+                .get::<&AttachedComponents>(entity)
+                .map(|attached| attached.0.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn strength(&self) -> Option<f32> {
+        self.decay.as_ref().map(|decay| decay.strength)
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.base.as_ref().is_some_and(|base| base.deleted_at.is_some())
+    }
+
+    /// External id: the entity's UUID, or the ECS handle for entities outside the index
+    pub fn external_id(&self) -> String {
+        self.id.map(|id| id.to_string()).unwrap_or_else(|| format!("{:?}", self.entity))
+    }
+}
+
+/// Clone the String inside a newtype component (EntityType, DisplayText, ThreadId, ...)
+fn text<T: hecs::Component>(world: &World, entity: Entity, inner: fn(&T) -> &String) -> Option<String> {
+    world.get::<&T>(entity).ok().map(|component| inner(&component).clone())
+}
+
+/// Immutable copy of the world as of one tick
+#[derive(Debug, Default)]
+pub struct WorldSnapshot {
+    pub tick: u64,
+    /// Unix time (seconds) the snapshot was captured
+    pub captured_at: f64,
+    entities: Vec<Arc<EntitySnapshot>>,
+    by_id: HashMap<Uuid, usize>,
+}
+
+impl WorldSnapshot {
+    /// Capture every entity carrying an `EntityType`
+    pub fn capture(world: &World, index: &EntityIndex, tick: u64, captured_at: f64) -> Self {
+        let entities = world
+            .query::<&EntityType>()
+            .iter()
+            .map(|(entity, _)| Arc::new(EntitySnapshot::capture(world, index, entity)))
+            .collect();
+        Self::from_entities(entities, tick, captured_at)
+    }
+
+    /// The next tick's snapshot: `previous` with the entities in `changes` recaptured or dropped
+    pub fn advance(previous: &WorldSnapshot, world: &World, index: &EntityIndex, changes: &ChangeSet, captured_at: f64) -> Self {
+        let touched = changes.touched();
+        let removed = changes.removed();
+
+        let mut entities: Vec<Arc<EntitySnapshot>> = previous
+            .entities
+            .iter()
+            .filter(|snapshot| !touched.contains(&snapshot.entity) && !removed.contains(&snapshot.entity))
+            .cloned()
+            .collect();
+        entities.extend(
+            touched
+                .into_iter()
+                .filter(|&entity| world.satisfies::<&EntityType>(entity).unwrap_or(false))
+                .map(|entity| Arc::new(EntitySnapshot::capture(world, index, entity))),
+        );
+        Self::from_entities(entities, changes.tick, captured_at)
+    }
+
+    /// Creation order, ties broken by UUID, so readers page through a stable sequence
+    fn from_entities(mut entities: Vec<Arc<EntitySnapshot>>, tick: u64, captured_at: f64) -> Self {
+        entities.sort_by_key(|snapshot| (snapshot.base.as_ref().and_then(|base| base.created_at), snapshot.id));
+        let by_id = entities
+            .iter()
+            .enumerate()
+            .filter_map(|(i, snapshot)| snapshot.id.map(|id| (id, i)))
+            .collect();
+
+        Self { tick, captured_at, entities, by_id }
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = &EntitySnapshot> {
+        self.entities.iter().map(|snapshot| snapshot.as_ref())
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&EntitySnapshot> {
+        self.by_id.get(id).map(|&i| self.entities[i].as_ref())
+    }
+
+    pub fn of_type<'a>(&'a self, entity_type: &'a str) -> impl Iterator<Item = &'a EntitySnapshot> + 'a {
+        self.entities().filter(move |snapshot| snapshot.entity_type == entity_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// One published tick: what changed, and the world before and after the change
+#[derive(Debug, Clone)]
+pub struct TickUpdate {
+    pub changes: Arc<ChangeSet>,
+    /// Still holds the entities despawned this tick
+    pub previous: Arc<WorldSnapshot>,
+    pub snapshot: Arc<WorldSnapshot>,
+}
+
+/// How many ticks a slow subscriber may fall behind before it starts missing updates
+const UPDATE_BUFFER: usize = 64;
+
+/// Shared slot holding the latest snapshot: the memory system publishes, GraphQL loads
+#[derive(Debug)]
+pub struct SnapshotStore {
+    current: ArcSwap<WorldSnapshot>,
+    updates: broadcast::Sender<TickUpdate>,
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnapshotStore {
+    pub fn new() -> Self {
+        Self {
+            current: ArcSwap::default(),
+            updates: broadcast::channel(UPDATE_BUFFER).0,
+        }
+    }
+
+    /// The latest published snapshot; stays valid (and unchanged) while the caller holds it
+    pub fn load(&self) -> Arc<WorldSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Swap in a new snapshot and notify subscribers; readers still holding the previous one are unaffected
+    pub fn publish(&self, snapshot: WorldSnapshot, changes: Arc<ChangeSet>) {
+        let snapshot = Arc::new(snapshot);
+        let previous = self.current.swap(snapshot.clone());
+        // No subscribers is fine - nobody is listening
+        let _ = self.updates.send(TickUpdate { changes, previous, snapshot });
+    }
+
+    /// Receive every tick published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<TickUpdate> {
+        self.updates.subscribe()
+    }
+}
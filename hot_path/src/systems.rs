@@ -1,108 +1,360 @@
-use hecs::World;
-use crate::components::{DecayComponent, EntityType};
-use std::time::{SystemTime, UNIX_EPOCH};
+use hecs::{Entity, World};
+use crate::changes::ChangeSet;
+use crate::components::{Affinity, Binding, Bond, DecayComponent, EntityType, LawTrigger, Thread, ThreadId, ThreadName};
+use crate::formula::FormulaFields;
+use crate::index::EntityIndex;
+use crate::binding;
+use crate::bonding;
+use crate::consolidation;
+use crate::laws::{self, CompiledLaw, LawError, LawKind};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
 
-/// Mathematical law specifications loaded from schema
+/// Mathematical law specifications loaded from the cold path's law export
 pub struct LawSpecifications {
-    pub decay_law: DecayLawSpec,
-    pub resonance_law: ResonanceLawSpec,
+    pub laws: Vec<CompiledLaw>,
 }
 
-#[derive(Debug, Clone)]
-pub struct DecayLawSpec {
-    pub formula: String,
-    pub applies_to: Vec<String>,
-    pub min_strength: f32,
-}
+impl LawSpecifications {
+    /// Load and validate law specifications from an exported JSON/YAML law file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LawError> {
+        Ok(Self::from_laws(laws::load_laws(path)?))
+    }
 
-#[derive(Debug, Clone)]
-pub struct ResonanceLawSpec {
-    pub formula: String,
-    pub threshold: f32,
-    pub multiplier: f32,
-    pub max_strength: f32,
+    pub fn from_laws(laws: Vec<CompiledLaw>) -> Self {
+        Self { laws }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CompiledLaw> {
+        self.laws.iter().find(|law| law.name() == name)
+    }
+
+    /// Laws that write component fields directly (decay, resonance, ...)
+    pub fn field_laws(&self) -> impl Iterator<Item = &CompiledLaw> {
+        self.laws.iter().filter(|law| matches!(law.kind, LawKind::Field { .. }))
+    }
+
+    /// Field laws fired by the given trigger
+    pub fn field_laws_for(&self, trigger: LawTrigger) -> impl Iterator<Item = &CompiledLaw> {
+        self.field_laws().filter(move |law| law.law.trigger == trigger)
+    }
+
+    /// Structural `action(predicate)` laws fired by the given trigger
+    pub fn predicate_laws_for(&self, trigger: LawTrigger) -> impl Iterator<Item = &CompiledLaw> {
+        self.laws
+            .iter()
+            .filter(move |law| matches!(law.kind, LawKind::Predicate { .. }) && law.law.trigger == trigger)
+    }
 }
 
-impl LawSpecifications {
-    /// Load law specifications from schema (replaces law entities)
-    pub fn from_schema() -> Self {
-        Self {
-            decay_law: DecayLawSpec {
-                formula: "strength = strength * pow(0.5, time_elapsed / half_life)".to_string(),
-                applies_to: vec!["filament".to_string(), "motif".to_string(), "moment".to_string()],
-                min_strength: 0.1,
-            },
-            resonance_law: ResonanceLawSpec {
-                formula: "strength = min(strength * multiplier, max_strength) if strength > threshold else strength".to_string(),
-                threshold: 0.85,
-                multiplier: 1.2,
-                max_strength: 1.0,
-            },
+/// Run a structural law through the system implementing its action
+pub fn predicate_law_system(
+    world: &mut World,
+    index: &mut EntityIndex,
+    law: &CompiledLaw,
+    current_time: f64,
+    changes: &mut ChangeSet,
+    report: &mut ScheduleReport,
+) {
+    let LawKind::Predicate { action } = &law.kind else {
+        return;
+    };
+    match action.as_str() {
+        "create_binding_if" => report.bindings_created += binding::binding_system(world, index, law, current_time, changes),
+        "consolidate_if" => {
+            let consolidation = consolidation::consolidation_system(world, index, law, current_time, changes);
+            report.filaments_created += consolidation.filaments;
+            report.motifs_created += consolidation.motifs;
+            report.moments_consolidated += consolidation.consolidated_moments;
         }
+        "form_bond_if" => {
+            let bonding = bonding::bonding_system(world, index, law, current_time, changes);
+            report.bonds_created += bonding.created;
+            report.bonds_collected += bonding.collected;
+        }
+        _ => {}
     }
 }
 
-/// Decay System - applies exponential decay law to entities
-pub fn decay_system(world: &mut World, law_spec: &DecayLawSpec) {
-    let current_time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs_f64();
+/// Apply a `field = expr` law to one entity's decay component.
+/// Returns the target field's value before and after, if the law applied. `last_update` only
+/// advances along with the target: an unchanged value (say, one held at its clamp) is never
+/// recorded, so replay must still see the old `last_update`.
+fn apply_field_law(law: &CompiledLaw, decay: &mut DecayComponent, current_time: f64) -> Option<(f64, f64)> {
+    let LawKind::Field { target } = &law.kind else {
+        return None;
+    };
+    let before = decay.get_field(target);
 
-    for (_entity, (entity_type, decay)) in world.query_mut::<(&EntityType, &mut DecayComponent)>() {
+    // Apply mathematical formula from the law export
+    let mut scope = law.scope();
+    scope.bind_fields(&*decay, &law.law.variables);
+    if let Some(last_update) = decay.get_field("last_update") {
+        scope.set("time_elapsed", current_time - last_update);
+    }
+    if law.formula.apply(&scope, &mut *decay).is_err() {
+        // A formula that can't evaluate for this entity leaves it untouched
+        return None;
+    }
+
+    // Apply constraints from the law export
+    law.apply_constraints(&mut *decay);
+
+    let after = decay.get_field(target);
+    if after != before && law.law.variables.iter().any(|v| v == "last_update") {
+        decay.last_update = current_time;
+    }
+    before.zip(after)
+}
+
+/// Record the law's target field change; `last_update` bookkeeping isn't recorded on its own
+fn record_field_change(
+    changes: &mut ChangeSet,
+    index: &EntityIndex,
+    entity: Entity,
+    law: &CompiledLaw,
+    (old, new): (f64, f64),
+) {
+    let LawKind::Field { target } = &law.kind else {
+        return;
+    };
+    // Targets are validated against the field vocabulary, which gives us a 'static name
+    if let Some(field) = laws::FIELD_VARIABLES.iter().find(|field| *field == target) {
+        changes.mutated(index, entity, "DecayComponent", field, old, new);
+    }
+}
+
+/// Field Law System - applies a `field = expr` law to every entity type it governs
+pub fn field_law_system(world: &mut World, index: &EntityIndex, law: &CompiledLaw, current_time: f64, changes: &mut ChangeSet) {
+    for (entity, (entity_type, decay)) in world.query_mut::<(&EntityType, &mut DecayComponent)>() {
         // Only apply to entity types specified in law
-        if law_spec.applies_to.contains(&entity_type.0) {
-            let time_elapsed = current_time - decay.last_update;
-            
-            // Apply mathematical formula from schema
-            decay.strength = decay.strength * (0.5_f32).powf(time_elapsed as f32 / decay.half_life);
-            
-            // Apply constraints from schema  
-            if decay.strength < law_spec.min_strength {
-                decay.strength = law_spec.min_strength;
+        if law.applies_to(&entity_type.0) {
+            if let Some(change) = apply_field_law(law, decay, current_time) {
+                record_field_change(changes, index, entity, law, change);
+            }
+        }
+    }
+}
+
+/// Apply a field law to specific entities only (observed or affinity-matched ones).
+/// Returns how many of them the law was applied to; a formula that fails for an entity doesn't count.
+pub fn field_law_on_entities(
+    world: &mut World,
+    index: &EntityIndex,
+    law: &CompiledLaw,
+    entities: &[Entity],
+    current_time: f64,
+    changes: &mut ChangeSet,
+) -> usize {
+    let mut applied = 0;
+    for &entity in entities {
+        if let Ok((entity_type, decay)) = world.query_one_mut::<(&EntityType, &mut DecayComponent)>(entity) {
+            if law.applies_to(&entity_type.0) {
+                if let Some(change) = apply_field_law(law, decay, current_time) {
+                    record_field_change(changes, index, entity, law, change);
+                    applied += 1;
+                }
+            }
+        }
+    }
+    applied
+}
+
+/// Runs each law according to its trigger instead of every loop iteration:
+/// - `OnTick` laws on a fixed tick interval (or every `interval_seconds`, if the law declares it)
+/// - `OnObservation` laws lazily, on entities read through GraphQL since the last run
+/// - `OnAffinityMatch` laws when a bond/binding affinity crosses the threshold upwards
+pub struct LawScheduler {
+    tick_interval: Duration,
+    last_tick: Option<Instant>,
+    last_law_run: HashMap<String, Instant>,
+    affinity_threshold: f64,
+    pending_observations: HashSet<Entity>,
+    above_threshold: HashMap<(String, Entity), bool>,
+}
+
+/// What the scheduler did in one run
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleReport {
+    pub ticked: bool,
+    pub observed: usize,
+    pub affinity_matches: usize,
+    pub bindings_created: usize,
+    pub filaments_created: usize,
+    pub motifs_created: usize,
+    pub moments_consolidated: usize,
+    pub bonds_created: usize,
+    pub bonds_collected: usize,
+}
+
+impl LawScheduler {
+    /// `affinity_threshold` is used by affinity-triggered laws without an `affinity_threshold` constant
+    pub fn new(tick_interval: Duration, affinity_threshold: f64) -> Self {
+        Self {
+            tick_interval,
+            last_tick: None,
+            last_law_run: HashMap::new(),
+            affinity_threshold,
+            pending_observations: HashSet::new(),
+            above_threshold: HashMap::new(),
+        }
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        self.tick_interval
+    }
+
+    /// Queue entities read through GraphQL for the next run
+    pub fn observe(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.pending_observations.extend(entities);
+    }
+
+    pub fn run(
+        &mut self,
+        world: &mut World,
+        index: &mut EntityIndex,
+        law_specs: &LawSpecifications,
+        changes: &mut ChangeSet,
+    ) -> ScheduleReport {
+        let now = Instant::now();
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+        let mut report = ScheduleReport::default();
+
+        if self.last_tick.is_none_or(|last| now.duration_since(last) >= self.tick_interval) {
+            for law in law_specs.field_laws_for(LawTrigger::OnTick) {
+                if self.law_due(law, now) {
+                    field_law_system(world, index, law, current_time, changes);
+                }
+            }
+            for law in law_specs.predicate_laws_for(LawTrigger::OnTick) {
+                if self.law_due(law, now) {
+                    predicate_law_system(world, index, law, current_time, changes, &mut report);
+                }
+            }
+            self.last_tick = Some(now);
+            report.ticked = true;
+        }
+
+        if !self.pending_observations.is_empty() {
+            let observed: Vec<Entity> = self.pending_observations.drain().collect();
+            for law in law_specs.field_laws_for(LawTrigger::OnObservation) {
+                field_law_on_entities(world, index, law, &observed, current_time, changes);
             }
-            
-            decay.last_update = current_time;
+            report.observed = observed.len();
         }
+
+        for law in law_specs.field_laws_for(LawTrigger::OnAffinityMatch) {
+            let threshold = law.constant("affinity_threshold").unwrap_or(self.affinity_threshold);
+            for targets in self.affinity_crossings(world, law.name(), threshold) {
+                field_law_on_entities(world, index, law, &targets, current_time, changes);
+                report.affinity_matches += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Laws with an `interval_seconds` constant run on their own, slower cadence
+    fn law_due(&mut self, law: &CompiledLaw, now: Instant) -> bool {
+        let Some(interval) = law.constant("interval_seconds") else {
+            return true;
+        };
+        let due = self
+            .last_law_run
+            .get(law.name())
+            .is_none_or(|last| now.duration_since(*last).as_secs_f64() >= interval);
+        if due {
+            self.last_law_run.insert(law.name().to_string(), now);
+        }
+        due
+    }
+
+    /// Bonds/bindings whose affinity rose to `threshold` since the last run,
+    /// each resolved to the entities belonging to the threads it connects
+    fn affinity_crossings(&mut self, world: &World, law: &str, threshold: f64) -> Vec<Vec<Entity>> {
+        let mut current: Vec<(Entity, f64, Vec<Uuid>)> = Vec::new();
+        for (entity, bond) in world.query::<&Bond>().iter() {
+            current.push((entity, bond.affinity_score, bond.thread_ids.clone()));
+        }
+        for (entity, (binding, affinity)) in world.query::<(&Binding, &Affinity)>().iter() {
+            current.push((entity, affinity.0, binding.get_thread_ids()));
+        }
+
+        let mut crossings = Vec::new();
+        let mut seen = HashMap::new();
+        for (entity, affinity, thread_ids) in current {
+            let above = affinity >= threshold;
+            let key = (law.to_string(), entity);
+            let was_above = self.above_threshold.get(&key).copied().unwrap_or(false);
+            if above && !was_above {
+                crossings.push(thread_members(world, &thread_ids));
+            }
+            seen.insert(key, above);
+        }
+        // Tracked per law so each affinity law sees its own crossings; despawned relations drop out
+        self.above_threshold.retain(|(name, _), _| name != law);
+        self.above_threshold.extend(seen);
+        crossings
     }
 }
 
-/// Resonance System - applies resonance amplification law
-pub fn resonance_system(world: &mut World, law_spec: &ResonanceLawSpec) {
-    for (_entity, (entity_type, decay)) in world.query_mut::<(&EntityType, &mut DecayComponent)>() {
-        // Apply resonance to filaments when strength is high
-        if entity_type.0 == "filament" && decay.strength > law_spec.threshold {
-            // Apply mathematical formula from schema
-            let new_strength = decay.strength * law_spec.multiplier;
-            decay.strength = new_strength.min(law_spec.max_strength);
+/// Entities attached to the given threads, by thread UUID or thread name
+fn thread_members(world: &World, thread_ids: &[Uuid]) -> Vec<Entity> {
+    let names: HashSet<String> = world
+        .query::<(&Thread, &ThreadId)>()
+        .iter()
+        .filter(|(_, (thread, _))| thread.base.id.is_some_and(|id| thread_ids.contains(&id)))
+        .map(|(_, (_, thread_id))| thread_id.0.clone())
+        .collect();
+
+    let mut members = Vec::new();
+    for (entity, (thread_name, thread_id)) in world.query::<(Option<&ThreadName>, Option<&ThreadId>)>().iter() {
+        let belongs = thread_name.is_some_and(|name| names.contains(&name.0))
+            || thread_id.is_some_and(|id| names.contains(&id.0) || thread_ids.iter().any(|uuid| uuid.to_string() == id.0));
+        if belongs {
+            members.push(entity);
         }
     }
+    members
 }
 
-/// System runner that applies all physical laws
-pub fn run_physics_systems(world: &mut World, law_specs: &LawSpecifications) {
+/// System runner that applies all physical laws once, regardless of trigger
+pub fn run_physics_systems(world: &mut World, index: &EntityIndex, law_specs: &LawSpecifications, changes: &mut ChangeSet) {
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64();
+
     // Apply each law as a proper ECS system
-    decay_system(world, &law_specs.decay_law);
-    resonance_system(world, &law_specs.resonance_law);
+    for law in law_specs.field_laws() {
+        field_law_system(world, index, law, current_time, changes);
+    }
 }
 
 /// Get system statistics for monitoring
 pub fn get_system_stats(world: &World, law_specs: &LawSpecifications) -> SystemStats {
     let mut affected_entities = 0;
-    
+
     for (_entity, (entity_type, _decay)) in world.query::<(&EntityType, &DecayComponent)>().iter() {
-        if law_specs.decay_law.applies_to.contains(&entity_type.0) {
+        if law_specs.field_laws().any(|law| law.applies_to(&entity_type.0)) {
             affected_entities += 1;
         }
     }
-    
+
     SystemStats {
-        active_systems: 2, // decay + resonance
+        active_systems: law_specs.field_laws().count(),
         affected_entities,
-        law_specifications: format!("decay: {}, resonance: {}", 
-            law_specs.decay_law.applies_to.len(),
-            if law_specs.resonance_law.threshold > 0.0 { 1 } else { 0 }
-        ),
+        law_specifications: law_specs
+            .laws
+            .iter()
+            .map(|law| format!("{}: {}", law.name(), law.law.applies_to.len()))
+            .collect::<Vec<_>>()
+            .join(", "),
     }
 }
 
@@ -111,4 +363,4 @@ pub struct SystemStats {
     pub active_systems: usize,
     pub affected_entities: usize,
     pub law_specifications: String,
-} 
\ No newline at end of file
+}
@@ -0,0 +1,111 @@
+// Binding law under the shipped laws.json: moments created through the API carry no tag but
+// `moment`, so one author's moments posted close together on two threads must still bind - and
+// since a binding is private to its owner and names both moments, only one owner's moments do.
+
+use chrono::{DateTime, Duration, Utc};
+use familiar_hot_path::binding::binding_system;
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::components::{
+    AccessScope, BaseEntity, Binding, DecayComponent, DisplayText, EntityType, Moment, TemporalPosition, ThreadId,
+    Visibility,
+};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::laws::{load_laws, CompiledLaw};
+use hecs::World;
+use uuid::Uuid;
+
+const ORG: Uuid = Uuid::from_u128(0x100);
+const ALICE: Uuid = Uuid::from_u128(1);
+const BOB: Uuid = Uuid::from_u128(2);
+const WORK: (Uuid, &str) = (Uuid::from_u128(0x10), "work");
+const FAMILY: (Uuid, &str) = (Uuid::from_u128(0x11), "family");
+
+fn law() -> CompiledLaw {
+    load_laws(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/laws.json"))
+        .unwrap()
+        .into_iter()
+        .find(|law| law.name() == "binding")
+        .unwrap()
+}
+
+/// A moment as `createMoment` makes it: tagged only `moment`, authored and owned by its creator
+fn moment(world: &mut World, index: &mut EntityIndex, user: Uuid, (thread_id, thread): (Uuid, &str), created_at: DateTime<Utc>) -> Uuid {
+    let id = Uuid::new_v4();
+    index.spawn(world, (
+        Moment {
+            base: BaseEntity {
+                id: Some(id),
+                org_id: ORG,
+                owner_id: user,
+                created_at: Some(created_at),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["moment".to_string()],
+                component_ids: vec![],
+                sub_type: Some("moment".to_string()),
+                visibility: Visibility::Private,
+                security_level: 0,
+                access_scope: vec![AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+            thread_id,
+            author_id: user,
+            binding_hint: None,
+            binding_id: None,
+            cardinality: None,
+        },
+        DisplayText(format!("{} on {}", user, thread)),
+        ThreadId(thread.to_string()),
+        EntityType("moment".to_string()),
+        TemporalPosition::at(created_at),
+        DecayComponent { strength: 1.0, half_life: 300.0, last_update: 0.0 },
+    ));
+    id
+}
+
+fn bindings(world: &World) -> Vec<Binding> {
+    world.query::<&Binding>().iter().map(|(_, binding)| binding.clone()).collect()
+}
+
+#[test]
+fn one_authors_api_moments_bind_across_threads() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let now = Utc::now();
+    let morning = moment(&mut world, &mut index, ALICE, WORK, now);
+    let lunch = moment(&mut world, &mut index, ALICE, FAMILY, now + Duration::minutes(1));
+
+    let created = binding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!(created, 1);
+    let binding = &bindings(&world)[0];
+    let mut moments = binding.get_moment_ids();
+    moments.sort();
+    let mut expected = vec![morning, lunch];
+    expected.sort();
+    assert_eq!(moments, expected);
+    assert_eq!(binding.base.owner_id, ALICE);
+}
+
+#[test]
+fn moments_drift_apart_past_the_affinity_threshold() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let now = Utc::now();
+    moment(&mut world, &mut index, ALICE, WORK, now);
+    // Within the proximity threshold, but too far apart for same-author closeness to clear 0.7
+    moment(&mut world, &mut index, ALICE, FAMILY, now + Duration::seconds(290));
+
+    assert_eq!(binding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1)), 0);
+}
+
+#[test]
+fn different_owners_moments_never_bind() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let now = Utc::now();
+    moment(&mut world, &mut index, ALICE, WORK, now);
+    let bobs = moment(&mut world, &mut index, BOB, FAMILY, now);
+    // Written by Alice, so on affinity alone the pair would bind
+    world.get::<&mut Moment>(index.entity(&bobs).unwrap()).unwrap().author_id = ALICE;
+
+    assert_eq!(binding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1)), 0);
+    assert!(bindings(&world).is_empty());
+}
@@ -0,0 +1,149 @@
+// Bonding law upkeep: only the bonds the law formed itself are refreshed, fade away once their
+// bindings are gone, or give way to the per-thread cap - user-created bonds keep their values
+// (and keep the law off their pair), and neither they nor soft-deleted tombstones are despawned.
+
+use chrono::Utc;
+use familiar_hot_path::bonding::{bonding_system, BONDING_LAW_TAG};
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::components::{
+    AccessScope, BaseEntity, Binding, BindingPoint, Bond, Cardinality, DecayComponent, EntityType, Visibility,
+};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::laws::{load_laws, CompiledLaw};
+use hecs::World;
+use uuid::Uuid;
+
+fn law() -> CompiledLaw {
+    load_laws(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/laws.json"))
+        .unwrap()
+        .into_iter()
+        .find(|law| law.name() == "bonding")
+        .unwrap()
+}
+
+fn base_of(id: Uuid) -> BaseEntity {
+    BaseEntity {
+        id: Some(id),
+        org_id: Uuid::from_u128(0x100),
+        owner_id: Uuid::from_u128(1),
+        created_at: Some(Utc::now()),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![],
+        component_ids: vec![],
+        sub_type: Some("bond".to_string()),
+        visibility: Visibility::Private,
+        security_level: 0,
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    }
+}
+
+fn bond(world: &mut World, index: &mut EntityIndex, threads: (Uuid, Uuid), strength: f32, by_law: bool, deleted: bool) -> Uuid {
+    let id = Uuid::new_v4();
+    let mut tags = vec!["bond".to_string()];
+    if by_law {
+        tags.push(BONDING_LAW_TAG.to_string());
+    }
+    index.spawn(world, (
+        Bond {
+            base: BaseEntity { deleted_at: deleted.then(Utc::now), tags, ..base_of(id) },
+            thread_ids: vec![threads.0, threads.1],
+            affinity_score: strength as f64,
+            bond_strength: strength as f64,
+            component_context: vec![],
+        },
+        EntityType("bond".to_string()),
+        DecayComponent { strength, half_life: 120.0, last_update: 0.0 },
+    ));
+    id
+}
+
+/// A live binding between one moment on each thread of `threads`
+fn binding(world: &mut World, index: &mut EntityIndex, threads: (Uuid, Uuid)) {
+    let points = vec![
+        BindingPoint::new(threads.0, Uuid::new_v4(), Cardinality::Actor),
+        BindingPoint::new(threads.1, Uuid::new_v4(), Cardinality::Actor),
+    ];
+    let mut base = BaseEntity { tags: vec!["binding".to_string()], ..base_of(Uuid::new_v4()) };
+    base.sub_type = Some("binding".to_string());
+    index.spawn(world, (Binding { base, points, thread_ids: vec![threads.0, threads.1] }, EntityType("binding".to_string())));
+}
+
+fn bond_record(world: &World, index: &EntityIndex, id: &Uuid) -> Bond {
+    (*world.get::<&Bond>(index.entity(id).unwrap()).unwrap()).clone()
+}
+
+fn live(index: &EntityIndex, ids: &[Uuid]) -> usize {
+    ids.iter().filter(|id| index.entity(id).is_some()).count()
+}
+
+#[test]
+fn only_the_laws_faded_bonds_are_collected() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let pair = (Uuid::new_v4(), Uuid::new_v4());
+    let faded_law_bond = bond(&mut world, &mut index, pair, 0.01, true, false);
+    let user_bond = bond(&mut world, &mut index, (Uuid::new_v4(), Uuid::new_v4()), 0.01, false, false);
+    let tombstone = bond(&mut world, &mut index, (Uuid::new_v4(), Uuid::new_v4()), 0.01, true, true);
+
+    let report = bonding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!(report.collected, 1);
+    assert_eq!(live(&index, &[faded_law_bond]), 0);
+    assert_eq!(live(&index, &[user_bond, tombstone]), 2);
+}
+
+#[test]
+fn user_bonds_neither_count_against_nor_give_way_to_the_cap() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let hub = Uuid::new_v4();
+    let user_bonds: Vec<Uuid> = (0..6).map(|_| bond(&mut world, &mut index, (hub, Uuid::new_v4()), 0.9, false, false)).collect();
+    // Law bond i is backed by i + 1 bindings, so each is stronger than the one before
+    let law_bonds: Vec<Uuid> = (0..6)
+        .map(|i| {
+            let pair = (hub, Uuid::new_v4());
+            (0..=i).for_each(|_| binding(&mut world, &mut index, pair));
+            bond(&mut world, &mut index, pair, 0.5, true, false)
+        })
+        .collect();
+
+    let report = bonding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    // The cap is 5: the weakest law bond goes, every user bond stays
+    assert_eq!(report.collected, 1);
+    assert_eq!(live(&index, &user_bonds), 6);
+    assert_eq!(live(&index, &law_bonds[1..]), 5);
+    assert_eq!(live(&index, &law_bonds[..1]), 0);
+}
+
+#[test]
+fn user_bonds_keep_their_values_and_their_pair() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let pair = (Uuid::new_v4(), Uuid::new_v4());
+    (0..3).for_each(|_| binding(&mut world, &mut index, pair));
+    let user_bond = bond(&mut world, &mut index, pair, 0.9, false, false);
+
+    let report = bonding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!((report.created, report.updated, report.collected), (0, 0, 0));
+    let bond = bond_record(&world, &index, &user_bond);
+    let set = 0.9f32 as f64;
+    assert_eq!((bond.affinity_score, bond.bond_strength, bond.base.version), (set, set, 1));
+    assert_eq!(world.query::<&Bond>().iter().count(), 1);
+}
+
+#[test]
+fn law_bonds_fade_once_their_bindings_are_gone() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let backed = (Uuid::new_v4(), Uuid::new_v4());
+    (0..3).for_each(|_| binding(&mut world, &mut index, backed));
+    let held = bond(&mut world, &mut index, backed, 0.9, true, false);
+    // Strong, but nothing binds its threads any more - with bond strengths left out of the
+    // shipped decay law, only the bonding law itself can let it go
+    let orphaned = bond(&mut world, &mut index, (Uuid::new_v4(), Uuid::new_v4()), 0.9, true, false);
+
+    let report = bonding_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!(report.collected, 1);
+    assert_eq!(live(&index, &[orphaned]), 0);
+    // Three bindings at full strength: affinity 1 - e^(-3/5)
+    let bond = bond_record(&world, &index, &held);
+    assert!((bond.bond_strength - (1.0 - (-0.6f64).exp())).abs() < 1e-9, "{}", bond.bond_strength);
+}
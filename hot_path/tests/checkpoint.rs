@@ -0,0 +1,214 @@
+// World checkpoints and log compaction: a checkpoint plus the log tail restores the same world
+// as a full replay, compaction deletes only segments a kept checkpoint covers while the
+// checkpoint keeps every entity's version history, and the compacted log still verifies. A world
+// that lags the log - restored with a replay limit - is never checkpointed.
+
+use chrono::Utc;
+use familiar_hot_path::checkpoint::{self, Checkpoint};
+use familiar_hot_path::components::{
+    with_base_entity_mut, AccessScope, BaseEntity, ConsolidationStatus, DecayComponent, DisplayText, EntityType,
+    MemoryLayer, MemoryLayerType, Thread, ThreadId, Visibility,
+};
+use familiar_hot_path::event_log::{self, read_dir, EventLog, SyncPolicy};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::migration::Migrations;
+use familiar_hot_path::persistence::{EventType, MemoryPersistence, PersistenceConfig, PersistenceError};
+use familiar_hot_path::replay::{self, EntityRecord, ReplayLimit, Replayed};
+use hecs::{Entity, World};
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn thread(world: &mut World, index: &mut EntityIndex, id: u128, name: &str) -> Entity {
+    let base = BaseEntity {
+        id: Some(Uuid::from_u128(id)),
+        org_id: Uuid::from_u128(0x100),
+        owner_id: Uuid::from_u128(1),
+        created_at: Some(Utc::now()),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec!["thread".to_string()],
+        component_ids: vec![],
+        sub_type: Some("thread".to_string()),
+        visibility: Visibility::Org,
+        security_level: 0,
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    };
+    index.spawn(world, (
+        Thread { base },
+        DisplayText(name.to_string()),
+        ThreadId(name.to_string()),
+        EntityType("thread".to_string()),
+        DecayComponent { strength: 1.0, half_life: 600.0, last_update: 1_700_000_000.0 },
+        MemoryLayer {
+            layer_type: MemoryLayerType::WorkingMemory,
+            access_frequency: 0.5,
+            last_accessed: Utc::now(),
+            consolidation_status: ConsolidationStatus::Fresh,
+            redis_key: None,
+        },
+    ))
+}
+
+/// Rename a thread in the world and log the new version
+fn rename(world: &mut World, log: &mut EventLog, entity: Entity, name: &str) {
+    world.insert_one(entity, DisplayText(name.to_string())).unwrap();
+    with_base_entity_mut(world, entity, |base| base.record_update());
+    log.append(replay::entry(world, entity, EventType::ComponentUpdated).unwrap()).unwrap();
+}
+
+fn records(replayed: &Replayed) -> Vec<serde_json::Value> {
+    let mut records: Vec<_> = replayed
+        .world
+        .iter()
+        .map(|entity_ref| serde_json::to_value(EntityRecord::capture(&replayed.world, entity_ref.entity()).unwrap()).unwrap())
+        .collect();
+    records.sort_by_key(|record| record.to_string());
+    records
+}
+
+/// Checkpoint first, then the log after it - what startup does
+fn restore(dir: &Path, limit: ReplayLimit) -> Replayed {
+    let migrations = Migrations::default();
+    let mut replayed = match checkpoint::latest(dir, limit).unwrap() {
+        Some(checkpoint) => checkpoint.restore(&migrations),
+        None => Replayed::new(),
+    };
+    replayed.replay_dir(dir, limit, &migrations).unwrap();
+    replayed
+}
+
+fn open(dir: &Path) -> EventLog {
+    EventLog::open(dir, 1000, SyncPolicy::Never, Duration::ZERO).unwrap()
+}
+
+#[test]
+fn checkpoint_and_tail_restore_the_same_world_as_a_full_replay() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let mut log = open(dir.path());
+    let lane = thread(&mut world, &mut index, 10, "Memory Lane");
+    log.append(replay::entry(&world, lane, EventType::EntityCreated).unwrap()).unwrap();
+    rename(&mut world, &mut log, lane, "Memory Avenue");
+
+    let entries: Vec<_> = read_dir(dir.path()).unwrap().into_iter().map(|record| record.entry).collect();
+    let checkpoint = Checkpoint::capture(&world, None, &entries);
+    assert_eq!(checkpoint.sequence, 2);
+    checkpoint::write(dir.path(), &checkpoint).unwrap();
+
+    // The tail: more versions and a new entity after the checkpoint
+    rename(&mut world, &mut log, lane, "Memory Boulevard");
+    let garden = thread(&mut world, &mut index, 11, "Garden");
+    log.append(replay::entry(&world, garden, EventType::EntityCreated).unwrap()).unwrap();
+
+    let restored = restore(dir.path(), ReplayLimit::none());
+    assert_eq!(restored.report.last_sequence, 4);
+    assert_eq!(records(&restored), records(&replay::replay_dir(dir.path(), ReplayLimit::none(), &Migrations::default()).unwrap()));
+    assert!(restored.world.get::<&MemoryLayer>(restored.index.entity(&Uuid::from_u128(10)).unwrap()).is_ok());
+
+    // A limit before the tail still gets the checkpoint, and nothing after its point
+    let restored = restore(dir.path(), ReplayLimit { until_sequence: Some(3), ..ReplayLimit::none() });
+    assert_eq!(restored.report.last_sequence, 3);
+    assert_eq!(restored.entity_count(), 1);
+}
+
+#[test]
+fn compaction_keeps_version_history_and_a_verifiable_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PersistenceConfig { segment_bytes: 1500, fsync: SyncPolicy::Never, keep_checkpoints: 1, ..Default::default() };
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    let lane = thread(&mut world, &mut index, 10, "Memory Lane");
+    persistence.append_primary_memory(replay::entry(&world, lane, EventType::EntityCreated).unwrap()).unwrap();
+    for version in 2..=12 {
+        world.insert_one(lane, DisplayText(format!("Memory Lane, take {}", version))).unwrap();
+        with_base_entity_mut(&mut world, lane, |base| base.record_update());
+        persistence.append_primary_memory(replay::entry(&world, lane, EventType::ComponentUpdated).unwrap()).unwrap();
+    }
+    let segments_before = read_dir(dir.path()).unwrap().iter().map(|r| r.position.segment).max().unwrap();
+    assert!(segments_before > 2, "expected several segments");
+
+    let report = persistence.checkpoint(&world, 12).unwrap().unwrap();
+    assert_eq!(report.sequence, 12);
+    assert!(report.compacted > 0 && report.compacted < 12, "compacted {}", report.compacted);
+    // Nothing new since: no second checkpoint
+    assert!(persistence.checkpoint(&world, 12).unwrap().is_none());
+
+    // Covered payloads are gone from the log, but every version is still on record
+    let remaining = read_dir(dir.path()).unwrap();
+    assert_eq!(remaining.first().unwrap().entry.sequence as usize, report.compacted + 1);
+    let checkpoint = checkpoint::latest(dir.path(), ReplayLimit::none()).unwrap().unwrap();
+    let versions: Vec<u64> = checkpoint.history(&Uuid::from_u128(10)).iter().map(|stamp| stamp.version).collect();
+    assert_eq!(versions, (1..=12).collect::<Vec<_>>());
+
+    let verification = event_log::verify(dir.path());
+    assert!(verification.is_ok(), "{:?}", verification.corruption);
+    assert_eq!(verification.last_sequence, 12);
+
+    // Appends carry on, and a restart restores the latest state
+    world.insert_one(lane, DisplayText("Memory Lane, at last".to_string())).unwrap();
+    with_base_entity_mut(&mut world, lane, |base| base.record_update());
+    persistence.append_primary_memory(replay::entry(&world, lane, EventType::ComponentUpdated).unwrap()).unwrap();
+    drop(persistence);
+    let restored = restore(dir.path(), ReplayLimit::none());
+    let entity = restored.index.entity(&Uuid::from_u128(10)).unwrap();
+    assert_eq!(restored.world.get::<&DisplayText>(entity).unwrap().0, "Memory Lane, at last");
+    assert!(event_log::verify(dir.path()).is_ok());
+}
+
+#[test]
+fn older_checkpoints_are_pruned_and_limits_pick_an_earlier_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let mut log = open(dir.path());
+    let lane = thread(&mut world, &mut index, 10, "Memory Lane");
+    log.append(replay::entry(&world, lane, EventType::EntityCreated).unwrap()).unwrap();
+
+    let mut previous: Option<Checkpoint> = None;
+    for name in ["One", "Two", "Three"] {
+        rename(&mut world, &mut log, lane, name);
+        let entries: Vec<_> = log.read_all().unwrap().into_iter().map(|record| record.entry).collect();
+        let checkpoint = Checkpoint::capture(&world, previous.as_ref(), &entries);
+        checkpoint::write(dir.path(), &checkpoint).unwrap();
+        previous = Some(checkpoint);
+    }
+    assert_eq!(checkpoint::list(dir.path()).unwrap(), vec![2, 3, 4]);
+
+    let earlier = checkpoint::latest(dir.path(), ReplayLimit { until_sequence: Some(3), ..ReplayLimit::none() }).unwrap().unwrap();
+    assert_eq!(earlier.sequence, 3);
+    assert_eq!(earlier.history(&Uuid::from_u128(10)).len(), 3);
+
+    assert_eq!(checkpoint::prune(dir.path(), 2).unwrap(), Some(3));
+    assert_eq!(checkpoint::list(dir.path()).unwrap(), vec![3, 4]);
+}
+
+#[test]
+fn a_world_behind_the_log_is_not_checkpointed() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PersistenceConfig { segment_bytes: 1500, fsync: SyncPolicy::Never, keep_checkpoints: 1, ..Default::default() };
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    let lane = thread(&mut world, &mut index, 10, "Memory Lane");
+    persistence.append_primary_memory(replay::entry(&world, lane, EventType::EntityCreated).unwrap()).unwrap();
+    for version in 2..=12 {
+        world.insert_one(lane, DisplayText(format!("Memory Lane, take {}", version))).unwrap();
+        with_base_entity_mut(&mut world, lane, |base| base.record_update());
+        persistence.append_primary_memory(replay::entry(&world, lane, EventType::ComponentUpdated).unwrap()).unwrap();
+    }
+    drop(persistence);
+
+    // Restart as of entry 3: checkpointing that world as the log's version would compact away 4..12
+    let limited = restore(dir.path(), ReplayLimit { until_sequence: Some(3), ..ReplayLimit::none() });
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    let refused = persistence.checkpoint(&limited.world, limited.report.last_sequence);
+    assert!(matches!(refused, Err(PersistenceError::WorldBehindLog { world: 3, log: 12 })), "{:?}", refused.map(|_| ()));
+    assert!(checkpoint::list(dir.path()).unwrap().is_empty());
+    assert_eq!(read_dir(dir.path()).unwrap().len(), 12);
+
+    // The full world checkpoints as usual
+    let full = restore(dir.path(), ReplayLimit::none());
+    let report = persistence.checkpoint(&full.world, full.report.last_sequence).unwrap().unwrap();
+    assert_eq!(report.sequence, 12);
+}
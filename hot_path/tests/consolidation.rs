@@ -0,0 +1,145 @@
+// Consolidation law: decaying moments collapse into a filament per thread and a motif per shared
+// tag or emotion across threads - but only ever one owner's moments, since the consolidated
+// entity is private to that owner and quotes its sources. For the same reason moments deleted in
+// the meantime drop out, and the summary is as sensitive as its most sensitive source.
+
+use chrono::Utc;
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::components::{
+    AccessScope, AttachedComponents, BaseComponent, BaseEntity, DecayComponent, DisplayText,
+    EntityType, Filament, Moment, Motif, ThreadId, Visibility,
+};
+use familiar_hot_path::consolidation::consolidation_system;
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::laws::{load_laws, CompiledLaw};
+use hecs::World;
+use uuid::Uuid;
+
+const ALICE: Uuid = Uuid::from_u128(1);
+const BOB: Uuid = Uuid::from_u128(2);
+
+fn law() -> CompiledLaw {
+    load_laws(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/laws.json"))
+        .unwrap()
+        .into_iter()
+        .find(|law| law.name() == "consolidation")
+        .unwrap()
+}
+
+/// A moment weak enough for the law to consolidate
+fn moment(world: &mut World, index: &mut EntityIndex, owner: Uuid, thread: &str, text: &str, emotion: Option<&str>) -> Uuid {
+    let id = Uuid::new_v4();
+    let entity = index.spawn(world, (
+        Moment {
+            base: BaseEntity {
+                id: Some(id),
+                org_id: Uuid::from_u128(0x100),
+                owner_id: owner,
+                created_at: Some(Utc::now()),
+                updated_at: None,
+                deleted_at: None,
+                tags: vec!["moment".to_string()],
+                component_ids: vec![],
+                sub_type: Some("moment".to_string()),
+                visibility: Visibility::Private,
+                security_level: 0,
+                access_scope: vec![AccessScope::View],
+                version: 1,
+                parent_version: None,
+            },
+            thread_id: Uuid::new_v4(),
+            author_id: owner,
+            binding_hint: None,
+            binding_id: None,
+            cardinality: None,
+        },
+        DisplayText(text.to_string()),
+        ThreadId(thread.to_string()),
+        EntityType("moment".to_string()),
+        DecayComponent { strength: 0.1, half_life: 300.0, last_update: 0.0 },
+    ));
+    if let Some(emotion) = emotion {
+        world.insert_one(entity, AttachedComponents(vec![BaseComponent::emotion(emotion, 0.9)])).unwrap();
+    }
+    id
+}
+
+/// Owner, source moments (`component_ids`) and summary of every consolidated `T`
+fn consolidated<T: hecs::Component>(world: &World, base: impl Fn(&T) -> &BaseEntity) -> Vec<(Uuid, Vec<Uuid>, String)> {
+    world
+        .query::<(&T, &DisplayText)>()
+        .iter()
+        .map(|(_, (entity, text))| (base(entity).owner_id, base(entity).component_ids.clone(), text.0.clone()))
+        .collect()
+}
+
+#[test]
+fn filaments_only_gather_one_owners_moments() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let alices: Vec<Uuid> = (0..3).map(|i| moment(&mut world, &mut index, ALICE, "Lane", &format!("alice {}", i), None)).collect();
+    moment(&mut world, &mut index, BOB, "Lane", "bob's secret", None);
+    moment(&mut world, &mut index, BOB, "Lane", "bob's other secret", None);
+
+    let report = consolidation_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!(report.filaments, 1);
+
+    let filaments = consolidated::<Filament>(&world, |filament| &filament.base);
+    assert_eq!(filaments.len(), 1);
+    let (owner, sources, text) = &filaments[0];
+    assert_eq!(*owner, ALICE);
+    assert_eq!(sources, &alices);
+    assert!(!text.contains("bob"), "{}", text);
+
+    // Each status change (consolidating, then consolidated) is a new version of the moment
+    for id in &alices {
+        let moment = world.get::<&Moment>(index.entity(id).unwrap()).unwrap();
+        assert_eq!((moment.base.version, moment.base.parent_version), (3, Some(2)));
+    }
+}
+
+#[test]
+fn motifs_form_around_a_shared_emotion_per_owner() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let alices: Vec<Uuid> = ["Lane", "Garden", "Harbor"]
+        .iter()
+        .map(|thread| moment(&mut world, &mut index, ALICE, thread, "sunny day", Some("joy")))
+        .collect();
+    // Bob's joyful moment on yet another thread stays out of Alice's motif
+    moment(&mut world, &mut index, BOB, "Attic", "bob's secret", Some("joy"));
+
+    let report = consolidation_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!((report.filaments, report.motifs), (0, 1));
+
+    let motifs = consolidated::<Motif>(&world, |motif| &motif.base);
+    let (owner, sources, text) = &motifs[0];
+    assert_eq!(*owner, ALICE);
+    let mut sources = sources.clone();
+    let mut alices = alices;
+    sources.sort();
+    alices.sort();
+    assert_eq!(sources, alices);
+    assert!(text.starts_with("#joy across threads"), "{}", text);
+}
+
+#[test]
+fn deleted_moments_drop_out_and_summaries_keep_the_highest_security_level() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let deleted = moment(&mut world, &mut index, ALICE, "Lane", "deleted secret", None);
+    let classified = moment(&mut world, &mut index, ALICE, "Lane", "classified", None);
+    // Too few to cluster yet: both are only marked consolidating
+    let report = consolidation_system(&mut world, &mut index, &law(), 1.0, &mut ChangeSet::new(1));
+    assert_eq!((report.marked, report.filaments), (2, 0));
+
+    world.get::<&mut Moment>(index.entity(&deleted).unwrap()).unwrap().base.deleted_at = Some(Utc::now());
+    world.get::<&mut Moment>(index.entity(&classified).unwrap()).unwrap().base.security_level = 2;
+    let later: Vec<Uuid> = (0..2).map(|i| moment(&mut world, &mut index, ALICE, "Lane", &format!("later {}", i), None)).collect();
+
+    let report = consolidation_system(&mut world, &mut index, &law(), 2.0, &mut ChangeSet::new(2));
+    assert_eq!(report.filaments, 1);
+    let filaments = consolidated::<Filament>(&world, |filament| &filament.base);
+    let (_, sources, text) = &filaments[0];
+    assert_eq!(sources, &[vec![classified], later].concat());
+    assert!(!text.contains("deleted"), "{}", text);
+    let level = world.query::<&Filament>().iter().map(|(_, filament)| filament.base.security_level).next();
+    assert_eq!(level, Some(2));
+}
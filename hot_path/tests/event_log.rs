@@ -0,0 +1,256 @@
+// Primary memory event log: records survive reopening, torn appends are dropped, segments roll
+// over, and damage inside a sealed segment is reported rather than skipped. Verification finds
+// the first entry that was edited, dropped or reordered, even when its frame CRC was fixed up.
+
+use chrono::Utc;
+use familiar_hot_path::event_log::{read_dir, verify, EventLog, LogError, SyncPolicy};
+use familiar_hot_path::migration::SchemaStamp;
+use familiar_hot_path::persistence::{EventType, MemoryPersistence, PersistenceConfig, PrimaryMemoryEntry};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn entry(version: u64, text: &str) -> PrimaryMemoryEntry {
+    PrimaryMemoryEntry {
+        entity_id: Uuid::from_u128(7),
+        version,
+        parent_version: version.checked_sub(1).filter(|parent| *parent > 0),
+        data: serde_json::to_vec(&serde_json::json!({ "text": text })).unwrap(),
+        timestamp: Utc::now(),
+        event_type: EventType::ComponentUpdated,
+        checksum: String::new(),
+        schema: SchemaStamp::current(),
+        prev_checksum: String::new(),
+        sequence: 0,
+    }
+}
+
+fn open(dir: &Path, segment_bytes: u64) -> EventLog {
+    EventLog::open(dir, segment_bytes, SyncPolicy::Always, Duration::ZERO).unwrap()
+}
+
+fn versions(dir: &Path) -> Vec<u64> {
+    read_dir(dir).unwrap().into_iter().map(|record| record.entry.version).collect()
+}
+
+fn segment_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn records_survive_reopening() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut log = open(dir.path(), 1 << 20);
+        log.append(entry(1, "first")).unwrap();
+        log.append(entry(2, "second")).unwrap();
+    }
+    let mut log = open(dir.path(), 1 << 20);
+    log.append(entry(3, "third")).unwrap();
+
+    let records = log.read_all().unwrap();
+    assert_eq!(records.iter().map(|r| r.entry.version).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(records[0].entry.data, entry(1, "first").data);
+    assert!(records.windows(2).all(|pair| pair[0].position < pair[1].position));
+}
+
+#[test]
+fn torn_append_is_truncated_on_open() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut log = open(dir.path(), 1 << 20);
+        log.append(entry(1, "kept")).unwrap();
+    }
+    let segment = dir.path().join(&segment_files(dir.path())[0]);
+    let whole = fs::metadata(&segment).unwrap().len();
+    // A crash mid-append: the header promises more payload than made it to disk
+    let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+    file.write_all(&100u32.to_le_bytes()).unwrap();
+    file.write_all(&[0, 0, 0, 0, b'{']).unwrap();
+    drop(file);
+
+    // Readers stop at the torn tail; reopening for writes cuts it off
+    assert_eq!(versions(dir.path()), vec![1]);
+    let mut log = open(dir.path(), 1 << 20);
+    assert_eq!(fs::metadata(&segment).unwrap().len(), whole);
+    log.append(entry(2, "after the crash")).unwrap();
+    assert_eq!(versions(dir.path()), vec![1, 2]);
+}
+
+#[test]
+fn segments_roll_over_at_the_size_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut log = open(dir.path(), 1000);
+    for version in 1..=10 {
+        log.append(entry(version, "a moment worth remembering")).unwrap();
+    }
+    let files = segment_files(dir.path());
+    assert!(files.len() > 1, "expected several segments, got {files:?}");
+    for file in &files {
+        assert!(fs::metadata(dir.path().join(file)).unwrap().len() <= 1000);
+    }
+    assert_eq!(versions(dir.path()), (1..=10).collect::<Vec<_>>());
+
+    // Appends continue in the newest segment after reopening
+    drop(log);
+    let mut log = open(dir.path(), 1000);
+    log.append(entry(11, "later")).unwrap();
+    assert_eq!(versions(dir.path()), (1..=11).collect::<Vec<_>>());
+}
+
+#[test]
+fn damage_in_a_sealed_segment_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut log = open(dir.path(), 1000);
+    for version in 1..=6 {
+        log.append(entry(version, "a moment worth remembering")).unwrap();
+    }
+    let first = dir.path().join(&segment_files(dir.path())[0]);
+    let mut bytes = fs::read(&first).unwrap();
+    let last = bytes.len() - 2;
+    bytes[last] ^= 0xff;
+    fs::write(&first, bytes).unwrap();
+
+    match read_dir(dir.path()) {
+        Err(LogError::Corrupt { segment, reason, .. }) => {
+            assert_eq!(segment, segment_files(dir.path())[0]);
+            assert!(reason.contains("checksum"), "{reason}");
+        }
+        other => panic!("expected a corrupt record, got {other:?}"),
+    }
+}
+
+#[test]
+fn persistence_appends_to_primary_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PersistenceConfig { fsync: SyncPolicy::Interval, ..Default::default() };
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    persistence.append_primary_memory(entry(1, "one")).unwrap();
+    persistence.append_primary_memory(entry(2, "two")).unwrap();
+    persistence.sync().unwrap();
+    drop(persistence);
+
+    let reopened = MemoryPersistence::open(dir.path(), &config).unwrap();
+    let records = reopened.primary_memory().unwrap();
+    assert_eq!(records.iter().map(|r| r.entry.version).collect::<Vec<_>>(), vec![1, 2]);
+
+    // Without a log, primary memory is refused rather than silently dropped
+    assert!(MemoryPersistence::new().append_primary_memory(entry(1, "lost")).is_err());
+}
+
+/// Frame one JSON payload the way the log does: [len][crc32][payload]
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = (payload.len() as u32).to_le_bytes().to_vec();
+    frame.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Rewrite the single segment of `dir` from its entries, passed through `edit`, with valid frames
+fn rewrite(dir: &Path, edit: impl FnOnce(&mut Vec<PrimaryMemoryEntry>)) {
+    let mut entries: Vec<PrimaryMemoryEntry> = read_dir(dir).unwrap().into_iter().map(|r| r.entry).collect();
+    edit(&mut entries);
+    let bytes: Vec<u8> = entries.iter().flat_map(|e| frame(&serde_json::to_vec(e).unwrap())).collect();
+    fs::write(dir.join(&segment_files(dir)[0]), bytes).unwrap();
+}
+
+fn chained_log(dir: &Path) {
+    let mut log = open(dir, 1 << 20);
+    for (version, text) in [(1, "alpha"), (2, "bravo"), (3, "charlie"), (4, "delta")] {
+        log.append(entry(version, text)).unwrap();
+    }
+}
+
+#[test]
+fn entries_are_chained() {
+    let dir = tempfile::tempdir().unwrap();
+    chained_log(dir.path());
+    let records = read_dir(dir.path()).unwrap();
+    assert_eq!(records[0].entry.prev_checksum, "");
+    for pair in records.windows(2) {
+        assert_eq!(pair[1].entry.prev_checksum, pair[0].entry.checksum);
+    }
+    for record in &records {
+        assert_eq!(record.entry.checksum.len(), 64);
+        assert_eq!(record.entry.checksum, record.entry.compute_checksum());
+    }
+
+    // The chain carries on across a restart
+    let mut log = open(dir.path(), 1 << 20);
+    let appended = log.append(entry(5, "echo")).unwrap();
+    assert_eq!(appended.entry.prev_checksum, records[3].entry.checksum);
+
+    let verification = verify(dir.path());
+    assert!(verification.is_ok(), "{:?}", verification.corruption);
+    assert_eq!(verification.verified, 5);
+}
+
+#[test]
+fn checksum_covers_content_not_just_length() {
+    // Same length, different bytes
+    let (a, b) = (entry(1, "abc"), entry(1, "abd"));
+    let b = PrimaryMemoryEntry { timestamp: a.timestamp, ..b };
+    assert_eq!(a.data.len(), b.data.len());
+    assert_ne!(a.compute_checksum(), b.compute_checksum());
+
+    let moved = PrimaryMemoryEntry { prev_checksum: "00".to_string(), ..a.clone() };
+    assert_ne!(a.compute_checksum(), moved.compute_checksum());
+}
+
+#[test]
+fn verify_finds_an_edited_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    chained_log(dir.path());
+    // Rewrite the third entry's payload and give it a valid frame: only the hash can tell
+    rewrite(dir.path(), |entries| {
+        entries[2].data = serde_json::to_vec(&serde_json::json!({ "text": "CHARLIE" })).unwrap();
+    });
+
+    let verification = verify(dir.path());
+    assert_eq!(verification.verified, 2);
+    match verification.corruption {
+        Some(LogError::Tampered { version, .. }) => assert_eq!(version, 3),
+        other => panic!("expected a tampered entry, got {other:?}"),
+    }
+}
+
+#[test]
+fn verify_finds_a_dropped_or_reordered_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    chained_log(dir.path());
+    rewrite(dir.path(), |entries| {
+        entries.remove(1);
+    });
+    let verification = verify(dir.path());
+    assert_eq!(verification.verified, 1);
+    assert!(matches!(verification.corruption, Some(LogError::BrokenChain { .. })), "{:?}", verification.corruption);
+
+    let dir = tempfile::tempdir().unwrap();
+    chained_log(dir.path());
+    rewrite(dir.path(), |entries| entries.swap(2, 3));
+    let verification = verify(dir.path());
+    assert_eq!(verification.verified, 2);
+    assert!(matches!(verification.corruption, Some(LogError::BrokenChain { .. })), "{:?}", verification.corruption);
+}
+
+#[test]
+fn verify_reports_a_damaged_frame() {
+    let dir = tempfile::tempdir().unwrap();
+    chained_log(dir.path());
+    let segment = dir.path().join(&segment_files(dir.path())[0]);
+    let mut bytes = fs::read(&segment).unwrap();
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0x01;
+    fs::write(&segment, bytes).unwrap();
+
+    let verification = verify(dir.path());
+    assert!(verification.verified < 4);
+    assert!(matches!(verification.corruption, Some(LogError::Corrupt { .. })), "{:?}", verification.corruption);
+}
@@ -0,0 +1,108 @@
+// Law formula engine: precedence and associativity, conditionals, comparisons, the built-in
+// functions, assignments, and the errors for unknown names and malformed formulas - parse errors
+// point at a character offset in the source.
+
+use familiar_hot_path::components::DecayComponent;
+use familiar_hot_path::formula::{Formula, FormulaError, Scope};
+
+fn eval(source: &str) -> f64 {
+    eval_with(source, &Scope::new())
+}
+
+fn eval_with(source: &str, scope: &Scope) -> f64 {
+    Formula::compile(source).unwrap().evaluate(scope).unwrap()
+}
+
+fn parse_error(source: &str) -> (usize, String) {
+    match Formula::compile(source) {
+        Err(FormulaError::Parse { position, message }) => (position, message),
+        other => panic!("expected a parse error for `{}`, got {:?}", source, other),
+    }
+}
+
+#[test]
+fn operators_follow_precedence_and_associativity() {
+    assert_eq!(eval("1 + 2 * 3"), 7.0);
+    assert_eq!(eval("(1 + 2) * 3"), 9.0);
+    assert_eq!(eval("10 - 4 - 3"), 3.0);
+    assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+    assert_eq!(eval("2 ** 3"), 8.0);
+    assert_eq!(eval("-2 ^ 2"), -4.0);
+    assert_eq!(eval("7 % 4 + 1"), 4.0);
+    assert_eq!(eval("1.5e1 / 3"), 5.0);
+}
+
+#[test]
+fn comparisons_and_logic_yield_one_or_zero() {
+    assert_eq!(eval("1 < 2"), 1.0);
+    assert_eq!(eval("2 <= 1"), 0.0);
+    assert_eq!(eval("3 > 2 and 2 >= 2"), 1.0);
+    assert_eq!(eval("1 == 2 or 1 != 2"), 1.0);
+    assert_eq!(eval("not 1 < 2"), 0.0);
+    assert_eq!(eval("!(1 > 2) && true"), 1.0);
+    // Comparisons bind looser than arithmetic
+    assert_eq!(eval("1 + 1 == 2"), 1.0);
+}
+
+#[test]
+fn conditionals_pick_a_branch() {
+    let mut scope = Scope::new();
+    scope.set("strength", 0.05);
+    assert_eq!(eval_with("0 if strength < 0.1 else strength", &scope), 0.0);
+    scope.set("strength", 0.5);
+    assert_eq!(eval_with("0 if strength < 0.1 else strength", &scope), 0.5);
+    // `else` chains nest to the right
+    assert_eq!(eval_with("1 if strength > 0.9 else 2 if strength > 0.4 else 3", &scope), 2.0);
+}
+
+#[test]
+fn functions_evaluate_with_their_arity() {
+    assert_eq!(eval("pow(2, 10)"), 1024.0);
+    assert_eq!(eval("min(3, 1, 2)"), 1.0);
+    assert_eq!(eval("max(3, 1, 2)"), 3.0);
+    assert_eq!(eval("exp(0)"), 1.0);
+    assert!((eval("ln(exp(2))") - 2.0).abs() < 1e-12);
+    assert_eq!(eval("clamp(5, 0, 1)"), 1.0);
+
+    assert_eq!(Formula::compile("pow(2)"), Err(FormulaError::Arity { function: "pow".to_string(), found: 1 }));
+    assert_eq!(Formula::compile("frobnicate(1)"), Err(FormulaError::UnknownFunction("frobnicate".to_string())));
+}
+
+#[test]
+fn assignments_write_the_target_field() {
+    let formula = Formula::compile("strength = strength * pow(0.5, time_elapsed / half_life)").unwrap();
+    assert_eq!(formula.target(), Some("strength"));
+    assert_eq!(formula.referenced_names(), vec!["strength", "time_elapsed", "half_life"]);
+
+    let mut decay = DecayComponent { strength: 0.8, half_life: 60.0, last_update: 0.0 };
+    let mut scope = Scope::new();
+    scope.bind_fields(&decay, &["strength".to_string(), "half_life".to_string()]).set("time_elapsed", 60.0);
+    formula.apply(&scope, &mut decay).unwrap();
+    assert!((decay.strength - 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn unknown_variables_and_bad_arithmetic_fail_at_evaluation() {
+    let formula = Formula::compile("strength * missing").unwrap();
+    let mut scope = Scope::new();
+    scope.set("strength", 1.0);
+    assert_eq!(formula.evaluate(&scope), Err(FormulaError::UnboundVariable("missing".to_string())));
+    assert_eq!(Formula::compile("1 / 0").unwrap().evaluate(&scope), Err(FormulaError::DivisionByZero));
+    assert_eq!(Formula::compile("sqrt(-1)").unwrap().evaluate(&scope), Err(FormulaError::NotANumber));
+}
+
+#[test]
+fn parse_errors_point_at_a_character_offset() {
+    // Tokenizer errors
+    assert_eq!(parse_error("1 + $").0, 4);
+    assert_eq!(parse_error("1.2.3 + 1"), (0, "invalid number `1.2.3`".to_string()));
+    // Parser errors use the same unit, not token indexes
+    assert_eq!(parse_error("strength +  )"), (12, "unexpected `)`".to_string()));
+    assert_eq!(parse_error("(strength + 1"), (13, "expected `)`".to_string()));
+    assert_eq!(parse_error("1 if strength then 2").0, 14);
+    assert_eq!(parse_error("pow(1 2)"), (6, "expected `,` or `)` in argument list".to_string()));
+    assert_eq!(parse_error("1 2"), (2, "unexpected number 2".to_string()));
+    assert_eq!(parse_error("1 +"), (3, "unexpected end of formula".to_string()));
+    // Offsets count characters, not bytes
+    assert_eq!(parse_error("\u{3000}\u{3000}1 +").0, 5);
+}
@@ -0,0 +1,123 @@
+// Bond and Binding records name other entities by id: a principal who may see the bond or binding
+// itself but not one of those entities gets the record without them, rather than their ids.
+
+use async_graphql::{Request, Schema, Variables};
+use chrono::Utc;
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::common::GqlCommand;
+use familiar_hot_path::components::{
+    AccessScope, BaseEntity, Binding, BindingPoint, Bond, Cardinality, DisplayText, EntityType, Moment, Thread, Visibility,
+};
+use familiar_hot_path::graphql::{AppSchema, MutationRoot, QueryRoot, SubscriptionRoot};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::security::{Principal, Role};
+use familiar_hot_path::snapshot::{SnapshotStore, WorldSnapshot};
+use hecs::World;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const ORG: Uuid = Uuid::from_u128(0x100);
+const ALICE: Uuid = Uuid::from_u128(1);
+const BOB: Uuid = Uuid::from_u128(2);
+
+fn base(owner_id: Uuid, visibility: Visibility) -> BaseEntity {
+    BaseEntity {
+        id: Some(Uuid::new_v4()),
+        org_id: ORG,
+        owner_id,
+        created_at: Some(Utc::now()),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![],
+        component_ids: vec![],
+        sub_type: None,
+        visibility,
+        security_level: 0,
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    }
+}
+
+fn thread(world: &mut World, index: &mut EntityIndex, base: BaseEntity) -> Uuid {
+    let id = base.id.unwrap();
+    index.spawn(world, (Thread { base }, DisplayText(id.to_string()), EntityType("thread".to_string())));
+    id
+}
+
+fn moment(world: &mut World, index: &mut EntityIndex, base: BaseEntity, thread_id: Uuid) -> Uuid {
+    let id = base.id.unwrap();
+    let author_id = base.owner_id;
+    index.spawn(world, (
+        Moment { base, thread_id, author_id, binding_hint: None, binding_id: None, cardinality: None },
+        DisplayText(id.to_string()),
+        EntityType("moment".to_string()),
+    ));
+    id
+}
+
+/// Run `query` as Alice, a member of the org, against a snapshot of `world`
+async fn query_as_alice(world: &World, index: &EntityIndex, query: &str, variables: Value) -> Value {
+    let store = Arc::new(SnapshotStore::new());
+    store.publish(WorldSnapshot::capture(world, index, 1, 0.0), Arc::new(ChangeSet::new(1)));
+    let (sender, _commands) = crossbeam_channel::unbounded::<GqlCommand>();
+    let schema: AppSchema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot).data(sender).data(store).finish();
+    let principal = Principal { user_id: ALICE, org_id: ORG, household: vec![], role: Role::Member, scope: AccessScope::Admin };
+    let response = schema.execute(Request::new(query).variables(Variables::from_json(variables)).data(Arc::new(principal))).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[tokio::test]
+async fn records_leave_out_what_the_principal_cannot_see() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let shared_thread = thread(&mut world, &mut index, base(ALICE, Visibility::Org));
+    let shared_moment = moment(&mut world, &mut index, base(ALICE, Visibility::Org), shared_thread);
+    // Bob's own thread, and his moment on a thread Alice can see
+    let private_thread = thread(&mut world, &mut index, base(BOB, Visibility::Private));
+    let private_moment = moment(&mut world, &mut index, base(BOB, Visibility::Private), shared_thread);
+
+    let points = vec![
+        BindingPoint::new(shared_thread, shared_moment, Cardinality::Actor),
+        BindingPoint::new(private_thread, shared_moment, Cardinality::Recipient),
+        BindingPoint::new(shared_thread, private_moment, Cardinality::Recipient),
+    ];
+    let binding = base(ALICE, Visibility::Org);
+    let binding_id = binding.id.unwrap();
+    index.spawn(&mut world, (
+        Binding { base: binding, points, thread_ids: vec![shared_thread, private_thread] },
+        EntityType("binding".to_string()),
+    ));
+    let bond = base(ALICE, Visibility::Org);
+    let bond_id = bond.id.unwrap();
+    index.spawn(&mut world, (
+        Bond {
+            base: bond,
+            thread_ids: vec![shared_thread, private_thread],
+            affinity_score: 0.9,
+            bond_strength: 0.9,
+            component_context: vec![],
+        },
+        EntityType("bond".to_string()),
+    ));
+
+    let data = query_as_alice(
+        &world,
+        &index,
+        "query($binding: UUID!, $bond: UUID!) {
+            binding: entity(id: $binding) {
+                ... on Binding { points { threadId momentId } record { threadIds points { threadId momentId } } }
+            }
+            bond: entity(id: $bond) { ... on Bond { record { threadIds } } }
+        }",
+        json!({ "binding": binding_id, "bond": bond_id }),
+    )
+    .await;
+
+    let only_visible_point = json!([{ "threadId": shared_thread, "momentId": shared_moment }]);
+    assert_eq!(data["binding"]["points"], only_visible_point);
+    assert_eq!(data["binding"]["record"]["points"], only_visible_point);
+    assert_eq!(data["binding"]["record"]["threadIds"], json!([shared_thread]));
+    assert_eq!(data["bond"]["record"]["threadIds"], json!([shared_thread]));
+}
@@ -0,0 +1,120 @@
+// Law export validation: the shipped export compiles, and inconsistent laws - unknown or
+// predicate-only variables, missing constants, bad constraints, missing targets, duplicates and
+// unreadable files - are refused with an error naming the law and the problem.
+
+use familiar_hot_path::components::{Law, LawTrigger};
+use familiar_hot_path::laws::{load_laws, CompiledLaw, LawError, LawKind};
+use serde_json::json;
+
+fn law(formula: &str, variables: &[&str]) -> Law {
+    Law {
+        name: "test".to_string(),
+        trigger: LawTrigger::OnTick,
+        applies_to: vec!["moment".to_string()],
+        formula: formula.to_string(),
+        variables: variables.iter().map(|v| v.to_string()).collect(),
+        constants: serde_json::Map::new(),
+        constraints: None,
+    }
+}
+
+fn with_constants(mut law: Law, constants: serde_json::Value) -> Law {
+    law.constants = constants.as_object().unwrap().clone();
+    law
+}
+
+fn with_constraints(mut law: Law, constraints: serde_json::Value) -> Law {
+    law.constraints = Some(constraints.as_object().unwrap().clone());
+    law
+}
+
+fn write_laws(laws: serde_json::Value) -> tempfile::NamedTempFile {
+    let file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    let export = json!({ "schema_version": "1.0.0", "generated_by": "familiar-cold-path", "laws": laws });
+    std::fs::write(file.path(), serde_json::to_vec(&export).unwrap()).unwrap();
+    file
+}
+
+#[test]
+fn the_shipped_law_export_compiles() {
+    let laws = load_laws(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/laws.json")).unwrap();
+    let names: Vec<&str> = laws.iter().map(CompiledLaw::name).collect();
+    assert_eq!(names, vec!["decay", "resonance", "binding", "consolidation", "bonding"]);
+    assert_eq!(laws[0].kind, LawKind::Field { target: "strength".to_string() });
+    assert_eq!(laws[2].kind, LawKind::Predicate { action: "create_binding_if".to_string() });
+}
+
+#[test]
+fn unknown_variables_are_refused() {
+    let result = CompiledLaw::compile(law("strength = strength * mood", &["strength", "mood"]));
+    assert!(matches!(result, Err(LawError::UnknownVariable { variable, .. }) if variable == "mood"));
+
+    // The assignment target must be declared too
+    let result = CompiledLaw::compile(law("half_life = 10", &["strength"]));
+    assert!(matches!(result, Err(LawError::UnknownVariable { variable, .. }) if variable == "half_life"));
+}
+
+#[test]
+fn field_laws_cannot_use_variables_only_structural_systems_bind() {
+    for variable in ["affinity", "position"] {
+        let result = CompiledLaw::compile(law(&format!("strength = strength * {}", variable), &["strength", variable]));
+        assert!(
+            matches!(&result, Err(LawError::PredicateOnlyVariable { variable: found, .. }) if found == variable),
+            "{:?}",
+            result.map(|law| law.kind)
+        );
+    }
+    // ...while a predicate law may
+    let binding = with_constants(law("create_binding_if(affinity > threshold)", &["affinity"]), json!({ "threshold": 0.5 }));
+    assert!(CompiledLaw::compile(binding).is_ok());
+}
+
+#[test]
+fn names_that_are_neither_variables_nor_constants_are_refused() {
+    let result = CompiledLaw::compile(law("strength = strength * multiplier", &["strength"]));
+    assert!(matches!(result, Err(LawError::MissingConstant { name, .. }) if name == "multiplier"));
+
+    let fixed = with_constants(law("strength = strength * multiplier", &["strength"]), json!({ "multiplier": 1.1 }));
+    assert!(CompiledLaw::compile(fixed).is_ok());
+
+    let result = CompiledLaw::compile(with_constants(law("strength = strength * m", &["strength"]), json!({ "m": "fast" })));
+    assert!(matches!(result, Err(LawError::InvalidConstant { constant, .. }) if constant == "m"));
+}
+
+#[test]
+fn bad_constraints_are_refused() {
+    let cases = [
+        (json!({ "half_life": { "min": 1.0 } }), "doesn't declare"),
+        (json!({ "strength": 0.5 }), "expected an object"),
+        (json!({ "strength": { "min": "low" } }), "must be numbers"),
+        (json!({ "strength": { "step": 0.1 } }), "only min and max"),
+        (json!({ "strength": { "min": 0.9, "max": 0.1 } }), "min is greater than max"),
+    ];
+    for (constraints, expected) in cases {
+        let result = CompiledLaw::compile(with_constraints(law("strength = strength", &["strength"]), constraints.clone()));
+        match result {
+            Err(LawError::InvalidConstraint { reason, .. }) => assert!(reason.contains(expected), "{}: {}", constraints, reason),
+            other => panic!("{}: expected an invalid constraint, got {:?}", constraints, other.map(|law| law.kind)),
+        }
+    }
+}
+
+#[test]
+fn formulas_must_parse_and_field_laws_must_assign() {
+    let result = CompiledLaw::compile(law("strength = strength *", &["strength"]));
+    assert!(matches!(result, Err(LawError::Formula { .. })));
+    let result = CompiledLaw::compile(law("strength * 2", &["strength"]));
+    assert!(matches!(result, Err(LawError::MissingTarget { .. })));
+}
+
+#[test]
+fn law_files_are_checked_as_a_whole() {
+    let decay = json!({
+        "name": "decay", "trigger": "on_tick", "applies_to": ["moment"],
+        "formula": "strength = strength * 0.5", "variables": ["strength"], "constants": {}
+    });
+    assert_eq!(load_laws(write_laws(json!([decay.clone()])).path()).unwrap().len(), 1);
+    assert!(matches!(load_laws(write_laws(json!([decay.clone(), decay])).path()), Err(LawError::Duplicate(name)) if name == "decay"));
+    assert!(matches!(load_laws(write_laws(json!([{ "name": "decay" }])).path()), Err(LawError::Parse { .. })));
+    assert!(matches!(load_laws("/nonexistent/laws.json"), Err(LawError::Io { .. })));
+}
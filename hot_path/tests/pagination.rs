@@ -0,0 +1,218 @@
+// Paginated entity queries: cursors are keys rather than offsets, so a page stays put while
+// earlier entities come and go; bad arguments are refused; and every EntityFilter field narrows
+// the matches on its own.
+
+use async_graphql::{Request, Schema, Variables};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::common::GqlCommand;
+use familiar_hot_path::components::{AccessScope, BaseEntity, DecayComponent, DisplayText, EntityType, Motif, Visibility};
+use familiar_hot_path::graphql::{AppSchema, EntityFilter, MutationRoot, QueryRoot, SubscriptionRoot};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::security::{Principal, Role};
+use familiar_hot_path::snapshot::{SnapshotStore, WorldSnapshot};
+use hecs::World;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const ORG: Uuid = Uuid::from_u128(0x100);
+const OWNER: Uuid = Uuid::from_u128(1);
+const OTHER: Uuid = Uuid::from_u128(2);
+
+fn at(second: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(second)
+}
+
+fn base(created_at: DateTime<Utc>) -> BaseEntity {
+    BaseEntity {
+        id: Some(Uuid::new_v4()),
+        org_id: ORG,
+        owner_id: OWNER,
+        created_at: Some(created_at),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![],
+        component_ids: vec![],
+        sub_type: None,
+        visibility: Visibility::Org,
+        security_level: 0,
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    }
+}
+
+fn spawn(world: &mut World, index: &mut EntityIndex, base: BaseEntity, strength: f32) -> Uuid {
+    let id = base.id.unwrap();
+    index.spawn(world, (
+        Motif { base },
+        DisplayText(id.to_string()),
+        EntityType("motif".to_string()),
+        DecayComponent { strength, half_life: 60.0, last_update: 0.0 },
+    ));
+    id
+}
+
+/// A schema over `store`, and a way to run queries against it as an org admin
+struct Api {
+    schema: AppSchema,
+    // Kept so observation reports have somewhere to go
+    _commands: crossbeam_channel::Receiver<GqlCommand>,
+}
+
+impl Api {
+    fn new(store: Arc<SnapshotStore>) -> Self {
+        let (sender, commands) = crossbeam_channel::unbounded();
+        let schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot).data(sender).data(store).finish();
+        Self { schema, _commands: commands }
+    }
+
+    async fn query(&self, query: &str, variables: Value) -> async_graphql::Response {
+        let principal = Principal { user_id: OWNER, org_id: ORG, household: vec![], role: Role::Admin, scope: AccessScope::Admin };
+        let request = Request::new(query).variables(Variables::from_json(variables)).data(Arc::new(principal));
+        self.schema.execute(request).await
+    }
+
+    /// Ids on one page of `entitiesByType(entityType: "motif")`, the total and the end cursor
+    async fn page(&self, first: i32, after: Option<&str>) -> (Vec<String>, usize, String) {
+        let response = self
+            .query(
+                "query($first: Int, $after: String) {
+                    entitiesByType(entityType: \"motif\", first: $first, after: $after) {
+                        totalCount pageInfo { endCursor } edges { node { id } }
+                    }
+                }",
+                json!({ "first": first, "after": after }),
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let connection = &response.data.into_json().unwrap()["entitiesByType"];
+        let ids = connection["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["node"]["id"].as_str().unwrap().to_string())
+            .collect();
+        let total = connection["totalCount"].as_u64().unwrap() as usize;
+        (ids, total, connection["pageInfo"]["endCursor"].as_str().unwrap_or_default().to_string())
+    }
+
+    async fn error(&self, first: i32, after: &str) -> String {
+        let response = self
+            .query(
+                "query($first: Int, $after: String) {
+                    entitiesByType(entityType: \"motif\", first: $first, after: $after) { totalCount }
+                }",
+                json!({ "first": first, "after": after }),
+            )
+            .await;
+        assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+        response.errors[0].message.clone()
+    }
+}
+
+fn publish(store: &SnapshotStore, world: &World, index: &EntityIndex, tick: u64) {
+    store.publish(WorldSnapshot::capture(world, index, tick, 0.0), Arc::new(ChangeSet::new(tick)));
+}
+
+fn strings(ids: &[Uuid]) -> Vec<String> {
+    ids.iter().map(Uuid::to_string).collect()
+}
+
+#[tokio::test]
+async fn pages_stay_put_across_inserts_and_deletes() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let ids: Vec<Uuid> = (0..6).map(|i| spawn(&mut world, &mut index, base(at(i)), 0.5)).collect();
+    let store = Arc::new(SnapshotStore::new());
+    publish(&store, &world, &index, 1);
+    let api = Api::new(store.clone());
+
+    let (first_page, total, cursor) = api.page(2, None).await;
+    assert_eq!((first_page, total), (strings(&ids[0..2]), 6));
+
+    // An older entity arrives and the cursor's own entity goes away...
+    let older = spawn(&mut world, &mut index, base(at(-10)), 0.5);
+    let last_seen = index.entity(&ids[1]).unwrap();
+    index.despawn(&mut world, last_seen).unwrap();
+    publish(&store, &world, &index, 2);
+
+    // ...yet the next page picks up right where the first one stopped
+    let (second_page, total, cursor) = api.page(2, Some(&cursor)).await;
+    assert_eq!((second_page, total), (strings(&ids[2..4]), 6));
+
+    // Soft-deleting an entity on the next page drops it without shifting the others
+    let mut deleted = world.get::<&mut Motif>(index.entity(&ids[4]).unwrap()).unwrap();
+    deleted.base.deleted_at = Some(Utc::now());
+    drop(deleted);
+    publish(&store, &world, &index, 3);
+    let (third_page, total, _) = api.page(2, Some(&cursor)).await;
+    assert_eq!((third_page, total), (strings(&ids[5..6]), 5));
+
+    let (from_the_start, _, _) = api.page(1, None).await;
+    assert_eq!(from_the_start, strings(&[older]));
+}
+
+#[tokio::test]
+async fn negative_first_and_bad_cursors_are_refused() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    spawn(&mut world, &mut index, base(at(0)), 0.5);
+    let store = Arc::new(SnapshotStore::new());
+    publish(&store, &world, &index, 1);
+    let api = Api::new(store);
+
+    let (_, _, cursor) = api.page(1, None).await;
+    assert!(api.error(-1, &cursor).await.ends_with("`first` must not be negative"));
+    assert!(api.error(1, "not-a-cursor").await.ends_with("invalid `after` cursor"));
+    // Valid base64, but not a page key
+    assert!(api.error(1, "e30").await.ends_with("invalid `after` cursor"));
+
+    // `first: 0` is a valid, empty page
+    let (page, total, _) = api.page(0, None).await;
+    assert_eq!((page.len(), total), (0, 1));
+}
+
+#[test]
+fn every_filter_field_narrows_the_matches() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let plain = spawn(&mut world, &mut index, base(at(0)), 0.25);
+    let tagged = spawn(&mut world, &mut index, BaseEntity { tags: vec!["family".to_string()], ..base(at(10)) }, 0.5);
+    let public = spawn(&mut world, &mut index, BaseEntity { visibility: Visibility::Public, ..base(at(20)) }, 0.5);
+    let others = spawn(&mut world, &mut index, BaseEntity { owner_id: OTHER, ..base(at(30)) }, 0.75);
+    let deleted = spawn(&mut world, &mut index, BaseEntity { deleted_at: Some(at(50)), ..base(at(40)) }, 0.5);
+    let thread = index.spawn(&mut world, (DisplayText("thread".to_string()), EntityType("thread".to_string())));
+    let snapshot = WorldSnapshot::capture(&world, &index, 1, 0.0);
+
+    let matching = |filter: EntityFilter| {
+        let mut ids: Vec<String> = snapshot.entities().filter(|entity| filter.matches(entity)).map(|entity| entity.external_id()).collect();
+        ids.sort();
+        ids
+    };
+    let sorted = |ids: Vec<Uuid>| {
+        let mut ids = strings(&ids);
+        ids.sort();
+        ids
+    };
+    // Entities without a BaseEntity go by their ECS handle
+    let thread = format!("{:?}", thread);
+    let mut plain_and_thread = sorted(vec![plain, tagged, public, others]);
+    plain_and_thread.push(thread.clone());
+    plain_and_thread.sort();
+
+    // Live entities only by default
+    assert_eq!(matching(EntityFilter::default()), plain_and_thread);
+    assert_eq!(matching(EntityFilter { deleted: Some(true), ..Default::default() }), strings(&[deleted]));
+    assert_eq!(matching(EntityFilter { entity_type: Some("thread".to_string()), ..Default::default() }), vec![thread]);
+    assert_eq!(matching(EntityFilter { tag: Some("family".to_string()), ..Default::default() }), strings(&[tagged]));
+    assert_eq!(matching(EntityFilter { visibility: Some(Visibility::Public), ..Default::default() }), strings(&[public]));
+    assert_eq!(matching(EntityFilter { owner_id: Some(OTHER), ..Default::default() }), strings(&[others]));
+    // Strength bounds are inclusive and skip entities without a strength
+    assert_eq!(matching(EntityFilter { min_strength: Some(0.75), ..Default::default() }), strings(&[others]));
+    assert_eq!(matching(EntityFilter { max_strength: Some(0.25), ..Default::default() }), strings(&[plain]));
+    // Creation bounds are exclusive and skip entities without a creation time
+    assert_eq!(matching(EntityFilter { created_after: Some(at(20)), ..Default::default() }), strings(&[others]));
+    assert_eq!(matching(EntityFilter { created_before: Some(at(10)), ..Default::default() }), strings(&[plain]));
+    // Set fields combine
+    let combined = EntityFilter { min_strength: Some(0.5), max_strength: Some(0.5), created_after: Some(at(10)), ..Default::default() };
+    assert_eq!(matching(combined), strings(&[public]));
+}
@@ -0,0 +1,253 @@
+// Event-sourced reconstruction: entities written to primary memory come back with every
+// component intact, later entries win, removals drop entities, and a replay limit (timestamp or
+// sequence number) rebuilds the world as it was at that point - without accepting new entries
+// after the part of the log it left out.
+
+use chrono::{Duration as ChronoDuration, Utc};
+use familiar_hot_path::components::{
+    with_base_entity, with_base_entity_mut, AccessScope, AttachedComponents, BaseComponent, BaseEntity, DecayComponent,
+    DisplayText, EntityType, Moment, TemporalPosition, Thread, ThreadId, Visibility,
+};
+use familiar_hot_path::event_log::{EventLog, SyncPolicy};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::migration::Migrations;
+use familiar_hot_path::persistence::{EventType, MemoryPersistence, PersistenceConfig, PersistenceError, PrimaryMemoryEntry};
+use familiar_hot_path::replay::{self, EntityRecord, ReplayLimit};
+use hecs::{Entity, World};
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+const ORG: Uuid = Uuid::from_u128(0x100);
+const OWNER: Uuid = Uuid::from_u128(1);
+
+fn base(id: Uuid, tag: &str) -> BaseEntity {
+    BaseEntity {
+        id: Some(id),
+        org_id: ORG,
+        owner_id: OWNER,
+        created_at: Some(Utc::now()),
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![tag.to_string()],
+        component_ids: vec![],
+        sub_type: Some(tag.to_string()),
+        visibility: Visibility::Org,
+        security_level: 0,
+        access_scope: vec![AccessScope::View],
+        version: 1,
+        parent_version: None,
+    }
+}
+
+fn decay(strength: f32) -> DecayComponent {
+    DecayComponent { strength, half_life: 300.0, last_update: 1_700_000_000.0 }
+}
+
+/// A thread and a moment on it, with the components commands attach
+fn populate(world: &mut World, index: &mut EntityIndex) -> (Entity, Entity) {
+    let thread_id = Uuid::from_u128(10);
+    let thread = index.spawn(world, (
+        Thread { base: base(thread_id, "thread") },
+        DisplayText("Memory Lane".to_string()),
+        ThreadId("Memory Lane".to_string()),
+        EntityType("thread".to_string()),
+        decay(1.0),
+    ));
+    let created_at = Utc::now();
+    let moment = index.spawn(world, (
+        Moment {
+            base: base(Uuid::from_u128(11), "moment"),
+            thread_id,
+            author_id: OWNER,
+            binding_hint: None,
+            binding_id: None,
+            cardinality: None,
+        },
+        DisplayText("coffee with Sam".to_string()),
+        ThreadId("Memory Lane".to_string()),
+        EntityType("moment".to_string()),
+        TemporalPosition::at(created_at),
+        decay(0.8),
+        AttachedComponents(vec![BaseComponent::time(created_at), BaseComponent::emotion("joy", 0.9)]),
+    ));
+    (thread, moment)
+}
+
+fn open(dir: &Path) -> EventLog {
+    EventLog::open(dir, 1 << 20, SyncPolicy::Never, Duration::ZERO).unwrap()
+}
+
+fn append(log: &mut EventLog, world: &World, entity: Entity, event_type: EventType) -> PrimaryMemoryEntry {
+    log.append(replay::entry(world, entity, event_type).unwrap()).unwrap().entry
+}
+
+/// Every entity's record, keyed by UUID, as JSON so worlds can be compared
+fn records(world: &World, index: &EntityIndex) -> Vec<(Uuid, serde_json::Value)> {
+    let mut records: Vec<_> = world
+        .iter()
+        .map(|entity_ref| entity_ref.entity())
+        .map(|entity| {
+            let record = EntityRecord::capture(world, entity).unwrap();
+            (index.uuid(entity).unwrap(), serde_json::to_value(record).unwrap())
+        })
+        .collect();
+    records.sort_by_key(|(id, _)| *id);
+    records
+}
+
+fn text(world: &World, index: &EntityIndex, id: u128) -> Option<String> {
+    let entity = index.entity(&Uuid::from_u128(id))?;
+    world.get::<&DisplayText>(entity).ok().map(|text| text.0.clone())
+}
+
+#[test]
+fn replay_restores_every_component() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, moment) = populate(&mut world, &mut index);
+    let mut log = open(dir.path());
+    append(&mut log, &world, thread, EventType::EntityCreated);
+    append(&mut log, &world, moment, EventType::EntityCreated);
+
+    let replayed = replay::replay_dir(dir.path(), ReplayLimit::none(), &Migrations::default()).unwrap();
+    assert_eq!(replayed.report.applied, 2);
+    assert!(replayed.report.skipped.is_empty());
+    assert_eq!(replayed.report.last_sequence, 2);
+    assert_eq!(records(&replayed.world, &replayed.index), records(&world, &index));
+
+    // The index is rebuilt too, so clients keep addressing entities by the same UUIDs
+    let restored = replayed.index.entity(&Uuid::from_u128(11)).unwrap();
+    let attached = replayed.world.get::<&AttachedComponents>(restored).unwrap();
+    assert!(attached.get("emotion").is_some());
+}
+
+#[test]
+fn later_entries_win_and_removals_drop_entities() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, moment) = populate(&mut world, &mut index);
+    let mut log = open(dir.path());
+    append(&mut log, &world, thread, EventType::EntityCreated);
+    append(&mut log, &world, moment, EventType::EntityCreated);
+
+    world.insert_one(thread, DisplayText("Memory Avenue".to_string())).unwrap();
+    with_base_entity_mut(&mut world, thread, |base| base.record_update());
+    append(&mut log, &world, thread, EventType::ComponentUpdated);
+    log.append(replay::removal_entry(Uuid::from_u128(11), 1)).unwrap();
+
+    let replayed = replay::replay_dir(dir.path(), ReplayLimit::none(), &Migrations::default()).unwrap();
+    assert_eq!(replayed.entity_count(), 1);
+    assert_eq!(text(&replayed.world, &replayed.index, 10).as_deref(), Some("Memory Avenue"));
+    assert!(replayed.index.entity(&Uuid::from_u128(11)).is_none());
+    let restored = replayed.index.entity(&Uuid::from_u128(10)).unwrap();
+    let version = with_base_entity(&replayed.world, restored, |base| (base.version, base.parent_version));
+    assert_eq!(version, Some((2, Some(1))));
+}
+
+#[test]
+fn limits_stop_replay_at_a_sequence_or_timestamp() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, _moment) = populate(&mut world, &mut index);
+    let mut log = open(dir.path());
+    let mut entries = vec![append(&mut log, &world, thread, EventType::EntityCreated)];
+    for name in ["Memory Avenue", "Memory Boulevard"] {
+        world.insert_one(thread, DisplayText(name.to_string())).unwrap();
+        with_base_entity_mut(&mut world, thread, |base| base.record_update());
+        let mut entry = replay::entry(&world, thread, EventType::ComponentUpdated).unwrap();
+        // Space the entries out so a timestamp can fall between them
+        entry.timestamp = entries.last().unwrap().timestamp + ChronoDuration::seconds(60);
+        entries.push(log.append(entry).unwrap().entry);
+    }
+    assert_eq!(entries.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    let migrations = Migrations::default();
+    let by_sequence = ReplayLimit { until_sequence: Some(2), ..ReplayLimit::none() };
+    let replayed = replay::replay_dir(dir.path(), by_sequence, &migrations).unwrap();
+    assert_eq!(replayed.report.applied, 2);
+    assert_eq!(text(&replayed.world, &replayed.index, 10).as_deref(), Some("Memory Avenue"));
+
+    let by_time = ReplayLimit { until: Some(entries[0].timestamp + ChronoDuration::seconds(30)), ..ReplayLimit::none() };
+    let replayed = replay::replay_dir(dir.path(), by_time, &migrations).unwrap();
+    assert_eq!(replayed.report.last_sequence, 1);
+    assert_eq!(text(&replayed.world, &replayed.index, 10).as_deref(), Some("Memory Lane"));
+
+    let replayed = replay::replay_dir(dir.path(), ReplayLimit::none(), &migrations).unwrap();
+    assert_eq!(text(&replayed.world, &replayed.index, 10).as_deref(), Some("Memory Boulevard"));
+}
+
+#[test]
+fn a_limited_replay_leaves_primary_memory_read_only_until_a_full_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PersistenceConfig { fsync: SyncPolicy::Never, ..Default::default() };
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, moment) = populate(&mut world, &mut index);
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    for (entity, event_type) in [(thread, EventType::EntityCreated), (moment, EventType::EntityCreated)] {
+        persistence.append_primary_memory(replay::entry(&world, entity, event_type).unwrap()).unwrap();
+    }
+    drop(persistence);
+
+    // Restart as of entry 1: the moment at entry 2 isn't in the world, so nothing may follow it
+    let migrations = Migrations::default();
+    let limit = ReplayLimit { until_sequence: Some(1), ..ReplayLimit::none() };
+    let mut limited = replay::replay_dir(dir.path(), limit, &migrations).unwrap();
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    persistence.restored(limited.report.last_sequence);
+    assert_eq!(persistence.read_only(), Some("the world was restored up to entry 1 of 2"));
+
+    let lane = limited.index.entity(&Uuid::from_u128(10)).unwrap();
+    limited.world.insert_one(lane, DisplayText("Memory Avenue".to_string())).unwrap();
+    with_base_entity_mut(&mut limited.world, lane, |base| base.record_update());
+    let refused = persistence.append_primary_memory(replay::entry(&limited.world, lane, EventType::ComponentUpdated).unwrap());
+    assert!(matches!(refused, Err(PersistenceError::ReadOnly(_))));
+    drop(persistence);
+
+    // A full restart finds the log as it was and takes appends again
+    let full = replay::replay_dir(dir.path(), ReplayLimit::none(), &migrations).unwrap();
+    assert_eq!(full.report.last_sequence, 2);
+    assert_eq!(records(&full.world, &full.index), records(&world, &index));
+    let mut persistence = MemoryPersistence::open(dir.path(), &config).unwrap();
+    persistence.restored(full.report.last_sequence);
+    assert_eq!(persistence.read_only(), None);
+    let appended = persistence.append_primary_memory(replay::entry(&world, thread, EventType::ComponentUpdated).unwrap());
+    assert_eq!(appended.unwrap().entry.sequence, 3);
+}
+
+#[test]
+fn undecodable_entries_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, moment) = populate(&mut world, &mut index);
+    let mut log = open(dir.path());
+    append(&mut log, &world, thread, EventType::EntityCreated);
+    let mut broken = replay::entry(&world, moment, EventType::EntityCreated).unwrap();
+    broken.data = br#"{"model":"Moment","entity":{"text":"no base entity"}}"#.to_vec();
+    log.append(broken).unwrap();
+    append(&mut log, &world, moment, EventType::EntityCreated);
+
+    let replayed = replay::replay_dir(dir.path(), ReplayLimit::none(), &Migrations::default()).unwrap();
+    assert_eq!(replayed.report.applied, 2);
+    assert_eq!(replayed.report.skipped.len(), 1);
+    assert_eq!(replayed.report.skipped[0].0, 2);
+    assert_eq!(records(&replayed.world, &replayed.index), records(&world, &index));
+}
+
+#[test]
+fn entries_carry_the_entity_version_and_its_parent() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let (thread, _moment) = populate(&mut world, &mut index);
+    let mut log = open(dir.path());
+    let created = append(&mut log, &world, thread, EventType::EntityCreated);
+    assert_eq!((created.version, created.parent_version), (1, None));
+
+    with_base_entity_mut(&mut world, thread, |base| base.record_update());
+    let updated = append(&mut log, &world, thread, EventType::EntityTagAdded);
+    assert_eq!((updated.version, updated.parent_version), (2, Some(1)));
+
+    // The lineage is part of what the checksum seals
+    let relinked = PrimaryMemoryEntry { parent_version: Some(0), ..updated.clone() };
+    assert_ne!(relinked.compute_checksum(), updated.checksum);
+}
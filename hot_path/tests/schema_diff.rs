@@ -0,0 +1,139 @@
+// Schema diff classification: optional fields and new models are additive, new enum values widen,
+// removals, renames, type changes and new required fields break - and `--schema-diff` exits 1
+// only when something breaks.
+
+use familiar_hot_path::schema_diff::{diff_schemas, ChangeKind, SchemaChange};
+use serde_json::{json, Value};
+use std::process::Command;
+
+fn export(models: Value) -> Value {
+    json!({ "schema_version": "1.0.0", "generated_by": "familiar-cold-path", "models": models })
+}
+
+fn moment(properties: Value, required: &[&str]) -> Value {
+    export(json!({
+        "Moment": { "type": "object", "properties": properties, "required": required },
+        "Visibility": { "type": "string", "enum": ["private", "org"] },
+    }))
+}
+
+fn base_properties() -> Value {
+    json!({
+        "thread_id": { "type": "string", "format": "uuid" },
+        "text": { "type": "string" },
+    })
+}
+
+fn changes(old: &Value, new: &Value) -> Vec<(ChangeKind, String)> {
+    diff_schemas(old, new)
+        .unwrap()
+        .changes
+        .iter()
+        .map(|change: &SchemaChange| (change.kind, change.to_string()))
+        .collect()
+}
+
+#[test]
+fn optional_fields_and_new_models_are_additive() {
+    let old = moment(base_properties(), &["thread_id"]);
+    let mut new = moment(json!({
+        "thread_id": { "type": "string", "format": "uuid" },
+        "text": { "type": "string" },
+        "mood": { "anyOf": [{ "type": "string" }, { "type": "null" }], "default": null },
+    }), &["thread_id"]);
+    new["models"]["Echo"] = json!({ "type": "object", "properties": {} });
+
+    let diff = diff_schemas(&old, &new).unwrap();
+    assert!(!diff.has_breaking_changes());
+    assert_eq!(changes(&old, &new), vec![
+        (ChangeKind::Additive, "Echo: model added".to_string()),
+        (ChangeKind::Additive, "Moment.mood: new optional field (option<string>)".to_string()),
+    ]);
+}
+
+#[test]
+fn removals_type_changes_and_required_fields_break() {
+    let old = moment(base_properties(), &["thread_id"]);
+    let new = moment(json!({
+        "thread_id": { "type": "string" },
+        "author_id": { "type": "string", "format": "uuid" },
+    }), &["thread_id", "author_id"]);
+
+    let diff = diff_schemas(&old, &new).unwrap();
+    assert_eq!(diff.count(ChangeKind::Breaking), 3, "{:?}", diff.changes);
+    let details = changes(&old, &new);
+    assert!(details.contains(&(ChangeKind::Breaking, "Moment.text: field removed (string)".to_string())));
+    assert!(details.contains(&(ChangeKind::Breaking, "Moment.author_id: new required field (uuid)".to_string())));
+    assert!(details.contains(&(ChangeKind::Breaking, "Moment.thread_id: type changed from uuid to string".to_string())));
+
+    let mut without_model = old.clone();
+    without_model["models"].as_object_mut().unwrap().remove("Visibility");
+    assert_eq!(changes(&old, &without_model), vec![(ChangeKind::Breaking, "Visibility: model removed".to_string())]);
+}
+
+#[test]
+fn a_rename_claims_its_target_once() {
+    let old = moment(json!({
+        "thread_id": { "type": "string", "format": "uuid" },
+        "text": { "type": "string" },
+        "note": { "type": "string" },
+    }), &["thread_id"]);
+    let new = moment(json!({
+        "thread_id": { "type": "string", "format": "uuid" },
+        "body": { "type": "string" },
+    }), &["thread_id"]);
+
+    // `body` absorbs one removed field; the other is plainly removed, and `body` isn't also "added"
+    assert_eq!(changes(&old, &new), vec![
+        (ChangeKind::Breaking, "Moment.note: field renamed to `body` (string)".to_string()),
+        (ChangeKind::Breaking, "Moment.text: field removed (string)".to_string()),
+    ]);
+}
+
+#[test]
+fn new_enum_values_widen_and_removed_values_break() {
+    let old = moment(json!({ "visibility": { "type": "string", "enum": ["private", "org"] } }), &[]);
+    let mut new = moment(json!({ "visibility": { "type": "string", "enum": ["private", "org", "public"] } }), &[]);
+    new["models"]["Visibility"]["enum"] = json!(["private", "org", "household"]);
+
+    let diff = diff_schemas(&old, &new).unwrap();
+    assert!(!diff.has_breaking_changes());
+    assert_eq!(changes(&old, &new), vec![
+        (ChangeKind::EnumWidening, "Moment.visibility: enum values added: [\"public\"]".to_string()),
+        (ChangeKind::EnumWidening, "Visibility: enum values added: [\"household\"]".to_string()),
+    ]);
+
+    new["models"]["Visibility"]["enum"] = json!(["private"]);
+    assert!(diff_schemas(&old, &new).unwrap().has_breaking_changes());
+}
+
+#[test]
+fn schema_diff_mode_exits_one_only_on_breaking_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, schema: &Value| {
+        let path = dir.path().join(name);
+        std::fs::write(&path, serde_json::to_vec(schema).unwrap()).unwrap();
+        path
+    };
+    let old = write("old.json", &moment(base_properties(), &["thread_id"]));
+    let additive = write("additive.json", &moment(json!({
+        "thread_id": { "type": "string", "format": "uuid" },
+        "text": { "type": "string" },
+        "mood": { "type": "string" },
+    }), &["thread_id"]));
+    let breaking = write("breaking.json", &moment(json!({ "thread_id": { "type": "string", "format": "uuid" } }), &["thread_id"]));
+
+    let exit_code = |new: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_familiar_hot_path"))
+            .arg("--schema-diff")
+            .arg(&old)
+            .arg(new)
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+    assert_eq!(exit_code(&additive), Some(0));
+    assert_eq!(exit_code(&breaking), Some(1));
+    assert_eq!(exit_code(&dir.path().join("missing.json")), Some(2));
+}
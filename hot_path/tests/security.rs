@@ -0,0 +1,259 @@
+// Security enforcement law: who may view, edit and administer which entities.
+// The table walks org × visibility × relationship × scope × security level; the sweeps below it
+// check the rules that must hold for every combination.
+
+use familiar_hot_path::components::{AccessScope, BaseEntity, DisplayText, EntityType, Motif, Visibility};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::security::{AccessDenied, ApiKeys, Principal, Role, ScopedSnapshot};
+use familiar_hot_path::snapshot::WorldSnapshot;
+use hecs::World;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const ORG: Uuid = Uuid::from_u128(0x100);
+const OTHER_ORG: Uuid = Uuid::from_u128(0x200);
+const OWNER: Uuid = Uuid::from_u128(1);
+const HOUSEMATE: Uuid = Uuid::from_u128(2);
+const COLLEAGUE: Uuid = Uuid::from_u128(3);
+const ADMIN: Uuid = Uuid::from_u128(4);
+const STRANGER: Uuid = Uuid::from_u128(5);
+
+const VISIBILITIES: [Visibility; 4] = [Visibility::Private, Visibility::Household, Visibility::Org, Visibility::Public];
+const SCOPES: [AccessScope; 3] = [AccessScope::View, AccessScope::Edit, AccessScope::Admin];
+
+/// How the principal relates to the entity's owner
+#[derive(Debug, Clone, Copy)]
+enum Relation {
+    Owner,
+    Household,
+    OrgMember,
+    OrgAdmin,
+    OrgGuest,
+    OtherOrg,
+}
+
+const RELATIONS: [Relation; 6] = [
+    Relation::Owner,
+    Relation::Household,
+    Relation::OrgMember,
+    Relation::OrgAdmin,
+    Relation::OrgGuest,
+    Relation::OtherOrg,
+];
+
+/// Who asks, the entity's visibility, security level and grants, the scope asked for, and the verdict
+type Case = (Relation, Visibility, i64, &'static [AccessScope], AccessScope, Result<(), AccessDenied>);
+
+fn principal(relation: Relation) -> Principal {
+    let (user_id, org_id, household, role) = match relation {
+        Relation::Owner => (OWNER, ORG, vec![HOUSEMATE], Role::Member),
+        Relation::Household => (HOUSEMATE, ORG, vec![OWNER], Role::Member),
+        Relation::OrgMember => (COLLEAGUE, ORG, vec![], Role::Member),
+        Relation::OrgAdmin => (ADMIN, ORG, vec![], Role::Admin),
+        Relation::OrgGuest => (COLLEAGUE, ORG, vec![OWNER], Role::Guest),
+        Relation::OtherOrg => (STRANGER, OTHER_ORG, vec![OWNER], Role::Admin),
+    };
+    Principal { user_id, org_id, household, role, scope: AccessScope::Admin }
+}
+
+fn entity(visibility: Visibility, security_level: i64, access_scope: &[AccessScope]) -> BaseEntity {
+    BaseEntity {
+        id: Some(Uuid::new_v4()),
+        org_id: ORG,
+        owner_id: OWNER,
+        created_at: None,
+        updated_at: None,
+        deleted_at: None,
+        tags: vec![],
+        component_ids: vec![],
+        sub_type: None,
+        visibility,
+        security_level,
+        access_scope: access_scope.to_vec(),
+        version: 1,
+        parent_version: None,
+    }
+}
+
+#[test]
+fn access_table() {
+    use AccessScope::{Admin, Edit, View};
+    use Relation::*;
+    use Visibility::{Household as Home, Org, Private, Public};
+
+    let cases: &[Case] = &[
+        // Owners have full control of their own entities, whatever the visibility
+        (Owner, Private, 0, &[], Admin, Ok(())),
+        (Owner, Private, 1, &[], Edit, Ok(())),
+        (Owner, Private, 2, &[], View, Err(AccessDenied::SecurityLevel { level: 2, clearance: 1 })),
+        // Household entities are shared with the owner's household only
+        (Household, Home, 0, &[View], View, Ok(())),
+        (Household, Home, 0, &[View], Edit, Err(AccessDenied::Scope(Edit))),
+        (Household, Home, 0, &[Edit], Edit, Ok(())),
+        (Household, Private, 0, &[Admin], View, Err(AccessDenied::NotVisible(Private))),
+        (OrgMember, Home, 0, &[Admin], View, Err(AccessDenied::NotVisible(Home))),
+        // Org entities are visible to the whole org; changing them needs a grant
+        (OrgMember, Org, 0, &[View], View, Ok(())),
+        (OrgMember, Org, 0, &[View], Edit, Err(AccessDenied::Scope(Edit))),
+        (OrgMember, Org, 0, &[Admin], Edit, Ok(())),
+        (OrgMember, Org, 0, &[Edit], Admin, Err(AccessDenied::Scope(Admin))),
+        (OrgMember, Org, 1, &[View], View, Ok(())),
+        (OrgMember, Org, 2, &[View], View, Err(AccessDenied::SecurityLevel { level: 2, clearance: 1 })),
+        // Admins control everything in their org up to their clearance
+        (OrgAdmin, Private, 2, &[], Admin, Ok(())),
+        (OrgAdmin, Private, 3, &[], View, Err(AccessDenied::SecurityLevel { level: 3, clearance: 2 })),
+        // Guests only ever view, and only unclassified entities
+        (OrgGuest, Org, 0, &[Admin], View, Ok(())),
+        (OrgGuest, Org, 0, &[Admin], Edit, Err(AccessDenied::Scope(Edit))),
+        (OrgGuest, Home, 0, &[View], View, Ok(())),
+        (OrgGuest, Org, 1, &[View], View, Err(AccessDenied::SecurityLevel { level: 1, clearance: 0 })),
+        // Other orgs see public entities and nothing else, whatever their role or grants
+        (OtherOrg, Public, 0, &[Admin], View, Ok(())),
+        (OtherOrg, Public, 0, &[Admin], Edit, Err(AccessDenied::OtherOrganization)),
+        (OtherOrg, Org, 0, &[Admin], View, Err(AccessDenied::OtherOrganization)),
+        (OtherOrg, Home, 0, &[Admin], View, Err(AccessDenied::OtherOrganization)),
+        (OtherOrg, Public, 2, &[View], View, Ok(())),
+        (OtherOrg, Public, 3, &[View], View, Err(AccessDenied::SecurityLevel { level: 3, clearance: 2 })),
+    ];
+
+    for (relation, visibility, level, grants, scope, expected) in cases {
+        let base = entity(*visibility, *level, grants);
+        assert_eq!(
+            &principal(*relation).check(&base, *scope),
+            expected,
+            "{relation:?} asking for {scope:?} on a {visibility:?} level-{level} entity granting {grants:?}"
+        );
+    }
+}
+
+#[test]
+fn rules_hold_for_every_combination() {
+    let grant_sets: [&[AccessScope]; 4] = [&[], &[AccessScope::View], &[AccessScope::Edit], &[AccessScope::Admin]];
+    for relation in RELATIONS {
+        let principal = principal(relation);
+        for visibility in VISIBILITIES {
+            for level in 0..=3 {
+                for grants in grant_sets {
+                    let base = entity(visibility, level, grants);
+                    for scope in SCOPES {
+                        let allowed = principal.check(&base, scope).is_ok();
+                        let context = format!("{relation:?} {visibility:?} level {level} {grants:?} {scope:?}");
+
+                        if level > principal.role.clearance() {
+                            assert!(!allowed, "above clearance: {context}");
+                        }
+                        if matches!(relation, Relation::OtherOrg) {
+                            assert_eq!(
+                                allowed,
+                                scope == AccessScope::View && visibility == Visibility::Public && level <= 2,
+                                "org boundary: {context}"
+                            );
+                        }
+                        if principal.role == Role::Guest && scope != AccessScope::View {
+                            assert!(!allowed, "guests are read-only: {context}");
+                        }
+                        if visibility == Visibility::Private && matches!(relation, Relation::Household | Relation::OrgMember) {
+                            assert!(!allowed, "private: {context}");
+                        }
+                        // A stronger scope is never allowed where a weaker one is denied
+                        if allowed {
+                            assert!(principal.check(&base, AccessScope::View).is_ok(), "scope order: {context}");
+                        }
+                        // Viewing is all visibility gives; non-owners need a grant for anything more
+                        if allowed && scope != AccessScope::View && matches!(relation, Relation::Household | Relation::OrgMember) {
+                            assert!(!grants.is_empty() && !grants.contains(&AccessScope::View), "grant: {context}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn only_guests_cannot_create() {
+    assert!(principal(Relation::OrgMember).can_create());
+    assert!(principal(Relation::OrgAdmin).can_create());
+    assert!(!principal(Relation::OrgGuest).can_create());
+}
+
+#[test]
+fn key_scope_caps_the_role() {
+    let base = entity(Visibility::Private, 0, &[]);
+    let owner = |scope| Principal { scope, ..principal(Relation::Owner) };
+
+    assert_eq!(owner(AccessScope::Edit).check(&base, AccessScope::Edit), Ok(()));
+    assert_eq!(owner(AccessScope::Edit).check(&base, AccessScope::Admin), Err(AccessDenied::Scope(AccessScope::Admin)));
+    assert_eq!(owner(AccessScope::View).check(&base, AccessScope::View), Ok(()));
+    assert!(!owner(AccessScope::View).can_create());
+    // A broad key never lifts a narrow role
+    let guest = Principal { scope: AccessScope::Admin, ..principal(Relation::OrgGuest) };
+    assert_eq!(guest.max_scope(), AccessScope::View);
+}
+
+#[test]
+fn api_keys_map_to_principals() {
+    let keys = ApiKeys::new(
+        [
+            ("owner-key".to_string(), principal(Relation::Owner)),
+            ("guest-key".to_string(), principal(Relation::OrgGuest)),
+            (String::new(), principal(Relation::OrgAdmin)),
+        ],
+        principal(Relation::OrgAdmin),
+    );
+    assert!(keys.required());
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys.authenticate(Some("owner-key")), Some(principal(Relation::Owner)));
+    assert_eq!(keys.authenticate(Some("guest-key")), Some(principal(Relation::OrgGuest)));
+    assert_eq!(keys.authenticate(Some("")), None);
+    assert_eq!(keys.authenticate(Some("wrong")), None);
+    assert_eq!(keys.authenticate(None), None);
+
+    // Without any key configured, every request acts for the anonymous principal
+    let open = ApiKeys::new([(String::new(), principal(Relation::Owner))], principal(Relation::OrgMember));
+    assert!(!open.required());
+    assert_eq!(open.authenticate(None), Some(principal(Relation::OrgMember)));
+    assert_eq!(open.authenticate(Some("anything")), Some(principal(Relation::OrgMember)));
+}
+
+#[test]
+fn scoped_snapshot_hides_what_the_principal_cannot_view() {
+    let mut world = World::new();
+    let mut index = EntityIndex::new();
+    let mut spawn = |name: &str, base: BaseEntity| {
+        let id = base.id.unwrap();
+        index.spawn(&mut world, (Motif { base }, DisplayText(name.to_string()), EntityType("motif".to_string())));
+        id
+    };
+
+    let private = spawn("private", entity(Visibility::Private, 0, &[]));
+    let household = spawn("household", entity(Visibility::Household, 0, &[]));
+    let org = spawn("org", entity(Visibility::Org, 0, &[]));
+    let public = spawn("public", entity(Visibility::Public, 0, &[]));
+    let classified = spawn("classified", entity(Visibility::Org, 2, &[]));
+
+    let snapshot = Arc::new(WorldSnapshot::capture(&world, &index, 1, 0.0));
+    let visible = |relation| {
+        let scoped = ScopedSnapshot::new(snapshot.clone(), Arc::new(principal(relation)));
+        let mut ids: Vec<Uuid> = scoped.entities().filter_map(|entity| entity.id).collect();
+        ids.sort();
+        assert_eq!(scoped.len(), ids.len());
+        assert_eq!(scoped.of_type("motif").count(), ids.len());
+        ids
+    };
+    let sorted = |mut ids: Vec<Uuid>| {
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(visible(Relation::Owner), sorted(vec![private, household, org, public]));
+    assert_eq!(visible(Relation::Household), sorted(vec![household, org, public]));
+    assert_eq!(visible(Relation::OrgMember), sorted(vec![org, public]));
+    assert_eq!(visible(Relation::OrgAdmin), sorted(vec![private, household, org, public, classified]));
+    assert_eq!(visible(Relation::OtherOrg), vec![public]);
+
+    let stranger = ScopedSnapshot::new(snapshot.clone(), Arc::new(principal(Relation::OtherOrg)));
+    assert!(stranger.get(&org).is_none());
+    assert!(stranger.get(&public).is_some());
+    assert_eq!(snapshot.len(), 5);
+}
@@ -0,0 +1,47 @@
+// Field laws on chosen entities, under the shipped decay law: only entities the formula could be
+// evaluated for count as applied, and `last_update` only moves along with a recorded change.
+
+use familiar_hot_path::changes::ChangeSet;
+use familiar_hot_path::components::{DecayComponent, EntityType};
+use familiar_hot_path::index::EntityIndex;
+use familiar_hot_path::laws::{load_laws, CompiledLaw};
+use familiar_hot_path::systems::field_law_on_entities;
+use hecs::{Entity, World};
+
+fn decay_law() -> CompiledLaw {
+    load_laws(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/laws.json"))
+        .unwrap()
+        .into_iter()
+        .find(|law| law.name() == "decay")
+        .unwrap()
+}
+
+fn moment(world: &mut World, index: &mut EntityIndex, strength: f32, half_life: f32) -> Entity {
+    index.spawn(world, (EntityType("moment".to_string()), DecayComponent { strength, half_life, last_update: 0.0 }))
+}
+
+fn decay(world: &World, entity: Entity) -> DecayComponent {
+    (*world.get::<&DecayComponent>(entity).unwrap()).clone()
+}
+
+#[test]
+fn only_evaluated_entities_count_and_unchanged_ones_keep_their_clock() {
+    let (mut world, mut index) = (World::new(), EntityIndex::new());
+    let fading = moment(&mut world, &mut index, 1.0, 60.0);
+    // `time_elapsed / half_life` divides by zero
+    let broken = moment(&mut world, &mut index, 1.0, 0.0);
+    // Already held at the law's `min: 0.1`
+    let clamped = moment(&mut world, &mut index, 0.1, 60.0);
+
+    let mut changes = ChangeSet::new(1);
+    let applied = field_law_on_entities(&mut world, &index, &decay_law(), &[fading, broken, clamped], 60.0, &mut changes);
+    assert_eq!(applied, 2);
+    assert_eq!(changes.len(), 1);
+
+    let fading = decay(&world, fading);
+    assert_eq!((fading.strength, fading.last_update), (0.5, 60.0));
+    let broken = decay(&world, broken);
+    assert_eq!((broken.strength, broken.last_update), (1.0, 0.0));
+    let clamped = decay(&world, clamped);
+    assert_eq!((clamped.strength, clamped.last_update), (0.1, 0.0));
+}